@@ -1,6 +1,7 @@
 use std::{
-    env::{temp_dir, var},
-    fs::copy,
+    collections::HashMap,
+    env::{temp_dir, var, var_os},
+    fs::{copy, read_to_string, write},
     path::{Path, PathBuf},
     process::Command,
 };
@@ -9,12 +10,10 @@ fn find_target_dir(out_dir: &Path) -> Option<PathBuf> {
     for ancestor in out_dir.ancestors() {
         let read_dir = ancestor
             .read_dir()
-            .expect(&format!("can not read dir for {}", ancestor.display()));
-        for entry in read_dir {
-            if let Ok(f) = entry {
-                if f.file_name() == ".cargo-lock" {
-                    return Some(ancestor.to_path_buf());
-                }
+            .unwrap_or_else(|_| panic!("can not read dir for {}", ancestor.display()));
+        for f in read_dir.flatten() {
+            if f.file_name() == ".cargo-lock" {
+                return Some(ancestor.to_path_buf());
             }
         }
     }
@@ -23,11 +22,103 @@ fn find_target_dir(out_dir: &Path) -> Option<PathBuf> {
 }
 
 const INTER_MEM_NAME: &str = "libinter_mem.so";
+/// bundled table of the target architecture, see `SYSCALLS` of `lib.rs`
+fn bundled_table(arch: &str) -> &'static str {
+    match arch {
+        "aarch64" => "src/data/syscalls_aarch64.tsv",
+        "x86" => "src/data/syscalls_x86.tsv",
+        _ => "src/data/syscalls_x64.tsv",
+    }
+}
+
+/// where `<sys/syscall.h>` gets syscall numbers from, by distro, only parsed for x86_64
+const UNISTD_HEADERS: [&str; 2] = [
+    "/usr/include/x86_64-linux-gnu/asm/unistd_64.h",
+    "/usr/include/asm/unistd_64.h",
+];
+
+/// `(number, name)` of `#define __NR_<name> <number>` lines in `header`
+fn parse_unistd(header: &str) -> Vec<(u64, String)> {
+    header
+        .lines()
+        .filter_map(|line| {
+            let mut words = line.split_whitespace();
+            if words.next() != Some("#define") {
+                return None;
+            }
+            let name = words.next()?.strip_prefix("__NR_")?;
+            let no = words.next()?.parse().ok()?;
+            Some((no, name.to_owned()))
+        })
+        .collect()
+}
+
+/// write the syscall table used by the `host-syscalls` feature into `OUT_DIR`, numbers
+/// are taken from kernel headers of the build host, and kinds of arguments from the
+/// bundled table. The bundled one is used as is if no header is found.
+fn generate_syscall_table(out_dir: &Path) {
+    let arch = var("CARGO_CFG_TARGET_ARCH").expect("env CARGO_CFG_TARGET_ARCH not found");
+    let table = bundled_table(&arch);
+    println!("cargo:rerun-if-changed={}", table);
+    let bundled = read_to_string(table).expect("read bundled syscall table error");
+    let dst = out_dir.join("syscalls.tsv");
+    let header = (arch == "x86_64")
+        .then(|| {
+            UNISTD_HEADERS.iter().find_map(|h| {
+                println!("cargo:rerun-if-changed={}", h);
+                read_to_string(h).ok()
+            })
+        })
+        .flatten();
+    let Some(header) = header else {
+        println!(
+            "cargo:warning=kernel headers of {} not found, the bundled syscall table is used",
+            arch
+        );
+        write(dst, bundled).expect("write syscall table error");
+        return;
+    };
+
+    let mut kinds = HashMap::new();
+    let mut x32 = Vec::new();
+    for line in bundled.lines().filter(|l| !l.starts_with('#')) {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        match fields[..] {
+            // x32 ABI, not in the 64-bit header
+            [no, ..] if no.parse::<u64>().is_ok_and(|no| no >= 512) => x32.push(line),
+            [_, name, kind, ..] => {
+                kinds.insert(name, kind);
+            }
+            _ => {}
+        }
+    }
+
+    let mut table = String::from("# generated from kernel headers by build.rs\n");
+    for (no, name) in parse_unistd(&header) {
+        match kinds.get(name.as_str()) {
+            Some(kind) => table.push_str(&format!("{}\t{}\t{}\n", no, name, kind)),
+            None => table.push_str(&format!("{}\t{}\n", no, name)),
+        }
+    }
+    for line in x32 {
+        table.push_str(line);
+        table.push('\n');
+    }
+    write(dst, table).expect("write syscall table error");
+}
 
 fn main() {
     println!("cargo:rerun-if-changed=mem");
+    if var_os("CARGO_FEATURE_HOST_SYSCALLS").is_some() {
+        let out_dir = var("OUT_DIR").expect("env OUT_DIR not found");
+        generate_syscall_table(Path::new(&out_dir));
+    }
 
     if !Path::new("mem").exists() {
+        println!(
+            "cargo:warning={} is not built without `mem`, remote memory is unavailable",
+            INTER_MEM_NAME
+        );
         return;
     }
 
@@ -67,5 +158,5 @@ fn main() {
         result_dir.join(INTER_MEM_NAME),
         target_dir.join(INTER_MEM_NAME),
     )
-    .expect(&format!("copy result {} error", INTER_MEM_NAME));
+    .unwrap_or_else(|_| panic!("copy result {} error", INTER_MEM_NAME));
 }
@@ -0,0 +1,46 @@
+//! Supervise a child from async code, run with `--features tokio`.
+//!
+//! The ptrace loop runs on its own thread, syscalls come in as a stream and the
+//! supervisor replies with a decision, here denying to delete files.
+use interceptor_rs::{Decision, EventStream, Phase};
+use std::{
+    env::{current_exe, temp_dir, var_os},
+    ffi::OsStr,
+    fs::{remove_file, write},
+    process::{self, Command},
+};
+
+const CHILD: &str = "ASYNC_SUPERVISOR_CHILD";
+
+fn child(path: &OsStr) {
+    write(path, "keep me").unwrap();
+    match remove_file(path) {
+        Ok(()) => println!("removed {:?}", path),
+        Err(e) => println!("remove {:?}: {}", path, e),
+    }
+}
+
+#[tokio::main(flavor = "current_thread")]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if let Some(path) = var_os(CHILD) {
+        child(&path);
+        return Ok(());
+    }
+
+    let path = temp_dir().join(format!("async-supervisor-{}", process::id()));
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, &path);
+    let mut events = EventStream::spawn(cmd, "unlink*", |interceptor| {
+        interceptor.without_remote_mem();
+    });
+    while let Some(pending) = events.next().await {
+        let e = &pending.event;
+        if e.phase == Phase::Enter {
+            println!("[supervisor] pid {} {}: denied", e.pid, e.name);
+            pending.reply(Decision::Block(-libc::EPERM as u64));
+        }
+    }
+    events.wait().await?;
+    remove_file(&path)?;
+    Ok(())
+}
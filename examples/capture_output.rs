@@ -0,0 +1,41 @@
+//! Run a command, log files it opens and capture what it prints, e.g.
+//! `cargo run --example capture_output -- cat /etc/hostname`.
+//!
+//! The interceptor shares stdio with the child by default, so their output is mixed.
+//! `capture_output` pipes stdout and stderr of the child, which are read in background
+//! while syscalls are intercepted, and are all available once `run` returned.
+use interceptor_rs::{syscall, Interceptor};
+use std::{
+    env::args,
+    ffi::{c_char, CStr},
+    process::Command,
+};
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = unsafe { CStr::from_ptr(filename).to_string_lossy() };
+    println!("[interceptor] openat {}", file);
+    real!(dfd, filename, flags, mode)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let Some((program, rest)) = args.split_first() else {
+        eprintln!("usage: capture_output <program> [args...]");
+        return Ok(());
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(rest);
+    let mut interceptor = Interceptor::new(cmd)?;
+    let output = interceptor.capture_output();
+    interceptor.without_remote_mem().on(&openat).run()?;
+
+    for line in String::from_utf8_lossy(&output.stdout()).lines() {
+        println!("[stdout] {}", line);
+    }
+    for line in String::from_utf8_lossy(&output.stderr()).lines() {
+        println!("[stderr] {}", line);
+    }
+    Ok(())
+}
@@ -40,6 +40,11 @@ fn openat(dfd: i32, mut filename: *const c_char, flags: i32, mode: i32) -> i32 {
             // pointed by the return pointer is not released.
             forget(a);
         }
+
+        // Or let `set_arg_string` pick one of the above by the length, it returns which
+        // one is taken:
+        // let name = CString::new("1.cpp").unwrap();
+        // with_context(|ctx| ctx.set_arg_string(1, &name)).unwrap();
     }
 
     // call the `real!()` macro to pass the modified arguments to the kernel, and of course
@@ -0,0 +1,31 @@
+use interceptor_rs::{alloc_ptr_to_ptr, read_ptr_to_ptr, syscall, Interceptor};
+use std::{ffi::c_char, process::Command};
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", "exec /usr/bin/env"]);
+    Interceptor::new(cmd)?.on(&execve).run()?;
+    Ok(())
+}
+
+#[syscall]
+fn execve(
+    filename: *const c_char,
+    argv: *const *const c_char,
+    mut envp: *const *const c_char,
+) -> i32 {
+    // Drop `LD_PRELOAD` so the new image doesn't load `libinter_mem.so` again, and
+    // inject a new variable. The number of entries changed, so a new array is built
+    // by `alloc_ptr_to_ptr`, the old content can not be reused in place.
+    let mut env = read_ptr_to_ptr(envp)
+        .into_iter()
+        .filter(|e| !e.starts_with(b"LD_PRELOAD="))
+        .collect::<Vec<_>>();
+    env.push(b"INTERCEPTED=1\0".to_vec());
+    match alloc_ptr_to_ptr(env) {
+        Ok(new) => envp = new,
+        Err(e) => eprintln!("rebuild envp error: {}", e),
+    }
+
+    real!(filename, argv, envp)
+}
@@ -0,0 +1,62 @@
+//! Report another path as the executable of a command, e.g.
+//! `cargo run --example fake_exe -- /usr/bin/fake readlink /proc/self/exe`.
+//!
+//! The link is read by `readlink` or `readlinkat`, the target kernel placed into the buffer
+//! is replaced after the syscall returned, and its new length is returned instead.
+use interceptor_rs::{syscall, with_context, Interceptor};
+use once_cell::sync::OnceCell;
+use std::{env::args, ffi::c_char, process::Command};
+
+static FAKE: OnceCell<String> = OnceCell::new();
+
+const EXE: &[u8] = b"/proc/self/exe\0";
+
+/// `ret` with the fake path placed instead if the path argument at `index` is the
+/// executable, code after `real!()` can not see the arguments so it's read again
+fn fake_target(index: usize, ret: i64) -> i64 {
+    if ret < 0 {
+        return ret;
+    }
+
+    with_context(|ctx| {
+        let path = ctx.arg(index);
+        if path == 0 || ctx.read_memory(path, EXE.len()) != EXE {
+            return ret;
+        }
+        let fake = FAKE.get().map(String::as_bytes).unwrap_or_default();
+        ctx.set_link_target(fake).unwrap_or_else(|e| {
+            eprintln!("fake exe error: {}", e);
+            ret
+        })
+    })
+}
+
+#[syscall]
+fn readlink(path: *const c_char, buf: u64, bufsiz: usize) -> i64 {
+    let ret = real!(path, buf, bufsiz);
+    fake_target(0, ret)
+}
+
+#[syscall]
+fn readlinkat(dirfd: i32, path: *const c_char, buf: u64, bufsiz: usize) -> i64 {
+    let ret = real!(dirfd, path, buf, bufsiz);
+    fake_target(1, ret)
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let [fake, program, rest @ ..] = args.as_slice() else {
+        eprintln!("usage: fake_exe <path> <program> [args...]");
+        return Ok(());
+    };
+
+    FAKE.set(fake.clone()).unwrap();
+    let mut cmd = Command::new(program);
+    cmd.args(rest);
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on(&readlink)
+        .on(&readlinkat)
+        .run()?;
+    Ok(())
+}
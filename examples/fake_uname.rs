@@ -0,0 +1,40 @@
+use interceptor_rs::{with_context, Interceptor, Outcome};
+use std::{
+    env::{self, current_exe, var_os},
+    mem::size_of,
+    process::Command,
+};
+
+const CHILD: &str = "FAKE_UNAME_CHILD";
+
+/// `struct utsname` with `sysname` and `release` set, each field is 65 bytes
+fn fake_utsname(sysname: &str, release: &str) -> Vec<u8> {
+    let mut uts = vec![0; size_of::<libc::utsname>()];
+    uts[..sysname.len()].copy_from_slice(sysname.as_bytes());
+    uts[65 * 2..65 * 2 + release.len()].copy_from_slice(release.as_bytes());
+    uts
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        let status = Command::new("uname").arg("-sr").status()?;
+        println!("uname exited with {}", status);
+        return Ok(());
+    }
+
+    let release = env::args().nth(1).unwrap_or_else(|| "2.6.32".to_owned());
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        // the real `uname` is not called, the caller sees the buffer filled
+        .on_fn("uname", move |buf: u64| {
+            let uts = fake_utsname("Linux", &release);
+            if let Err(e) = with_context(|ctx| ctx.write_on_block(buf, &uts)) {
+                eprintln!("fake uname error: {}", e);
+            }
+            Outcome::<i32, _>::Block(0)
+        })
+        .run()?;
+    Ok(())
+}
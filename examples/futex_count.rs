@@ -0,0 +1,101 @@
+//! Count `futex` operations of a multithreaded child without changing them.
+//!
+//! `futex` is what locks and condition variables sleep and wake on, it's made very often
+//! by multithreaded programs. An observe-only handler only slows it down, waiters are
+//! woken as usual, so the child can not deadlock because of it. Changing its arguments
+//! or blocking it is risky: a lost `FUTEX_WAKE` leaves waiters sleeping forever, and
+//! a `FUTEX_WAIT` returning early without the value changed makes callers spin or see
+//! a lock they do not own. Run with a timeout to catch such a hang.
+use interceptor_rs::{Decision, Interceptor, Phase};
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    env::{current_exe, var_os},
+    process::Command,
+    rc::Rc,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+const CHILD: &str = "FUTEX_COUNT_CHILD";
+
+/// `FUTEX_PRIVATE_FLAG` and `FUTEX_CLOCK_REALTIME` are not part of the operation
+const FUTEX_CMD_MASK: u64 = !(128 | 256);
+
+fn op_name(op: u64) -> &'static str {
+    const NAMES: [&str; 14] = [
+        "FUTEX_WAIT",
+        "FUTEX_WAKE",
+        "FUTEX_FD",
+        "FUTEX_REQUEUE",
+        "FUTEX_CMP_REQUEUE",
+        "FUTEX_WAKE_OP",
+        "FUTEX_LOCK_PI",
+        "FUTEX_UNLOCK_PI",
+        "FUTEX_TRYLOCK_PI",
+        "FUTEX_WAIT_BITSET",
+        "FUTEX_WAKE_BITSET",
+        "FUTEX_WAIT_REQUEUE_PI",
+        "FUTEX_CMP_REQUEUE_PI",
+        "FUTEX_LOCK_PI2",
+    ];
+    NAMES.get(op as usize).copied().unwrap_or("unknown")
+}
+
+/// threads contending on a lock and passing a token through a condition variable
+fn child() {
+    let counter = Arc::new(Mutex::new(0u64));
+    let token = Arc::new((Mutex::new(0usize), Condvar::new()));
+    let threads = 8;
+    let handles = (0..threads)
+        .map(|i| {
+            let counter = counter.clone();
+            let token = token.clone();
+            thread::spawn(move || {
+                for round in 0..50 {
+                    let (lock, cvar) = &*token;
+                    let mut turn = cvar
+                        .wait_while(lock.lock().unwrap(), |turn| *turn != round * threads + i)
+                        .unwrap();
+                    *counter.lock().unwrap() += 1;
+                    *turn += 1;
+                    cvar.notify_all();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    println!("counter: {}", counter.lock().unwrap());
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        child();
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    let counts = Rc::new(RefCell::new(BTreeMap::new()));
+    {
+        let counts = counts.clone();
+        Interceptor::new(cmd)?
+            .without_remote_mem()
+            .on_matching("futex", move |event| {
+                if event.phase == Phase::Enter {
+                    let op = op_name(event.args[1] & FUTEX_CMD_MASK);
+                    *counts.borrow_mut().entry(op).or_insert(0) += 1;
+                }
+                Decision::Continue
+            })
+            .run_with_timeout(Duration::from_secs(30))?;
+    }
+
+    for (op, count) in counts.borrow().iter() {
+        println!("{:<24}{}", op, count);
+    }
+    Ok(())
+}
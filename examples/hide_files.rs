@@ -0,0 +1,52 @@
+//! Hide a file name from directory listings of a command, e.g.
+//! `cargo run --example hide_files -- Cargo.toml ls`.
+//!
+//! Listings are read by `getdents64`, its buffer is filtered after the syscall returned,
+//! and the shorter byte count is returned instead.
+use interceptor_rs::{syscall, with_context, DirentBuf, Interceptor};
+use once_cell::sync::OnceCell;
+use std::{env::args, process::Command};
+
+static HIDDEN: OnceCell<String> = OnceCell::new();
+
+#[syscall]
+fn getdents64(fd: i32, dirp: u64, count: u32) -> i64 {
+    let ret = real!(fd, dirp, count);
+    if ret <= 0 {
+        return ret;
+    }
+
+    with_context(|ctx| {
+        let hidden = HIDDEN.get().map(String::as_bytes).unwrap_or_default();
+        let mut buf = DirentBuf::read_arg(ctx, 1, ret as usize);
+        let len = buf.len();
+        buf.retain(|e| e.name != hidden);
+        if buf.len() == len {
+            return ret;
+        }
+        match buf.store(ctx) {
+            Ok(n) => n as i64,
+            Err(e) => {
+                eprintln!("hide {:?} error: {}", hidden, e);
+                ret
+            }
+        }
+    })
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let [hidden, program, rest @ ..] = args.as_slice() else {
+        eprintln!("usage: hide_files <name> <program> [args...]");
+        return Ok(());
+    };
+
+    HIDDEN.set(hidden.clone()).unwrap();
+    let mut cmd = Command::new(program);
+    cmd.args(rest);
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on(&getdents64)
+        .run()?;
+    Ok(())
+}
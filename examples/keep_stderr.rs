@@ -0,0 +1,38 @@
+use interceptor_rs::{Interceptor, Outcome};
+use std::process::Command;
+
+/// fd redirections of stderr are moved to, so stderr itself is kept
+const MOVED_STDERR: i32 = 100;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::new("bash");
+    cmd.args([
+        "-c",
+        "exec 2>/dev/null; echo 'stderr is kept' >&2; ls -l /proc/self/fd/",
+    ]);
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on_fn("dup2", |oldfd: i32, newfd: i32| -> Outcome<i32, _> {
+            if newfd == libc::STDERR_FILENO {
+                // the register read by kernel is changed, the caller gets `MOVED_STDERR`
+                println!(
+                    "dup2({}, {}) -> dup2({}, {})",
+                    oldfd, newfd, oldfd, MOVED_STDERR
+                );
+                Outcome::Real((oldfd, MOVED_STDERR))
+            } else {
+                Outcome::Real((oldfd, newfd))
+            }
+        })
+        .on_fn("dup3", |oldfd: i32, newfd: i32, flags: i32| {
+            if newfd == libc::STDERR_FILENO {
+                // pretend it's redirected, kernel returns `newfd` on success
+                println!("dup3({}, {}, {}) blocked", oldfd, newfd, flags);
+                Outcome::Block(newfd)
+            } else {
+                Outcome::Real((oldfd, newfd, flags))
+            }
+        })
+        .run()?;
+    Ok(())
+}
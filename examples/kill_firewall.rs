@@ -0,0 +1,42 @@
+use interceptor_rs::{Interceptor, Outcome};
+use std::process::Command;
+
+/// what to do with a signal sent by the target
+fn policy(sig: i32) -> Outcome<i32, ()> {
+    match sig {
+        // refuse it, the caller sees `EPERM`
+        libc::SIGKILL => Outcome::Block(-libc::EPERM),
+        // pretend it's sent, the caller sees success
+        libc::SIGTERM => Outcome::Block(0),
+        _ => Outcome::Real(()),
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // `sleep` survives both signals, and is interrupted by the last one
+    let mut cmd = Command::new("sh");
+    cmd.args([
+        "-c",
+        r#"sleep 3 & kill -KILL $!; echo "SIGKILL: $?"; kill -TERM $!; echo "SIGTERM: $?";
+        sleep 1; kill -USR1 $!; wait $!; echo "sleep exited: $?""#,
+    ]);
+
+    let mut interceptor = Interceptor::new(cmd)?;
+    interceptor
+        .without_remote_mem()
+        .on_fn("kill", |pid: i32, sig: i32| {
+            println!("kill(pid = {}, sig = {})", pid, sig);
+            match policy(sig) {
+                Outcome::Real(()) => Outcome::Real((pid, sig)),
+                Outcome::Block(r) => Outcome::Block(r),
+            }
+        })
+        .on_fn("tgkill", |tgid: i32, tid: i32, sig: i32| {
+            match policy(sig) {
+                Outcome::Real(()) => Outcome::Real((tgid, tid, sig)),
+                Outcome::Block(r) => Outcome::Block(r),
+            }
+        });
+    interceptor.run()?;
+    Ok(())
+}
@@ -0,0 +1,31 @@
+use interceptor_rs::{FdEvent, FdOp, FdTarget, Interceptor};
+use std::{
+    env::{current_exe, var_os},
+    ffi::CString,
+    process::Command,
+};
+
+const CHILD: &str = "MEMFD_NAMES_CHILD";
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        for name in ["jit", "a-much-longer-name-of-an-in-memory-file"] {
+            let name = CString::new(name)?;
+            unsafe { libc::memfd_create(name.as_ptr(), libc::MFD_CLOEXEC) };
+        }
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        // reported after it returned, with the name read from the tracee and the new fd
+        .on_fd_events(|e: &FdEvent| {
+            if let (FdOp::MemFd, Some(FdTarget::MemFd(name))) = (e.op, &e.path_or_addr) {
+                println!("[{}] memfd {:?} = {}", e.pid, name, e.fd);
+            }
+        })
+        .run()?;
+    Ok(())
+}
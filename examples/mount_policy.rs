@@ -0,0 +1,98 @@
+//! Deny mounting some filesystem types, e.g. in a sandbox, other mounts and `umount2` are
+//! logged and sent to kernel, where they may still fail without privileges.
+use interceptor_rs::{
+    flags::{MountFlags, UmountFlags},
+    Interceptor, Outcome,
+};
+use std::{
+    env::{current_exe, temp_dir, var_os},
+    ffi::{c_char, CStr, CString},
+    fs::{create_dir_all, remove_dir},
+    io::Error,
+    process::{self, Command},
+    ptr::null,
+};
+
+const CHILD: &str = "MOUNT_POLICY_CHILD";
+
+/// filesystem types the child can not mount
+const DENIED: [&str; 3] = ["tmpfs", "proc", "sysfs"];
+
+fn child() {
+    let dir = temp_dir().join(format!("mount-policy-{}", process::id()));
+    create_dir_all(&dir).unwrap();
+    let target = CString::new(dir.to_string_lossy().as_bytes()).unwrap();
+    for (fstype, flags) in [
+        ("tmpfs", libc::MS_NOSUID),
+        ("proc", 0),
+        ("ramfs", libc::MS_NOEXEC),
+    ] {
+        let fstype = CString::new(fstype).unwrap();
+        let ret = unsafe {
+            libc::mount(
+                fstype.as_ptr(),
+                target.as_ptr(),
+                fstype.as_ptr(),
+                flags,
+                null(),
+            )
+        };
+        if ret < 0 {
+            println!("mount {:?}: {}", fstype, Error::last_os_error());
+        } else {
+            println!("mount {:?}: ok", fstype);
+            unsafe { libc::umount2(target.as_ptr(), libc::MNT_DETACH) };
+        }
+    }
+    remove_dir(&dir).unwrap();
+}
+
+fn string(p: *const c_char) -> String {
+    if p.is_null() {
+        return String::new();
+    }
+    unsafe { CStr::from_ptr(p) }.to_string_lossy().into_owned()
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        child();
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on_fn(
+            "mount",
+            |source: *const c_char,
+             target: *const c_char,
+             fstype: *const c_char,
+             flags: MountFlags,
+             data: u64| {
+                let kind = string(fstype);
+                println!(
+                    "mount({:?}, {:?}, {:?}, {:?})",
+                    string(source),
+                    string(target),
+                    kind,
+                    flags
+                );
+                // fstype is ignored by kernel for bind, move and propagation changes
+                let new_fs = !flags.is_propagation()
+                    && !flags.intersects(MountFlags::MS_BIND | MountFlags::MS_MOVE);
+                if new_fs && DENIED.contains(&kind.as_str()) {
+                    Outcome::Block(-libc::EPERM)
+                } else {
+                    Outcome::Real((source, target, fstype, flags, data))
+                }
+            },
+        )
+        .on_fn("umount2", |target: *const c_char, flags: UmountFlags| {
+            println!("umount2({:?}, {:?})", string(target), flags);
+            Outcome::<i32, _>::Real((target, flags))
+        })
+        .run()?;
+    Ok(())
+}
@@ -0,0 +1,66 @@
+use interceptor_rs::{Interceptor, Outcome};
+use std::{
+    env::{current_exe, var_os},
+    io::Error,
+    process::Command,
+};
+
+const CHILD: &str = "NO_SECCOMP_CHILD";
+
+/// the sandboxed child, it installs a filter killing itself on `getppid`
+fn child() {
+    let stmt = |code: u32, k: u32| libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    let mut filter = [
+        // load `nr` of `struct seccomp_data`
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 0),
+        libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 1,
+            k: libc::SYS_getppid as u32,
+        },
+        stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_KILL_PROCESS),
+        stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW),
+    ];
+    let prog = libc::sock_fprog {
+        len: filter.len() as u16,
+        filter: filter.as_mut_ptr(),
+    };
+
+    unsafe {
+        libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0);
+        let ret = libc::syscall(libc::SYS_seccomp, libc::SECCOMP_SET_MODE_FILTER, 0, &prog);
+        if ret < 0 {
+            println!("seccomp: {}", Error::last_os_error());
+        }
+        // killed here if the filter was installed
+        println!("getppid: {}", libc::getppid());
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        child();
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        // the child believes it's confined, so interceptor has to be the sandbox
+        .ignore_seccomp()
+        .on_fn("bpf", |cmd: i32, _attr: u64, _size: u32| {
+            println!("bpf({}) denied", cmd);
+            Outcome::Block(-libc::EPERM)
+        })
+        // still intercepted, the filter would have killed the child instead
+        .on_fn("getppid", || Outcome::<i32, ()>::Block(1))
+        .run()?;
+    Ok(())
+}
@@ -0,0 +1,21 @@
+use interceptor_rs::{syscall, with_context, FixedArray, Interceptor};
+use std::process::Command;
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", "echo hello | cat"]);
+    Interceptor::new(cmd)?.on(&pipe2).run()?;
+    Ok(())
+}
+
+#[syscall]
+fn pipe2(fds: FixedArray<i32, 2>, flags: i32) -> i32 {
+    // `fds` is filled by kernel, so its content is meaningless before `real!()`.
+    let ret = real!(fds, flags);
+    if ret == 0 {
+        // read it again from target after syscall returned.
+        let fds = with_context(|ctx| FixedArray::<i32, 2>::read_arg(ctx, 0));
+        println!("pipe2 fds: [{}, {}]", fds[0], fds[1]);
+    }
+    ret
+}
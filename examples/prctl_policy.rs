@@ -0,0 +1,64 @@
+use interceptor_rs::{flags::PrctlOption, Interceptor, Outcome};
+use std::{
+    env::{current_exe, var_os},
+    io::Error,
+    process::Command,
+};
+
+const CHILD: &str = "PRCTL_POLICY_CHILD";
+
+/// process attributes changed by the traced child
+fn child() {
+    for (name, option, arg2) in [
+        ("no new privs", libc::PR_SET_NO_NEW_PRIVS, 1),
+        ("dumpable", libc::PR_SET_DUMPABLE, 0),
+        // would kill the child at its next syscall if not denied
+        (
+            "strict seccomp",
+            libc::PR_SET_SECCOMP,
+            libc::SECCOMP_MODE_STRICT,
+        ),
+    ] {
+        let ret = unsafe { libc::prctl(option, arg2 as libc::c_ulong, 0, 0, 0) };
+        if ret < 0 {
+            println!("{}: {}", name, Error::last_os_error());
+        } else {
+            println!("{}: ok", name);
+        }
+    }
+
+    let ret = unsafe { libc::syscall(libc::SYS_seccomp, libc::SECCOMP_SET_MODE_STRICT, 0, 0) };
+    if ret < 0 {
+        println!("seccomp: {}", Error::last_os_error());
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        child();
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on_fn(
+            "prctl",
+            |option: PrctlOption, arg2: u64, arg3: u64, arg4: u64, arg5: u64| {
+                println!("prctl({:?}, {})", option, arg2);
+                // installed seccomp filters could deny syscalls interceptor relies on
+                if option == PrctlOption::PR_SET_SECCOMP {
+                    Outcome::Block(-libc::EPERM)
+                } else {
+                    Outcome::Real((option, arg2, arg3, arg4, arg5))
+                }
+            },
+        )
+        .on_fn("seccomp", |operation: u32, flags: u32, args: u64| {
+            println!("seccomp({}, {}, 0x{:x})", operation, flags, args);
+            Outcome::<i32, _>::Block(-libc::EPERM)
+        })
+        .run()?;
+    Ok(())
+}
@@ -0,0 +1,64 @@
+use interceptor_rs::{
+    flags::{Domain, SockType},
+    Interceptor, Outcome,
+};
+use std::{
+    env::{current_exe, var_os},
+    io::Error,
+    process::Command,
+};
+
+const CHILD: &str = "SOCKET_POLICY_CHILD";
+
+/// sockets created by the traced child
+fn child() {
+    for (name, domain, ty, protocol) in [
+        ("tcp", libc::AF_INET, libc::SOCK_STREAM, 0),
+        (
+            "raw icmp",
+            libc::AF_INET,
+            libc::SOCK_RAW,
+            libc::IPPROTO_ICMP,
+        ),
+        ("packet", libc::AF_PACKET, libc::SOCK_RAW, 0),
+        (
+            "unix",
+            libc::AF_UNIX,
+            libc::SOCK_DGRAM | libc::SOCK_CLOEXEC,
+            0,
+        ),
+    ] {
+        let fd = unsafe { libc::socket(domain, ty, protocol) };
+        if fd < 0 {
+            println!("{}: {}", name, Error::last_os_error());
+        } else {
+            println!("{}: fd {}", name, fd);
+            unsafe { libc::close(fd) };
+        }
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    if var_os(CHILD).is_some() {
+        child();
+        return Ok(());
+    }
+
+    let mut cmd = Command::new(current_exe()?);
+    cmd.env(CHILD, "1");
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on_fn("socket", |domain: Domain, ty: SockType, protocol: i32| {
+            println!("socket({:?}, {:?}, {})", domain, ty, protocol);
+            // no raw access to network
+            if domain == Domain::AF_PACKET
+                || (domain == Domain::AF_INET && ty.kind() == libc::SOCK_RAW)
+            {
+                Outcome::Block(-libc::EAFNOSUPPORT)
+            } else {
+                Outcome::Real((domain, ty, protocol))
+            }
+        })
+        .run()?;
+    Ok(())
+}
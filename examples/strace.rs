@@ -0,0 +1,56 @@
+use interceptor_rs::{ArgKind, Interceptor, Phase, SyscallEvent};
+use std::{
+    env::args,
+    fs::{read_link, File},
+    os::unix::fs::FileExt,
+    process::Command,
+};
+
+/// read a NUL terminated string at `addr` of `pid`
+fn read_str(pid: i32, addr: u64) -> Option<String> {
+    let mem = File::open(format!("/proc/{}/mem", pid)).ok()?;
+    let mut buf = vec![0; 256];
+    let n = mem.read_at(&mut buf, addr).ok()?;
+    let s = &buf[..n];
+    let s = &s[..s.iter().position(|b| *b == 0).unwrap_or(s.len())];
+    Some(String::from_utf8_lossy(s).into_owned())
+}
+
+fn format_arg(e: &SyscallEvent, kind: ArgKind, arg: u64) -> String {
+    match kind {
+        ArgKind::Int => (arg as i64).to_string(),
+        ArgKind::Fd if arg as i32 == libc::AT_FDCWD => "AT_FDCWD".to_owned(),
+        ArgKind::Fd => match read_link(format!("/proc/{}/fd/{}", e.pid, arg as i32)) {
+            Ok(path) => format!("{}<{}>", arg as i32, path.display()),
+            Err(_) => (arg as i32).to_string(),
+        },
+        ArgKind::Ptr if arg == 0 => "NULL".to_owned(),
+        ArgKind::Ptr | ArgKind::Flags => format!("0x{:x}", arg),
+        ArgKind::Str => match read_str(e.pid, arg) {
+            Some(s) => format!("{:?}", s),
+            None => format!("0x{:x}", arg),
+        },
+    }
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let mut cmd = Command::new(args.first().map_or("ls", String::as_str));
+    cmd.args(args.iter().skip(1));
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on_event(|e| {
+            if e.phase == Phase::Exit {
+                return;
+            }
+            let args = e
+                .arg_kinds
+                .iter()
+                .zip(e.args)
+                .map(|(kind, arg)| format_arg(e, *kind, arg))
+                .collect::<Vec<_>>();
+            eprintln!("[{}] {}({})", e.pid, e.name, args.join(", "));
+        })
+        .run()?;
+    Ok(())
+}
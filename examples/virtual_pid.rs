@@ -0,0 +1,35 @@
+//! Run a command as if it were the init of a pid namespace, e.g.
+//! `cargo run --example virtual_pid -- sh -c 'echo $$ $PPID'` prints `1 0`.
+//!
+//! `getpid` and `getppid` take no argument and return the pid in `rax`, they are blocked
+//! and the fake one is returned instead. Processes forked by the command are traced too,
+//! so they all see pid 1.
+use interceptor_rs::{syscall, Interceptor};
+use std::{env::args, process::Command};
+
+#[syscall]
+fn getpid() -> i32 {
+    1
+}
+
+#[syscall]
+fn getppid() -> i32 {
+    0
+}
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let args = args().skip(1).collect::<Vec<_>>();
+    let [program, rest @ ..] = args.as_slice() else {
+        eprintln!("usage: virtual_pid <program> [args...]");
+        return Ok(());
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(rest);
+    Interceptor::new(cmd)?
+        .without_remote_mem()
+        .on(&getpid)
+        .on(&getppid)
+        .run()?;
+    Ok(())
+}
@@ -1,17 +1,103 @@
-use std::{env::temp_dir, path::PathBuf, process::id};
+use std::{
+    env::{temp_dir, var},
+    path::PathBuf,
+    process::id,
+};
 
 #[ctor::ctor]
 fn initialize() {
-    let addr = unsafe { libc::malloc(MEM_BLOCK_SIZE) };
+    let prot = var(MEM_PROT_ENV)
+        .ok()
+        .and_then(|p| p.parse().ok())
+        .unwrap_or(libc::PROT_READ | libc::PROT_WRITE);
+    let len = page_aligned(MEM_BLOCK_SIZE);
+    let mut info = MemBlockInfo {
+        base: map(len, prot),
+        len,
+        prot,
+    };
+    if info.base == 0 && prot & libc::PROT_EXEC != 0 {
+        // executable memory may be denied by policy, e.g. SELinux `execmem`
+        info.prot = libc::PROT_READ | libc::PROT_WRITE;
+        info.base = map(len, info.prot);
+    }
+
     std::fs::write(
         mem_block_info_file().with_extension(id().to_string()),
-        (addr as usize).to_le_bytes(),
+        info.to_bytes(),
     )
     .unwrap();
 }
 
+fn map(len: usize, prot: i32) -> usize {
+    let addr = unsafe {
+        libc::mmap(
+            std::ptr::null_mut(),
+            len,
+            prot,
+            libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+            -1,
+            0,
+        )
+    };
+    if addr == libc::MAP_FAILED {
+        0
+    } else {
+        addr as usize
+    }
+}
+
+fn page_aligned(len: usize) -> usize {
+    let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+    len.div_ceil(page) * page
+}
+
 pub fn mem_block_info_file() -> PathBuf {
     temp_dir().join(env!("CARGO_PKG_NAME"))
 }
 
 pub const MEM_BLOCK_SIZE: usize = 1024 * 8;
+
+/// env of protections of the memory block, in `PROT_*` bits, default is
+/// `PROT_READ | PROT_WRITE`.
+pub const MEM_PROT_ENV: &str = "INTER_MEM_PROT";
+
+/// Content of the info file, describing the memory block mapped in target.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MemBlockInfo {
+    /// page aligned, zero if it can not be mapped
+    pub base: usize,
+    pub len: usize,
+    /// `PROT_*` bits
+    pub prot: i32,
+}
+
+impl MemBlockInfo {
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = self.base.to_le_bytes().to_vec();
+        data.extend(self.len.to_le_bytes());
+        data.extend((self.prot as usize).to_le_bytes());
+        data
+    }
+
+    /// a file only containing the base is a block of `malloc(MEM_BLOCK_SIZE)`, which is
+    /// written by older version.
+    pub fn from_bytes(data: &[u8]) -> Option<Self> {
+        let mut fields = data
+            .chunks_exact(std::mem::size_of::<usize>())
+            .map(|c| usize::from_le_bytes(c.try_into().unwrap()));
+        match (fields.next(), fields.next(), fields.next()) {
+            (Some(base), None, None) => Some(Self {
+                base,
+                len: MEM_BLOCK_SIZE,
+                prot: libc::PROT_READ | libc::PROT_WRITE,
+            }),
+            (Some(base), Some(len), Some(prot)) => Some(Self {
+                base,
+                len,
+                prot: prot as i32,
+            }),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,80 @@
+use crate::{ptr::ReadRemote, SyscallContext};
+use pete::Tracee;
+use std::{fs::read_to_string, mem::size_of};
+
+/// `struct clone_args` passed to `clone3`.
+///
+/// Kernel accepts smaller versions of the struct, the size is passed as the second
+/// argument of `clone3`, fields beyond it are zero.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CloneArgs {
+    pub flags: u64,
+    pub pidfd: u64,
+    pub child_tid: u64,
+    pub parent_tid: u64,
+    pub exit_signal: u64,
+    pub stack: u64,
+    pub stack_size: u64,
+    pub tls: u64,
+    pub set_tid: u64,
+    pub set_tid_size: u64,
+    pub cgroup: u64,
+}
+
+impl CloneArgs {
+    /// read from target at `addr`, `size` is the one passed to `clone3`.
+    pub fn read(ctx: &mut SyscallContext, addr: u64, size: u64) -> Self {
+        Self::from_bytes(&ctx.read_memory(addr, Self::len(size)))
+    }
+
+    /// new task shares the thread group of the caller, i.e. `CLONE_THREAD`.
+    pub fn is_thread(&self) -> bool {
+        self.flags & libc::CLONE_THREAD as u64 != 0
+    }
+
+    /// new task shares the address space of the caller, i.e. `CLONE_VM`.
+    pub fn shares_vm(&self) -> bool {
+        self.flags & libc::CLONE_VM as u64 != 0
+    }
+
+    pub(crate) fn read_remote(remote: &mut Tracee, addr: u64, size: u64) -> Self {
+        Self::from_bytes(&remote.read_remote(addr, Self::len(size)))
+    }
+
+    fn len(size: u64) -> usize {
+        (size as usize).min(size_of::<Self>())
+    }
+
+    fn from_bytes(data: &[u8]) -> Self {
+        let mut fields = [0u64; size_of::<Self>() / size_of::<u64>()];
+        for (field, chunk) in fields.iter_mut().zip(data.chunks_exact(size_of::<u64>())) {
+            *field = u64::from_le_bytes(chunk.try_into().unwrap_or_default());
+        }
+
+        let [flags, pidfd, child_tid, parent_tid, exit_signal, stack, stack_size, tls, set_tid, set_tid_size, cgroup] =
+            fields;
+        Self {
+            flags,
+            pidfd,
+            child_tid,
+            parent_tid,
+            exit_signal,
+            stack,
+            stack_size,
+            tls,
+            set_tid,
+            set_tid_size,
+            cgroup,
+        }
+    }
+}
+
+/// thread group id of `pid` from `/proc`, `None` if it already exited.
+pub(crate) fn proc_tgid(pid: i32) -> Option<i32> {
+    read_to_string(format!("/proc/{}/status", pid))
+        .ok()?
+        .lines()
+        .find_map(|l| l.strip_prefix("Tgid:"))
+        .and_then(|t| t.trim().parse().ok())
+}
@@ -0,0 +1,454 @@
+use crate::{
+    error::InterceptError,
+    event::{ArgKind, Phase},
+    maps::read_maps,
+    path::cwd,
+    ptr::{writes_discarded, ReadRemote, WriteRemote},
+    register::Number,
+    SYSCALL_ARGS,
+};
+use anyhow::anyhow;
+use pete::{Registers, Tracee};
+use std::{cell::RefCell, ffi::CStr, mem::take, path::PathBuf};
+
+/// Context of the syscall being handled, see [`with_context`].
+pub struct SyscallContext {
+    pid: i32,
+    name: String,
+    phase: Phase,
+    args: [u64; 6],
+    buffers: [Option<u64>; 6],
+    steps: Option<usize>,
+    /// registers when the handler is called
+    regs: Registers,
+    restore: bool,
+    /// strings set by [`SyscallContext::set_arg_string`], with NUL
+    strings: Vec<(usize, Vec<u8>, ArgWrite)>,
+    /// values set by [`SyscallContext::set_arg`]
+    overrides: Vec<(usize, u64)>,
+    /// see [`SyscallContext::write_on_block`]
+    block_writes: Vec<(u64, Vec<u8>)>,
+    /// path of the mapping containing `pc`, read on first use
+    caller: Option<Option<String>>,
+    tracee: TraceePtr,
+}
+
+/// How [`SyscallContext::set_arg_string`] writes the new string.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgWrite {
+    /// overwrite the original string, the argument is not changed
+    InPlace,
+    /// write into remote memory, the argument points to it
+    Allocated,
+}
+
+thread_local! {
+    static CURRENT: RefCell<Option<SyscallContext>> = const { RefCell::new(None) };
+}
+
+/// run `f` with the context of the syscall being handled.
+///
+/// It can only be called inside `#[syscall]` functions (both before and after
+/// `real!()`), and can not be nested, otherwise it panics.
+pub fn with_context<R>(f: impl FnOnce(&mut SyscallContext) -> R) -> R {
+    CURRENT.with(|c| {
+        f(c.borrow_mut()
+            .as_mut()
+            .expect("with_context called outside of syscall handler"))
+    })
+}
+
+/// What a handler requested through the context, handled after it returned.
+#[derive(Default)]
+pub(crate) struct Requests {
+    /// see [`SyscallContext::single_step`]
+    pub(crate) steps: Option<usize>,
+    /// see [`SyscallContext::restore_registers`]
+    pub(crate) restore: bool,
+    /// see [`SyscallContext::write_on_block`]
+    pub(crate) block_writes: Vec<(u64, Vec<u8>)>,
+}
+
+/// The tracee being handled, shared by [`enter_context`] and the context, both access it
+/// through the same pointer and only for the duration of each operation, as a handler
+/// may take it by [`SyscallContext::raw_tracee`] in the middle.
+pub(crate) struct TraceePtr(*mut Tracee);
+
+impl TraceePtr {
+    pub(crate) fn get(&mut self) -> &mut Tracee {
+        // SAFETY: it points to the tracee held by `enter_context` during the call, and the
+        // returned borrow is never kept across a handler call
+        unsafe { &mut *self.0 }
+    }
+}
+
+/// clears the context when `enter_context` returns or `f` panics, so no dangling pointer
+/// to the tracee is left
+struct ResetContext;
+
+impl Drop for ResetContext {
+    fn drop(&mut self) {
+        let _ = CURRENT.try_with(|c| {
+            if let Ok(mut c) = c.try_borrow_mut() {
+                c.take();
+            }
+        });
+    }
+}
+
+/// set context for the duration of `f`, `tracee` must outlive the call and `regs` are its
+/// current registers. Requests made by handler are returned along with the result of `f`.
+pub(crate) fn enter_context<R>(
+    tracee: &mut Tracee,
+    name: &str,
+    phase: Phase,
+    args: [u64; 6],
+    buffers: [Option<u64>; 6],
+    regs: Registers,
+    f: impl FnOnce(&mut TraceePtr) -> R,
+) -> (R, Requests) {
+    let pid = tracee.pid.as_raw();
+    let ptr = tracee as *mut Tracee;
+    let _reset = ResetContext;
+    CURRENT.with(|c| {
+        *c.borrow_mut() = Some(SyscallContext {
+            pid,
+            name: name.to_owned(),
+            phase,
+            args,
+            buffers,
+            steps: None,
+            regs,
+            restore: false,
+            strings: Vec::new(),
+            overrides: Vec::new(),
+            block_writes: Vec::new(),
+            caller: None,
+            tracee: TraceePtr(ptr),
+        })
+    });
+    let r = f(&mut TraceePtr(ptr));
+    let requests = CURRENT
+        .with(|c| c.borrow_mut().take())
+        .map(|c| Requests {
+            steps: c.steps,
+            restore: c.restore,
+            block_writes: c.block_writes,
+        })
+        .unwrap_or_default();
+    (r, requests)
+}
+
+impl SyscallContext {
+    /// pid of the tracee
+    pub fn pid(&self) -> i32 {
+        self.pid
+    }
+
+    /// syscall name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn phase(&self) -> Phase {
+        self.phase
+    }
+
+    /// raw argument at `index` when syscall entered, still available after `real!()`
+    pub fn arg(&self, index: usize) -> u64 {
+        self.args[index]
+    }
+
+    /// read `len` bytes from target, the result may be shorter if memory is not readable.
+    pub fn read_memory(&mut self, addr: u64, len: usize) -> Vec<u8> {
+        self.tracee().read_remote(addr, len)
+    }
+
+    /// write `data` into target.
+    pub fn write_memory(&mut self, addr: u64, data: &[u8]) -> Result<(), InterceptError> {
+        let n = self.tracee().write_remote(addr, data)?;
+        if n != data.len() {
+            return Err(InterceptError::MemoryAccess(addr + n as u64));
+        }
+
+        Ok(())
+    }
+
+    /// read the buffer of the pointer argument at `index`, whose size is the argument at
+    /// `size_index`, at most `limit` bytes, e.g. `read_arg_buffer(1, 2, limit)` for what
+    /// `write` or `pwrite64` is about to write, or the return value of `pread64` after
+    /// `real!()`. A null pointer reads nothing.
+    pub fn read_arg_buffer(
+        &mut self,
+        index: usize,
+        size_index: usize,
+        limit: usize,
+    ) -> Result<Vec<u8>, InterceptError> {
+        let size = self.buffer_size(index, size_index)?;
+        let addr = self.args[index];
+        if addr == 0 {
+            return Ok(Vec::new());
+        }
+
+        Ok(self.read_memory(addr, size.min(limit as u64) as usize))
+    }
+
+    /// size of the buffer at `index`, taken from the argument at `size_index`
+    fn buffer_size(&self, index: usize, size_index: usize) -> Result<u64, InterceptError> {
+        if index >= self.args.len() || size_index >= self.args.len() || index == size_index {
+            return Err(anyhow!(
+                "size of argument {} is not known from argument {}",
+                index,
+                size_index
+            )
+            .into());
+        }
+
+        Ok(self.args[size_index])
+    }
+
+    /// write `data` into the buffer of the pointer argument at `index` after `real!()`,
+    /// e.g. `overwrite_arg_buffer(1, 2, data)` to scrub what kernel filled into the buffer
+    /// of `read`.
+    ///
+    /// The buffer is the one passed in by the caller, its size is the argument at
+    /// `size_index` (e.g. `count` of `read`), and `data` must not exceed it.
+    pub fn overwrite_arg_buffer(
+        &mut self,
+        index: usize,
+        size_index: usize,
+        data: &[u8],
+    ) -> Result<(), InterceptError> {
+        let size = self.buffer_size(index, size_index)?;
+        let addr = self.buffers.get(index).copied().flatten().ok_or_else(|| {
+            anyhow!(
+                "argument {} is not a pointer available after real!()",
+                index
+            )
+        })?;
+        if data.len() as u64 > size {
+            return Err(InterceptError::WriteTooLarge {
+                size: data.len(),
+                max: size as usize,
+            });
+        }
+
+        self.write_memory(addr, data)
+    }
+
+    /// replace what `readlink` or `readlinkat` placed into its buffer with `target` after
+    /// `real!()`, and returns the new return value, i.e. the number of bytes placed.
+    ///
+    /// Same as kernel, `target` is cut to `bufsiz` silently and not NUL terminated, so the
+    /// returned value must be passed to caller for it to see the right length.
+    pub fn set_link_target(&mut self, target: &[u8]) -> Result<i64, InterceptError> {
+        let (index, size_index) = match self.name.as_str() {
+            "readlink" => (1, 2),
+            "readlinkat" => (2, 3),
+            name => return Err(anyhow!("{} does not read a link", name).into()),
+        };
+        let addr = self.arg(index);
+        let size = self.buffer_size(index, size_index)?;
+        let data = &target[..target.len().min(size as usize)];
+        if !data.is_empty() {
+            self.write_memory(addr, data)?;
+        }
+        Ok(data.len() as i64)
+    }
+
+    /// set the string argument at `index` to `new` before `real!()`, it's written in place
+    /// if not longer than the original one, otherwise into remote memory and the argument
+    /// is changed to point to it. Returns which way is taken.
+    ///
+    /// The string is written after the function returns, it overrides the value passed
+    /// to `real!()` for the argument, and is ignored if the syscall is blocked.
+    pub fn set_arg_string(&mut self, index: usize, new: &CStr) -> Result<ArgWrite, InterceptError> {
+        if self.phase != Phase::Enter {
+            return Err(anyhow!("argument {} can only be set before real!()", index).into());
+        }
+        let addr = *self
+            .args
+            .get(index)
+            .ok_or_else(|| anyhow!("invalid argument index {}", index))?;
+        if addr == 0 {
+            return Err(anyhow!("argument {} is a null pointer", index).into());
+        }
+
+        let origin = self.tracee().read_bytes_with_nul(addr);
+        let len = origin.iter().position(|b| *b == 0).unwrap_or(origin.len());
+        let write = if new.to_bytes().len() <= len {
+            ArgWrite::InPlace
+        } else {
+            ArgWrite::Allocated
+        };
+        self.strings.retain(|(i, ..)| *i != index);
+        self.strings
+            .push((index, new.to_bytes_with_nul().to_vec(), write));
+        Ok(write)
+    }
+
+    /// set the argument at `index` to `value` before `real!()`, without threading the
+    /// unchanged ones back through the tuple returned by handler.
+    ///
+    /// It's applied after the function returns and overrides the value passed to
+    /// `real!()` for the argument, the last one wins if set more than once. It's ignored
+    /// if the syscall is blocked.
+    pub fn set_arg(&mut self, index: usize, value: impl Number) -> Result<(), InterceptError> {
+        if self.phase != Phase::Enter {
+            return Err(anyhow!("argument {} can only be set before real!()", index).into());
+        }
+        if index >= self.args.len() {
+            return Err(anyhow!("invalid argument index {}", index).into());
+        }
+
+        self.overrides.retain(|(i, _)| *i != index);
+        self.overrides.push((index, value.to_reg().as_unsigned()));
+        Ok(())
+    }
+
+    /// set the flags argument, e.g. `flags` of `openat`, see [`SyscallContext::set_arg`].
+    /// It fails if the syscall takes no flags.
+    pub fn set_flags(&mut self, flags: impl Number) -> Result<(), InterceptError> {
+        let index = SYSCALL_ARGS
+            .get(self.name.as_str())
+            .and_then(|kinds| kinds.iter().position(|k| *k == ArgKind::Flags))
+            .ok_or_else(|| anyhow!("{} takes no flags", self.name))?;
+        self.set_arg(index, flags)
+    }
+
+    /// set the file mode argument, e.g. `mode` of `openat` or `mkdir`, see
+    /// [`SyscallContext::set_arg`]. It fails if the syscall takes no mode.
+    pub fn set_mode(&mut self, mode: u32) -> Result<(), InterceptError> {
+        let index = match self.name.as_str() {
+            "creat" | "mkdir" | "chmod" | "fchmod" | "mknod" => 1,
+            "open" | "mkdirat" | "fchmodat" | "fchmodat2" | "mknodat" => 2,
+            "openat" => 3,
+            _ => return Err(anyhow!("{} takes no mode", self.name).into()),
+        };
+        self.set_arg(index, mode)
+    }
+
+    /// write `data` into target at `addr` when the syscall is blocked by the handler, e.g.
+    /// the output buffer the caller expects to be filled by the syscall.
+    ///
+    /// It's written at the exit stop of the blocked syscall, along with its return value,
+    /// and ignored if the syscall is not blocked.
+    ///
+    /// ```rust,ignore
+    /// interceptor.on_fn("uname", |buf: u64| {
+    ///     with_context(|ctx| ctx.write_on_block(buf, &fake)).unwrap();
+    ///     Outcome::<i32, _>::Block(0)
+    /// });
+    /// ```
+    pub fn write_on_block(&mut self, addr: u64, data: &[u8]) -> Result<(), InterceptError> {
+        if self.phase != Phase::Enter {
+            return Err(anyhow!("only a syscall not sent to kernel yet can be blocked").into());
+        }
+
+        self.block_writes.push((addr, data.to_vec()));
+        Ok(())
+    }
+
+    /// take values set by [`SyscallContext::set_arg`]
+    pub(crate) fn take_arg_overrides(&mut self) -> Vec<(usize, u64)> {
+        take(&mut self.overrides)
+    }
+
+    /// take strings set by [`SyscallContext::set_arg_string`]
+    pub(crate) fn take_arg_strings(&mut self) -> Vec<(usize, Vec<u8>, ArgWrite)> {
+        take(&mut self.strings)
+    }
+
+    /// single-step the tracee for the next `n` instructions once the syscall returned,
+    /// each step is reported to handlers registered by
+    /// [`Interceptor::on_step`](crate::Interceptor::on_step), then it goes back to
+    /// syscall granularity.
+    ///
+    /// Syscalls made during stepping are not intercepted.
+    pub fn single_step(&mut self, n: usize) {
+        self.steps = Some(n);
+    }
+
+    /// registers of the tracee when the handler is called, e.g. to look at the stack
+    /// pointer, arguments are in `rdi`, `rsi`, `rdx`, `r10`, `r8` and `r9`.
+    pub fn registers(&self) -> Registers {
+        self.regs
+    }
+
+    /// address the syscall is made from, i.e. the saved `rip`. On x86_64 it points just
+    /// after the 2-byte `syscall` instruction, not at it.
+    pub fn pc(&self) -> u64 {
+        self.regs.rip
+    }
+
+    /// working directory of the tracee, `None` if it can not be read, e.g. the tracee
+    /// exited. It's cached if [`Interceptor::track_cwd`](crate::Interceptor::track_cwd)
+    /// is enabled.
+    pub fn cwd(&self) -> Option<PathBuf> {
+        cwd(self.pid)
+    }
+
+    /// path of the module the syscall is made from, e.g. `/usr/lib/libc.so.6`, found in
+    /// the memory maps of the tracee by [`SyscallContext::pc`], see
+    /// [`Interceptor::memory_maps`](crate::Interceptor::memory_maps).
+    ///
+    /// `None` if it's called from an anonymous mapping, e.g. JIT code, or maps are not
+    /// readable. Pseudo names like `[vdso]` are returned as is. Maps are read once per
+    /// handler call.
+    pub fn caller_module(&mut self) -> Option<&str> {
+        let (pid, pc) = (self.pid, self.pc());
+        self.caller
+            .get_or_insert_with(|| {
+                // the instruction itself, in case it ends a mapping
+                let insn = pc.wrapping_sub(2);
+                read_maps(pid)
+                    .ok()?
+                    .into_iter()
+                    .find(|m| m.contains(insn))?
+                    .path
+            })
+            .as_deref()
+    }
+
+    /// back out changes to registers made by the handler, the syscall is sent to kernel
+    /// (or returned to caller after `real!()`) as if the handler changed nothing.
+    ///
+    /// Registers returned by [`SyscallContext::registers`] are written back immediately,
+    /// and arguments or return value returned by the handler, including blocking and
+    /// arguments set by [`SyscallContext::set_arg`] and
+    /// [`SyscallContext::set_arg_string`], are ignored. Memory already
+    /// written into target is not restored. Registers are not written in
+    /// [`Interceptor::dry_run`](crate::Interceptor::dry_run) and
+    /// [`Interceptor::read_only`](crate::Interceptor::read_only) modes.
+    pub fn restore_registers(&mut self) -> Result<(), InterceptError> {
+        if !writes_discarded() {
+            let regs = self.regs;
+            self.tracee()
+                .set_registers(regs)
+                .map_err(|e| InterceptError::Other(e.into()))?;
+        }
+        self.restore = true;
+        self.strings.clear();
+        self.overrides.clear();
+        self.block_writes.clear();
+        Ok(())
+    }
+
+    /// the underlying tracee, an escape hatch for operations not covered by this crate,
+    /// e.g. debug registers, FP registers or the stack.
+    ///
+    /// Changes made through it bypass [`Interceptor::dry_run`](crate::Interceptor::dry_run),
+    /// [`Interceptor::read_only`](crate::Interceptor::read_only) and
+    /// [`Interceptor::verify_writes`](crate::Interceptor::verify_writes). Registers
+    /// are read before the handler and written back after it if arguments or the return
+    /// value are to be changed, which overwrites registers set through it, so do not
+    /// change the ones interceptor also changes in the same stop. The tracee must not be
+    /// restarted or detached.
+    pub fn raw_tracee(&mut self) -> &mut Tracee {
+        self.tracee()
+    }
+
+    fn tracee(&mut self) -> &mut Tracee {
+        self.tracee.get()
+    }
+}
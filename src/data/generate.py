@@ -0,0 +1,93 @@
+#!/usr/bin/env python3
+"""Regenerate bundled syscall tables of other architectures from kernel headers.
+
+Numbers are taken from the uapi headers of `linux-libc-dev`, kinds of arguments from
+`syscalls_x64.tsv` by name, syscalls only found on the architecture have none.
+
+    python3 src/data/generate.py aarch64 > src/data/syscalls_aarch64.tsv
+    python3 src/data/generate.py x86 > src/data/syscalls_x86.tsv
+"""
+import os
+import re
+import subprocess
+import sys
+
+HERE = os.path.dirname(os.path.abspath(__file__))
+INCLUDE = "/usr/include"
+# `arch/arm64/include/uapi/asm/unistd.h` of kernel, it includes the generic table
+AARCH64_WANTS = [
+    "__ARCH_WANT_RENAMEAT",
+    "__ARCH_WANT_NEW_STAT",
+    "__ARCH_WANT_SET_GET_RLIMIT",
+    "__ARCH_WANT_TIME32_SYSCALLS",
+    "__ARCH_WANT_SYS_CLONE3",
+    "__ARCH_WANT_MEMFD_SECRET",
+]
+NOT_SYSCALLS = {"__NR_syscalls", "__NR_arch_specific_syscall"}
+# same names as x86_64 but arguments laid out differently
+X86_OWN_KINDS = {"mmap", "select"}
+
+
+def aarch64():
+    header = os.path.join(INCLUDE, "asm-generic/unistd.h")
+    args = ["cpp", "-dM", "-D__BITS_PER_LONG=64"]
+    args += ["-D%s" % w for w in AARCH64_WANTS]
+    macros = subprocess.run(args + [header], capture_output=True, text=True, check=True)
+    values = {}
+    for line in macros.stdout.splitlines():
+        m = re.match(r"#define (__NR\w+) (\w+)$", line)
+        if m:
+            values[m.group(1)] = m.group(2)
+
+    numbers = []
+    for macro, value in values.items():
+        # not syscalls but the size of the table and the start of numbers reserved
+        if not macro.startswith("__NR_") or macro in NOT_SYSCALLS:
+            continue
+        value = values.get(value, value)
+        if value.isdigit():
+            numbers.append((int(value), macro[len("__NR_"):]))
+    return header, numbers
+
+
+def x86():
+    header = os.path.join(INCLUDE, "x86_64-linux-gnu/asm/unistd_32.h")
+    numbers = []
+    with open(header) as f:
+        for line in f:
+            m = re.match(r"#define __NR_(\w+) (\d+)$", line.strip())
+            if m:
+                numbers.append((int(m.group(2)), m.group(1)))
+    return header, numbers
+
+
+def kernel_version():
+    with open(os.path.join(INCLUDE, "linux/version.h")) as f:
+        parts = dict(re.findall(r"#define LINUX_VERSION_(MAJOR|PATCHLEVEL|SUBLEVEL) (\d+)", f.read()))
+    return "%s.%s.%s" % (parts["MAJOR"], parts["PATCHLEVEL"], parts["SUBLEVEL"])
+
+
+def main():
+    arch = sys.argv[1]
+    header, numbers = {"aarch64": aarch64, "x86": x86}[arch]()
+    kinds = {}
+    with open(os.path.join(HERE, "syscalls_x64.tsv")) as f:
+        for line in f:
+            fields = line.rstrip("\n").split("\t")
+            if not line.startswith("#") and len(fields) > 2:
+                kinds[fields[1]] = fields[2]
+    if arch == "x86":
+        for name in X86_OWN_KINDS:
+            kinds.pop(name, None)
+
+    print("# generated by src/data/generate.py from %s of linux %s" % (header, kernel_version()))
+    print("# number\tname\tkinds of arguments: int, fd, ptr, str or flags, - if unknown")
+    for no, name in sorted(numbers):
+        if name in kinds:
+            print("%d\t%s\t%s" % (no, name, kinds[name]))
+        else:
+            print("%d\t%s" % (no, name))
+
+
+if __name__ == "__main__":
+    main()
@@ -0,0 +1,136 @@
+use crate::{InterceptError, SyscallContext};
+use std::ops::Deref;
+
+/// size of `d_ino`, `d_off`, `d_reclen` and `d_type` of `struct linux_dirent64`
+const HEADER: usize = 8 + 8 + 2 + 1;
+
+/// A `struct linux_dirent64` record filled by `getdents64`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Dirent {
+    pub ino: u64,
+    /// position to continue after this entry, e.g. returned by `telldir`
+    pub off: i64,
+    /// `DT_*` file type
+    pub kind: u8,
+    /// file name without NUL
+    pub name: Vec<u8>,
+}
+
+/// The buffer of `getdents64` with its variable-length records, read after `real!()`
+/// with the returned byte count, see [`DirentBuf::read_arg`].
+///
+/// Entries can be removed by [`DirentBuf::retain`], then written back compacted by
+/// [`DirentBuf::store`], which returns the new byte count to return to the caller.
+///
+/// ```rust,ignore
+/// #[syscall]
+/// fn getdents64(fd: i32, dirp: u64, count: u32) -> i64 {
+///     let ret = real!(fd, dirp, count);
+///     if ret <= 0 {
+///         return ret;
+///     }
+///     with_context(|ctx| {
+///         let mut buf = DirentBuf::read_arg(ctx, 1, ret as usize);
+///         buf.retain(|e| e.name != b"secret");
+///         buf.store(ctx).map(|n| n as i64).unwrap_or(ret)
+///     })
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct DirentBuf {
+    addr: u64,
+    entries: Vec<Dirent>,
+}
+
+impl DirentBuf {
+    /// read `len` bytes of records from the buffer pointed by argument at `index` of the
+    /// syscall being handled, `len` is the return value of `getdents64`. Parsing stops
+    /// at a malformed record.
+    pub fn read_arg(ctx: &mut SyscallContext, index: usize, len: usize) -> Self {
+        let addr = ctx.arg(index);
+        let data = if addr == 0 {
+            Vec::new()
+        } else {
+            ctx.read_memory(addr, len)
+        };
+
+        let mut entries = Vec::new();
+        let mut pos = 0;
+        while pos + HEADER <= data.len() {
+            let record = &data[pos..];
+            let reclen = u16::from_le_bytes([record[16], record[17]]) as usize;
+            if reclen < HEADER || reclen > record.len() {
+                break;
+            }
+
+            let name = &record[HEADER..reclen];
+            let name = &name[..name.iter().position(|b| *b == 0).unwrap_or(name.len())];
+            entries.push(Dirent {
+                ino: u64::from_le_bytes(record[..8].try_into().unwrap_or_default()),
+                off: i64::from_le_bytes(record[8..16].try_into().unwrap_or_default()),
+                kind: record[18],
+                name: name.to_vec(),
+            });
+            pos += reclen;
+        }
+
+        Self { addr, entries }
+    }
+
+    /// origin address of the buffer inside target
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// keep entries for which `f` returns `true`.
+    ///
+    /// The position of an entry before removed ones is moved to the last removed one, so
+    /// the caller continues after them.
+    pub fn retain(&mut self, mut f: impl FnMut(&Dirent) -> bool) {
+        let mut kept: Vec<Dirent> = Vec::with_capacity(self.entries.len());
+        for entry in self.entries.drain(..) {
+            if f(&entry) {
+                kept.push(entry);
+            } else if let Some(prev) = kept.last_mut() {
+                prev.off = entry.off;
+            }
+        }
+        self.entries = kept;
+    }
+
+    /// write entries back to target compacted, returns their byte count, which should
+    /// be returned to the caller instead.
+    ///
+    /// `0` is returned if all entries are removed, which the caller takes as the end of
+    /// the directory, so entries after them are not listed.
+    pub fn store(&self, ctx: &mut SyscallContext) -> Result<usize, InterceptError> {
+        let data = self.to_bytes();
+        if !data.is_empty() {
+            ctx.write_memory(self.addr, &data)?;
+        }
+        Ok(data.len())
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut data = Vec::new();
+        for entry in &self.entries {
+            // name with NUL, padded to 8 bytes
+            let reclen = (HEADER + entry.name.len() + 1).next_multiple_of(8);
+            data.extend(entry.ino.to_le_bytes());
+            data.extend(entry.off.to_le_bytes());
+            data.extend((reclen as u16).to_le_bytes());
+            data.push(entry.kind);
+            data.extend(&entry.name);
+            data.resize(data.len() + reclen - HEADER - entry.name.len(), 0);
+        }
+        data
+    }
+}
+
+impl Deref for DirentBuf {
+    type Target = [Dirent];
+
+    fn deref(&self) -> &Self::Target {
+        &self.entries
+    }
+}
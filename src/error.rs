@@ -0,0 +1,50 @@
+use std::{io, time::Duration};
+use thiserror::Error;
+
+/// Error returned by public APIs, so the kind of failure can be matched.
+#[derive(Debug, Error)]
+pub enum InterceptError {
+    /// the child can not be spawned
+    #[error("spawn child error: {0}")]
+    Spawn(#[source] io::Error),
+    /// ptrace is not permitted, e.g. denied by `kernel.yama.ptrace_scope` or seccomp
+    #[error("ptrace is not permitted")]
+    PtracePermission,
+    /// a pointer argument is changed to a new one, but remote memory is not available
+    #[error("{0}")]
+    RemoteMemUnavailable(String),
+    /// memory of the tracee at the address can not be read or written
+    #[error("access tracee memory 0x{0:x} error")]
+    MemoryAccess(u64),
+    /// content written into target exceeds the space available
+    #[error("content is too large: {size} > {max}")]
+    WriteTooLarge { size: usize, max: usize },
+    /// remote memory of `max` bytes is used up by the syscall being handled, or wrapped
+    /// around too fast, see [`Interceptor::remote_mem_guard`](crate::Interceptor::remote_mem_guard)
+    #[error("remote memory of {max} bytes exhausted, {size} more bytes needed")]
+    RemoteMemExhausted { size: usize, max: usize },
+    #[error(transparent)]
+    TimedOut(#[from] TimedOut),
+    /// other errors, e.g. failed ptrace operations or invalid arguments
+    #[error(transparent)]
+    Other(anyhow::Error),
+}
+
+impl From<anyhow::Error> for InterceptError {
+    /// errors raised as `InterceptError` inside are recovered
+    fn from(e: anyhow::Error) -> Self {
+        match e.downcast::<Self>() {
+            Ok(e) => e,
+            Err(e) => match e.downcast::<TimedOut>() {
+                Ok(e) => Self::TimedOut(e),
+                Err(e) => Self::Other(e),
+            },
+        }
+    }
+}
+
+/// Error returned by [`Interceptor::run_with_timeout`](crate::Interceptor::run_with_timeout)
+/// when the session exceeds the duration.
+#[derive(Debug, Error)]
+#[error("intercepting session timed out after {0:?}")]
+pub struct TimedOut(pub Duration);
@@ -0,0 +1,143 @@
+use crate::register::Number;
+use std::time::Duration;
+
+/// Phase of an intercepted syscall.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+    /// stopped before the syscall is sent to kernel
+    Enter,
+    /// stopped after the syscall returned from kernel
+    Exit,
+}
+
+/// A syscall stop seen by interceptor, passed to handlers registered by
+/// [`Interceptor::on_event`](crate::Interceptor::on_event).
+#[derive(Debug, Clone)]
+pub struct SyscallEvent {
+    /// pid of the tracee, it's the thread id if tracee is a thread
+    pub pid: i32,
+    /// thread group id of the tracee, i.e. the pid of the process it belongs to
+    pub tgid: i32,
+    /// syscall name, "unknown" if not found in syscall table
+    pub name: &'static str,
+    /// syscall number
+    pub sysno: u64,
+    /// unique id of the call, the same at [`Phase::Enter`] and [`Phase::Exit`] of it, e.g.
+    /// to pair them when threads are interleaved. It's increasing in the order the calls
+    /// entered, `0` if the enter was not seen, e.g. the exit of the first `execve`.
+    pub call_id: u64,
+    pub phase: Phase,
+    /// raw arguments in register order
+    pub args: [u64; 6],
+    /// saved `rip` of the tracee, on x86_64 it points just after the `syscall`
+    /// instruction, see [`SyscallContext::pc`](crate::SyscallContext::pc)
+    pub pc: u64,
+    /// kinds of arguments in register order, e.g. to format them, the length is the
+    /// number of arguments, empty if unknown
+    pub arg_kinds: &'static [ArgKind],
+    /// how handlers changed arguments in register order, only available at
+    /// [`Phase::Enter`], all [`ArgUpdate::Unchanged`] at [`Phase::Exit`]
+    pub arg_updates: [ArgUpdate; 6],
+    /// raw return value, only available at [`Phase::Exit`]
+    pub ret: Option<u64>,
+    /// time elapsed from enter to exit, only available at [`Phase::Exit`]
+    pub duration: Option<Duration>,
+    /// changes requested by handlers but not applied to the tracee, filled in
+    /// [`Interceptor::dry_run`](crate::Interceptor::dry_run) and
+    /// [`Interceptor::read_only`](crate::Interceptor::read_only) modes, and for syscalls
+    /// restoring registers (e.g. `rt_sigreturn`) in any mode, empty otherwise
+    pub discarded: Vec<Change>,
+}
+
+/// Kind of a syscall argument, see [`SyscallEvent::arg_kinds`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ArgKind {
+    /// number, e.g. size, pid or mode
+    Int,
+    /// file descriptor, including `AT_FDCWD` of `*at` syscalls
+    Fd,
+    /// pointer to a buffer or struct
+    Ptr,
+    /// pointer to a NUL terminated string, e.g. path
+    Str,
+    /// bit flags
+    Flags,
+}
+
+/// How an argument is changed by handlers, see [`SyscallEvent::arg_updates`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ArgUpdate {
+    /// neither the argument nor the memory it points to is changed
+    #[default]
+    Unchanged,
+    /// memory the pointer argument points to is rewritten, the pointer is kept
+    RewrittenInPlace,
+    /// content is written into remote memory at the address, the argument points to it
+    Reallocated(u64),
+    /// the argument is changed to the value, e.g. a number or flags
+    Changed(u64),
+}
+
+/// A change to the tracee computed by handlers.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    /// argument at `index` in register order
+    Arg { index: usize, old: u64, new: u64 },
+    /// syscall is not sent to kernel, the value is returned to caller directly
+    Block(u64),
+    /// another syscall is sent to kernel instead, see [`Decision::Redirect`]
+    Sysno { old: u64, new: u64 },
+    /// return value changed at [`Phase::Exit`]
+    Ret { old: u64, new: u64 },
+    /// `data` written into target at `addr`
+    Memory { addr: u64, data: Vec<u8> },
+    /// `data` written into remote memory allocated for an argument, nothing is allocated
+    /// and the argument is kept when discarded
+    Alloc { data: Vec<u8> },
+}
+
+/// Decision made by handlers registered by [`Interceptor::on_all`](crate::Interceptor::on_all)
+/// at [`Phase::Enter`], only [`Decision::Retry`] is taken at [`Phase::Exit`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Decision {
+    /// send the syscall to kernel unchanged
+    Continue,
+    /// change raw arguments in register order, `None` keeps the original one
+    Modify([Option<u64>; 6]),
+    /// syscall will not be sent to kernel, the value is returned to caller directly
+    Block(u64),
+    /// send syscall `sysno` with raw arguments `args` to kernel instead, e.g. turn `open`
+    /// into `openat(AT_FDCWD, ...)`. Handlers of `sysno` are not called for it, and the
+    /// exit is reported as the origin syscall.
+    Redirect { sysno: u64, args: [u64; 6] },
+    /// at [`Phase::Exit`], send the syscall to kernel again instead of returning to the
+    /// caller, with raw arguments changed like [`Decision::Modify`], e.g. to retry one
+    /// failed with `EAGAIN`. It's a new syscall to handlers, reported with both phases.
+    ///
+    /// The tracee is rewound by the 2 bytes of the x86_64 `syscall` instruction, retries
+    /// in a row are bounded by [`Interceptor::max_retries`](crate::Interceptor::max_retries).
+    /// The first one wins, and it's ignored at [`Phase::Enter`].
+    Retry([Option<u64>; 6]),
+}
+
+/// A single-step stop requested by
+/// [`SyscallContext::single_step`](crate::SyscallContext::single_step).
+#[derive(Clone, Copy)]
+pub struct StepEvent {
+    /// pid of the tracee
+    pub pid: i32,
+    /// steps left before going back to syscall granularity
+    pub remaining: usize,
+    /// registers after the instruction executed
+    pub regs: libc::user_regs_struct,
+}
+
+/// errno of a failed syscall from its return value `ret`, `None` if it succeeded.
+///
+/// Linux syscalls return `-errno` on failure, and errno never exceeds 4095, so only
+/// values in `-4095..=-1` are errors, others (e.g. a large address returned by `mmap`)
+/// are valid results even if negative as signed.
+pub fn as_errno<R: Number>(ret: R) -> Option<i32> {
+    let ret = ret.to_reg().as_signed();
+    (-4095..=-1).contains(&ret).then_some(-ret as i32)
+}
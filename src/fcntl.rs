@@ -0,0 +1,152 @@
+use crate::{
+    ptr::{pod_bytes, Pod},
+    InterceptError, SyscallContext,
+};
+use anyhow::anyhow;
+use std::mem::size_of;
+
+/// not in `libc`
+const F_SETSIG: i32 = 10;
+const F_GETSIG: i32 = 11;
+
+/// The `struct flock` taken by the locking commands of `fcntl`, e.g. `F_SETLK`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Flock {
+    /// `F_RDLCK`, `F_WRLCK` or `F_UNLCK`
+    pub l_type: i16,
+    /// `SEEK_SET`, `SEEK_CUR` or `SEEK_END`
+    pub l_whence: i16,
+    pad0: i32,
+    pub l_start: i64,
+    pub l_len: i64,
+    /// holder of a conflicting lock, filled by `F_GETLK`
+    pub l_pid: i32,
+    pad1: i32,
+}
+
+const _: () = assert!(size_of::<Flock>() == 32);
+
+unsafe impl Pod for Flock {}
+
+/// The third argument of `fcntl`, decoded by its command.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FcntlArg {
+    /// not used by the command, e.g. `F_GETFL`
+    None,
+    /// an `int`, e.g. flags of `F_SETFL` or fd of `F_DUPFD`
+    Int(i32),
+    /// `struct flock` pointed by it, e.g. `F_SETLK`
+    Flock(Flock),
+    /// raw value of a command not known, or a `struct flock` pointer not readable
+    Raw(u64),
+}
+
+/// An `fcntl` handled by [`Interceptor::on_fcntl`](crate::Interceptor::on_fcntl), with
+/// its third argument decoded by `cmd`.
+#[derive(Debug, Clone)]
+pub struct FcntlCtx {
+    /// pid of the tracee
+    pub pid: i32,
+    pub fd: i32,
+    pub cmd: i32,
+    /// raw third argument
+    pub raw: u64,
+    /// decoded third argument, changes are written back after the handler returned,
+    /// into the register for [`FcntlArg::Int`] and [`FcntlArg::Raw`], or into target for
+    /// [`FcntlArg::Flock`]
+    pub arg: FcntlArg,
+    origin: FcntlArg,
+}
+
+impl FcntlCtx {
+    /// decode the `fcntl` being handled in `ctx` if its command is `cmd`
+    pub(crate) fn read(ctx: &mut SyscallContext, cmd: i32) -> Option<Self> {
+        if ctx.arg(1) as i32 != cmd {
+            return None;
+        }
+
+        let raw = ctx.arg(2);
+        let arg = match cmd {
+            libc::F_GETFD
+            | libc::F_GETFL
+            | libc::F_GETOWN
+            | F_GETSIG
+            | libc::F_GETLEASE
+            | libc::F_GETPIPE_SZ
+            | libc::F_GET_SEALS => FcntlArg::None,
+            libc::F_DUPFD
+            | libc::F_DUPFD_CLOEXEC
+            | libc::F_SETFD
+            | libc::F_SETFL
+            | libc::F_SETOWN
+            | F_SETSIG
+            | libc::F_SETLEASE
+            | libc::F_NOTIFY
+            | libc::F_SETPIPE_SZ
+            | libc::F_ADD_SEALS => FcntlArg::Int(raw as i32),
+            libc::F_GETLK
+            | libc::F_SETLK
+            | libc::F_SETLKW
+            | libc::F_OFD_GETLK
+            | libc::F_OFD_SETLK
+            | libc::F_OFD_SETLKW => {
+                read_flock(ctx, raw).map_or(FcntlArg::Raw(raw), FcntlArg::Flock)
+            }
+            _ => FcntlArg::Raw(raw),
+        };
+
+        Some(Self {
+            pid: ctx.pid(),
+            fd: ctx.arg(0) as i32,
+            cmd,
+            raw,
+            arg,
+            origin: arg,
+        })
+    }
+
+    /// the new third argument if changed by handler, `struct flock` is written back into
+    /// target here as the pointer is kept
+    pub(crate) fn store(&self, ctx: &mut SyscallContext) -> Result<Option<u64>, InterceptError> {
+        if self.arg == self.origin {
+            return Ok(None);
+        }
+
+        match self.arg {
+            FcntlArg::Int(v) => Ok(Some(v as u64)),
+            FcntlArg::Raw(v) => Ok(Some(v)),
+            FcntlArg::Flock(lock) if matches!(self.origin, FcntlArg::Flock(_)) => {
+                ctx.write_memory(self.raw, pod_bytes(&[lock]))?;
+                Ok(None)
+            }
+            _ => Err(anyhow!(
+                "fcntl command {} does not take argument {:?}",
+                self.cmd,
+                self.arg
+            )
+            .into()),
+        }
+    }
+}
+
+/// `None` if `addr` is null or not readable
+fn read_flock(ctx: &mut SyscallContext, addr: u64) -> Option<Flock> {
+    if addr == 0 {
+        return None;
+    }
+
+    let buf = ctx.read_memory(addr, size_of::<Flock>());
+    (buf.len() == size_of::<Flock>()).then(|| {
+        let mut lock = Flock::default();
+        // SAFETY: `Flock` is plain integers and `buf` is as long as it
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                buf.as_ptr(),
+                &mut lock as *mut Flock as *mut u8,
+                buf.len(),
+            );
+        }
+        lock
+    })
+}
@@ -0,0 +1,187 @@
+use crate::{path::PATH_SYSCALLS, ptr::ReadRemote, SyscallContext};
+use std::{
+    ffi::OsStr,
+    net::{Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+    os::unix::ffi::OsStrExt,
+    path::PathBuf,
+};
+
+/// What a syscall does with a file descriptor, see [`FdEvent::op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FdOp {
+    /// `open`, `creat`, `openat` and `openat2`
+    Open,
+    /// `socket`
+    Socket,
+    /// `dup`, `dup2` and `dup3`, a fd already open as the new one is closed by kernel
+    Dup,
+    /// `pipe` and `pipe2`, reported once for each end
+    Pipe,
+    /// `accept` and `accept4`
+    Accept,
+    /// `close`
+    Close,
+    /// `memfd_create`
+    MemFd,
+    /// `eventfd`, `timerfd_create`, `signalfd` (creating a new fd), `epoll_create`,
+    /// `inotify_init`, `userfaultfd`, `pidfd_open` and their variants, which have no path
+    Anon,
+}
+
+/// What a file descriptor refers to, see [`FdEvent::path_or_addr`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FdTarget {
+    /// file opened, resolved like
+    /// [`PathSyscallCtx::resolved_path`](crate::PathSyscallCtx::resolved_path)
+    Path(PathBuf),
+    /// IPv4 or IPv6 peer of an accepted connection
+    Inet(SocketAddr),
+    /// unix socket peer, abstract names start with `@`, empty if unnamed
+    Unix(PathBuf),
+    /// name given to `memfd_create`, shown as `/memfd:<name>` in `/proc/<pid>/fd`
+    MemFd(PathBuf),
+}
+
+/// A file descriptor created or closed by a syscall, reported by
+/// [`Interceptor::on_fd_events`](crate::Interceptor::on_fd_events).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FdEvent {
+    /// pid of the tracee
+    pub pid: i32,
+    /// syscall name, e.g. `accept4`
+    pub name: &'static str,
+    pub op: FdOp,
+    /// fd returned by the syscall, or closed by `close`
+    pub fd: i32,
+    /// fd duplicated by [`FdOp::Dup`], `None` for the others
+    pub from: Option<i32>,
+    /// `None` if unknown, e.g. a socket or an accepted connection without its address
+    pub path_or_addr: Option<FdTarget>,
+}
+
+/// size of `struct sockaddr_storage`
+const SOCKADDR_MAX: u32 = 128;
+
+/// syscalls reported by [`Interceptor::on_fd_events`](crate::Interceptor::on_fd_events)
+pub(crate) static FD_SYSCALLS: [(&str, FdOp); 25] = [
+    ("open", FdOp::Open),
+    ("creat", FdOp::Open),
+    ("openat", FdOp::Open),
+    ("openat2", FdOp::Open),
+    ("socket", FdOp::Socket),
+    ("dup", FdOp::Dup),
+    ("dup2", FdOp::Dup),
+    ("dup3", FdOp::Dup),
+    ("pipe", FdOp::Pipe),
+    ("pipe2", FdOp::Pipe),
+    ("accept", FdOp::Accept),
+    ("accept4", FdOp::Accept),
+    ("close", FdOp::Close),
+    ("memfd_create", FdOp::MemFd),
+    ("eventfd", FdOp::Anon),
+    ("eventfd2", FdOp::Anon),
+    ("timerfd_create", FdOp::Anon),
+    ("signalfd", FdOp::Anon),
+    ("signalfd4", FdOp::Anon),
+    ("epoll_create", FdOp::Anon),
+    ("epoll_create1", FdOp::Anon),
+    ("inotify_init", FdOp::Anon),
+    ("inotify_init1", FdOp::Anon),
+    ("userfaultfd", FdOp::Anon),
+    ("pidfd_open", FdOp::Anon),
+];
+
+/// events of syscall `name` handled in `ctx`, which returned `ret` successfully
+pub(crate) fn read_events(
+    ctx: &mut SyscallContext,
+    name: &'static str,
+    op: FdOp,
+    ret: i64,
+) -> Vec<FdEvent> {
+    let pid = ctx.pid();
+    let event = |fd, from, path_or_addr| FdEvent {
+        pid,
+        name,
+        op,
+        fd,
+        from,
+        path_or_addr,
+    };
+
+    match op {
+        FdOp::Open => {
+            let path = PATH_SYSCALLS
+                .iter()
+                .find(|sc| sc.name == name)
+                .map(|sc| FdTarget::Path(sc.read(ctx).resolved_path));
+            vec![event(ret as i32, None, path)]
+        }
+        FdOp::Socket => vec![event(ret as i32, None, None)],
+        // an existing signalfd is updated
+        FdOp::Anon if name.starts_with("signalfd") && ctx.arg(0) as i32 != -1 => Vec::new(),
+        FdOp::Anon => vec![event(ret as i32, None, None)],
+        FdOp::MemFd => {
+            let addr = ctx.arg(0);
+            let mut name = ctx.raw_tracee().read_bytes_with_nul(addr);
+            if name.last() == Some(&0) {
+                name.pop();
+            }
+            let name = PathBuf::from(OsStr::from_bytes(&name));
+            vec![event(ret as i32, None, Some(FdTarget::MemFd(name)))]
+        }
+        FdOp::Dup => vec![event(ret as i32, Some(ctx.arg(0) as i32), None)],
+        FdOp::Close => vec![event(ctx.arg(0) as i32, None, None)],
+        FdOp::Pipe => {
+            let fds = ctx.read_memory(ctx.arg(0), 8);
+            fds.chunks_exact(4)
+                .map(|fd| event(i32::from_ne_bytes([fd[0], fd[1], fd[2], fd[3]]), None, None))
+                .collect()
+        }
+        FdOp::Accept => {
+            let (addr, len) = (ctx.arg(1), ctx.arg(2));
+            let peer = (addr != 0 && len != 0)
+                .then(|| ctx.read_memory(len, 4))
+                .and_then(|len| Some(u32::from_ne_bytes(len.try_into().ok()?)))
+                // it's truncated if larger than the buffer, which is at most this size
+                .map(|len| ctx.read_memory(addr, len.min(SOCKADDR_MAX) as usize))
+                .and_then(|data| sockaddr(&data));
+            vec![event(ret as i32, None, peer)]
+        }
+    }
+}
+
+/// decode a `struct sockaddr` of family `AF_INET`, `AF_INET6` or `AF_UNIX`
+fn sockaddr(data: &[u8]) -> Option<FdTarget> {
+    let family = u16::from_ne_bytes(data.get(..2)?.try_into().ok()?) as i32;
+    let port = || Some(u16::from_be_bytes(data.get(2..4)?.try_into().ok()?));
+    match family {
+        libc::AF_INET => {
+            let ip: [u8; 4] = data.get(4..8)?.try_into().ok()?;
+            Some(FdTarget::Inet(SocketAddr::V4(SocketAddrV4::new(
+                Ipv4Addr::from(ip),
+                port()?,
+            ))))
+        }
+        libc::AF_INET6 => {
+            let flowinfo = u32::from_be_bytes(data.get(4..8)?.try_into().ok()?);
+            let ip: [u8; 16] = data.get(8..24)?.try_into().ok()?;
+            let scope_id = u32::from_ne_bytes(data.get(24..28)?.try_into().ok()?);
+            Some(FdTarget::Inet(SocketAddr::V6(SocketAddrV6::new(
+                Ipv6Addr::from(ip),
+                port()?,
+                flowinfo,
+                scope_id,
+            ))))
+        }
+        libc::AF_UNIX => {
+            let path = &data[2..];
+            let path = match path.split_first() {
+                // abstract name is not NUL terminated
+                Some((0, name)) => [b"@", name].concat(),
+                _ => path[..path.iter().position(|b| *b == 0).unwrap_or(path.len())].to_vec(),
+            };
+            Some(FdTarget::Unix(PathBuf::from(OsStr::from_bytes(&path))))
+        }
+        _ => None,
+    }
+}
@@ -0,0 +1,208 @@
+//! Typed wrappers for flag arguments, which can be used in `#[syscall]` functions in
+//! place of the raw integer, e.g. `flags: OpenFlags` instead of `flags: i32`.
+//!
+//! Unknown bits are retained, so writing back an unchanged value never loses anything.
+use bitflags::bitflags;
+use std::fmt::{self, Debug};
+
+bitflags! {
+    /// `flags` of `open`/`openat`.
+    ///
+    /// `O_RDONLY` is zero, use [`OpenFlags::access_mode`] to check the access mode.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct OpenFlags: i32 {
+        const O_WRONLY = libc::O_WRONLY;
+        const O_RDWR = libc::O_RDWR;
+        const O_CREAT = libc::O_CREAT;
+        const O_EXCL = libc::O_EXCL;
+        const O_NOCTTY = libc::O_NOCTTY;
+        const O_TRUNC = libc::O_TRUNC;
+        const O_APPEND = libc::O_APPEND;
+        const O_NONBLOCK = libc::O_NONBLOCK;
+        const O_DSYNC = libc::O_DSYNC;
+        const O_DIRECT = libc::O_DIRECT;
+        const O_LARGEFILE = libc::O_LARGEFILE;
+        const O_DIRECTORY = libc::O_DIRECTORY;
+        const O_NOFOLLOW = libc::O_NOFOLLOW;
+        const O_NOATIME = libc::O_NOATIME;
+        const O_CLOEXEC = libc::O_CLOEXEC;
+        const O_SYNC = libc::O_SYNC;
+        const O_PATH = libc::O_PATH;
+        const O_TMPFILE = libc::O_TMPFILE;
+    }
+}
+
+impl OpenFlags {
+    /// one of `O_RDONLY`, `O_WRONLY` or `O_RDWR`
+    pub fn access_mode(&self) -> i32 {
+        self.bits() & libc::O_ACCMODE
+    }
+}
+
+bitflags! {
+    /// `prot` of `mmap`/`mprotect`, empty means `PROT_NONE`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MapProt: i32 {
+        const PROT_READ = libc::PROT_READ;
+        const PROT_WRITE = libc::PROT_WRITE;
+        const PROT_EXEC = libc::PROT_EXEC;
+        const PROT_GROWSDOWN = libc::PROT_GROWSDOWN;
+        const PROT_GROWSUP = libc::PROT_GROWSUP;
+    }
+}
+
+bitflags! {
+    /// `type` of `socket`/`socketpair`, flags combined with the socket type.
+    ///
+    /// Socket types are not flags, use [`SockType::kind`] to check it.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct SockType: i32 {
+        const SOCK_NONBLOCK = libc::SOCK_NONBLOCK;
+        const SOCK_CLOEXEC = libc::SOCK_CLOEXEC;
+    }
+}
+
+impl SockType {
+    /// socket type without flags, e.g. `SOCK_STREAM` or `SOCK_RAW`
+    pub fn kind(&self) -> i32 {
+        self.bits() & 0xf
+    }
+}
+
+bitflags! {
+    /// `mountflags` of `mount`.
+    ///
+    /// The magic number `MS_MGC_VAL` in the upper 16 bits, used by old callers, is
+    /// retained as unknown bits.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct MountFlags: u64 {
+        const MS_RDONLY = libc::MS_RDONLY;
+        const MS_NOSUID = libc::MS_NOSUID;
+        const MS_NODEV = libc::MS_NODEV;
+        const MS_NOEXEC = libc::MS_NOEXEC;
+        const MS_SYNCHRONOUS = libc::MS_SYNCHRONOUS;
+        const MS_REMOUNT = libc::MS_REMOUNT;
+        const MS_MANDLOCK = libc::MS_MANDLOCK;
+        const MS_DIRSYNC = libc::MS_DIRSYNC;
+        const MS_NOSYMFOLLOW = libc::MS_NOSYMFOLLOW;
+        const MS_NOATIME = libc::MS_NOATIME;
+        const MS_NODIRATIME = libc::MS_NODIRATIME;
+        const MS_BIND = libc::MS_BIND;
+        const MS_MOVE = libc::MS_MOVE;
+        const MS_REC = libc::MS_REC;
+        const MS_SILENT = libc::MS_SILENT;
+        const MS_POSIXACL = libc::MS_POSIXACL;
+        const MS_UNBINDABLE = libc::MS_UNBINDABLE;
+        const MS_PRIVATE = libc::MS_PRIVATE;
+        const MS_SLAVE = libc::MS_SLAVE;
+        const MS_SHARED = libc::MS_SHARED;
+        const MS_RELATIME = libc::MS_RELATIME;
+        const MS_I_VERSION = libc::MS_I_VERSION;
+        const MS_STRICTATIME = libc::MS_STRICTATIME;
+        const MS_LAZYTIME = libc::MS_LAZYTIME;
+    }
+}
+
+impl MountFlags {
+    /// whether it changes propagation type of an existing mount instead of mounting a
+    /// filesystem, `source` and `fstype` are ignored by kernel then
+    pub fn is_propagation(&self) -> bool {
+        self.intersects(Self::MS_SHARED | Self::MS_PRIVATE | Self::MS_SLAVE | Self::MS_UNBINDABLE)
+    }
+}
+
+bitflags! {
+    /// `flags` of `umount2`.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct UmountFlags: i32 {
+        const MNT_FORCE = libc::MNT_FORCE;
+        const MNT_DETACH = libc::MNT_DETACH;
+        const MNT_EXPIRE = libc::MNT_EXPIRE;
+        const UMOUNT_NOFOLLOW = libc::UMOUNT_NOFOLLOW;
+    }
+}
+
+bitflags! {
+    /// `mask` of `statx`, fields requested by the caller, or filled by kernel as `stx_mask`
+    /// of [`Statx`](crate::Statx).
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct StatxMask: u32 {
+        const STATX_TYPE = libc::STATX_TYPE;
+        const STATX_MODE = libc::STATX_MODE;
+        const STATX_NLINK = libc::STATX_NLINK;
+        const STATX_UID = libc::STATX_UID;
+        const STATX_GID = libc::STATX_GID;
+        const STATX_ATIME = libc::STATX_ATIME;
+        const STATX_MTIME = libc::STATX_MTIME;
+        const STATX_CTIME = libc::STATX_CTIME;
+        const STATX_INO = libc::STATX_INO;
+        const STATX_SIZE = libc::STATX_SIZE;
+        const STATX_BLOCKS = libc::STATX_BLOCKS;
+        const STATX_BASIC_STATS = libc::STATX_BASIC_STATS;
+        const STATX_BTIME = libc::STATX_BTIME;
+        const STATX_MNT_ID = libc::STATX_MNT_ID;
+    }
+}
+
+/// `domain` of `socket`/`socketpair`, i.e. the address family.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Domain(pub i32);
+
+macro_rules! consts {
+    ($t: ident, $($name: ident),*) => {
+        impl $t {
+            $(pub const $name: Self = Self(libc::$name);)*
+        }
+
+        impl Debug for $t {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                match self.0 {
+                    $(libc::$name => f.write_str(stringify!($name)),)*
+                    v => write!(f, "{}({})", stringify!($t), v),
+                }
+            }
+        }
+    };
+}
+
+consts!(
+    Domain,
+    AF_UNIX,
+    AF_INET,
+    AF_INET6,
+    AF_NETLINK,
+    AF_PACKET,
+    AF_BLUETOOTH,
+    AF_ALG,
+    AF_VSOCK,
+    AF_XDP
+);
+
+/// `option` of `prctl`, the meaning of other arguments depends on it.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct PrctlOption(pub i32);
+
+consts!(
+    PrctlOption,
+    PR_SET_PDEATHSIG,
+    PR_GET_PDEATHSIG,
+    PR_GET_DUMPABLE,
+    PR_SET_DUMPABLE,
+    PR_GET_KEEPCAPS,
+    PR_SET_KEEPCAPS,
+    PR_SET_NAME,
+    PR_GET_NAME,
+    PR_GET_SECCOMP,
+    PR_SET_SECCOMP,
+    PR_CAPBSET_READ,
+    PR_CAPBSET_DROP,
+    PR_GET_SECUREBITS,
+    PR_SET_SECUREBITS,
+    PR_SET_PTRACER,
+    PR_SET_CHILD_SUBREAPER,
+    PR_GET_CHILD_SUBREAPER,
+    PR_SET_NO_NEW_PRIVS,
+    PR_GET_NO_NEW_PRIVS,
+    PR_SET_MM,
+    PR_CAP_AMBIENT
+);
@@ -0,0 +1,66 @@
+use crate::{
+    ptr::{pod_bytes, Pod},
+    InterceptError, SyscallContext,
+};
+use std::mem::size_of;
+
+/// An `ioctl` handled by [`Interceptor::on_ioctl`](crate::Interceptor::on_ioctl), with
+/// `argp` decoded as `T`.
+#[derive(Debug, Clone)]
+pub struct IoctlCtx<T: Pod> {
+    /// pid of the tracee
+    pub pid: i32,
+    pub fd: i32,
+    pub request: u64,
+    /// raw third argument
+    pub argp: u64,
+    /// `T` pointed by `argp`, `None` if it's null or not readable, e.g. a value passed
+    /// directly. Changes are written back into target after the handler returned.
+    pub arg: Option<T>,
+    origin: Option<T>,
+}
+
+impl<T: Pod> IoctlCtx<T> {
+    /// decode the `ioctl` being handled in `ctx` if its request is `request`, only the
+    /// low 32 bits are compared as kernel does.
+    pub(crate) fn read(ctx: &mut SyscallContext, request: u64) -> Option<Self> {
+        if ctx.arg(1) as u32 != request as u32 {
+            return None;
+        }
+
+        let argp = ctx.arg(2);
+        let arg = (argp != 0)
+            .then(|| ctx.read_memory(argp, size_of::<T>()))
+            .filter(|buf| buf.len() == size_of::<T>())
+            .map(|buf| {
+                let mut v = T::default();
+                unsafe {
+                    std::ptr::copy_nonoverlapping(
+                        buf.as_ptr(),
+                        &mut v as *mut T as *mut u8,
+                        buf.len(),
+                    );
+                }
+                v
+            });
+
+        Some(Self {
+            pid: ctx.pid(),
+            fd: ctx.arg(0) as i32,
+            request: ctx.arg(1),
+            argp,
+            arg,
+            origin: arg,
+        })
+    }
+
+    /// write `arg` back into target if changed by handler
+    pub(crate) fn store(&self, ctx: &mut SyscallContext) -> Result<(), InterceptError> {
+        match (&self.arg, &self.origin) {
+            (Some(arg), Some(origin)) if pod_bytes(&[*arg]) != pod_bytes(&[*origin]) => {
+                ctx.write_memory(self.argp, pod_bytes(&[*arg]))
+            }
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,47 @@
+use std::{ffi::OsString, path::PathBuf, process::Command};
+
+/// How the child is launched, captured from the [`Command`] passed to
+/// [`Interceptor::new`](crate::Interceptor::new), see
+/// [`Interceptor::launch_spec`](crate::Interceptor::launch_spec).
+///
+/// Stdio settings can not be read from a `Command`, so they are not part of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LaunchSpec {
+    pub program: OsString,
+    pub args: Vec<OsString>,
+    /// environment variables set (`Some`) or removed (`None`) on top of the ones
+    /// inherited from the interceptor, in the order they are applied
+    pub envs: Vec<(OsString, Option<OsString>)>,
+    /// working directory, `None` if inherited
+    pub current_dir: Option<PathBuf>,
+}
+
+impl LaunchSpec {
+    pub fn from_command(cmd: &Command) -> Self {
+        Self {
+            program: cmd.get_program().to_owned(),
+            args: cmd.get_args().map(ToOwned::to_owned).collect(),
+            envs: cmd
+                .get_envs()
+                .map(|(key, value)| (key.to_owned(), value.map(ToOwned::to_owned)))
+                .collect(),
+            current_dir: cmd.get_current_dir().map(ToOwned::to_owned),
+        }
+    }
+
+    /// a new [`Command`] launching the same way, stdio is inherited
+    pub fn to_command(&self) -> Command {
+        let mut cmd = Command::new(&self.program);
+        cmd.args(&self.args);
+        for (key, value) in &self.envs {
+            match value {
+                Some(value) => cmd.env(key, value),
+                None => cmd.env_remove(key),
+            };
+        }
+        if let Some(dir) = &self.current_dir {
+            cmd.current_dir(dir);
+        }
+        cmd
+    }
+}
@@ -5,7 +5,7 @@
 //! Write a function whose signature is same as a syscall, and mark it as `#[syscall]`,
 //! and you are done.
 //!
-//! ```rust
+//! ```rust,ignore
 //! #[syscall]
 //! fn openat(dfd: i32, mut filename: *const c_char, flags: i32, mode: i32) -> i32 {
 //!     // do something before syscall, logging, changing arguments, etc.
@@ -23,6 +23,55 @@
 //! We use "LD_PRELOAD" trick to insert a so into target process to malloc extra memory
 //! needed when modified a pointer argument which has larger length.
 //!
+//! The "LD_PRELOAD" is inherited by programs the child executes, use
+//! `inherit_preload(false)` to keep it only in the spawned child. If handlers never change
+//! a pointer argument to a new one, use `without_remote_mem()` to skip the injection.
+//!
+//! If `libinter_mem.so` is not found next to the executable (e.g. built without the `mem`
+//! sub-crate), it's not injected either, only changing a pointer argument to a new one fails.
+//!
+//! ## Syscalls served by vDSO
+//! `clock_gettime`, `gettimeofday`, `time` and `getcpu` are usually served by the vDSO
+//! without entering kernel, so handlers of them are never called and a warning is logged.
+//! Use `disable_vdso(true)` to hide the vDSO from the child, libc then makes real syscalls.
+//!
+//! ## Handling futex
+//! `futex` is made very often by multithreaded programs, observe-only handlers are safe but
+//! slow it down. Blocking it or changing its arguments can easily deadlock the target, e.g.
+//! a lost `FUTEX_WAKE` leaves waiters sleeping forever. See example "futex_count".
+//!
+//! ## Children of the target
+//! Processes forked by the target are traced too, and the target still waits for them as
+//! usual: the kernel reports their exit to the interceptor first, then to the target, so
+//! `wait4`/`waitpid` and `SIGCHLD` of the target get the real exit status.
+//!
+//! ## Output of child
+//! Stdio settings of the `Command` passed to `Interceptor::new` are kept when it's spawned,
+//! e.g. redirecting stdout to a file. Use `capture_output()` to read stdout and stderr of the
+//! child from the interceptor. See example "capture_output".
+//! A child spawned again by `respawn()` to run the command once more inherits stdio of the
+//! interceptor instead, but captured output is still captured.
+//!
+//! ## Async event stream
+//! With feature `tokio`, `EventStream` runs the interceptor on its own thread and streams
+//! syscalls to async code, which replies a `Decision` for each one. See example
+//! "async_supervisor".
+//!
+//! ## Cargo features
+//! Less common argument types can be left out to keep the binary small, all are enabled by
+//! default:
+//! - `struct-args`: `FixedArray`, e.g. `int fds[2]` of `pipe2`
+//! - `iovec`: `IoVecs` of `readv`/`writev` and their variants
+//! - `dirent`: `DirentBuf` of `getdents64`
+//! - `statx`: `Statx` of `statx`
+//!
+//! Syscall numbers and names come from a table bundled for the target architecture
+//! (x86_64, aarch64 or x86, the latter two are generated by `src/data/generate.py`), which
+//! may drift from the kernel of a distro. Enable `host-syscalls` to generate it from kernel
+//! headers of the build host (`asm/unistd_64.h`, x86_64 only) instead, the bundled one is
+//! still used if they're not found. The aarch64 and x86 tables are not used yet, as
+//! registers are only read and written for x86_64, they're kept for porting that layer.
+//!
 //! ## Remove dependency libgcc_s.so.1
 //! Some glibc released without `libgcc_s.so.1`, we removed this dependency using link
 //! script "linker_without_libgcc.wrap".
@@ -52,68 +101,540 @@
 //! ### usage
 //! you can use helper function [`read_ptr_to_ptr`] to read content from converted ptr.
 //! and use [`write_ptr_to_ptr`] to write back.
+//! If entries need to be added or removed (e.g. `execve`'s `envp`), build a new one with
+//! [`alloc_ptr_to_ptr`] and assign it to the argument, the whole array will be rebuilt
+//! in target. Entries must not be empty or contain NUL, and a pointer not built by it is
+//! rejected.
 //!
-use anyhow::Result;
+use anyhow::{Context as _, Result};
+use clone::proc_tgid;
+pub use clone::CloneArgs;
+use context::enter_context;
+pub use context::{with_context, ArgWrite, SyscallContext};
+#[cfg(feature = "dirent")]
+pub use dirent::{Dirent, DirentBuf};
+pub use error::{InterceptError, TimedOut};
+pub use event::{as_errno, ArgKind, ArgUpdate, Change, Decision, Phase, StepEvent, SyscallEvent};
+pub use fcntl::{FcntlArg, FcntlCtx, Flock};
+use fd::{read_events, FD_SYSCALLS};
+pub use fd::{FdEvent, FdOp, FdTarget};
+use flags::{MapProt, PrctlOption};
+use inter_mem::MEM_PROT_ENV;
+pub use ioctl::IoctlCtx;
+pub use launch::LaunchSpec;
+pub use maps::MapEntry;
 use once_cell::sync::Lazy;
-use paste::paste;
-use pete::{Ptracer, Restart, Stop, Tracee};
-pub use ptr::{read_ptr_to_ptr, write_ptr_to_ptr};
-use ptr::{MayBePtr, Number, Ptr, Read, RemoteMem, Write};
+pub use output::CapturedOutput;
+use parking_lot::Mutex;
+use path::{forget_cwd, set_track_cwd, PATH_SYSCALLS};
+pub use path::{PathOp, PathSyscallCtx};
+/// re-exported for [`SyscallContext::raw_tracee`]
+pub use pete;
+use pete::{Pid, Ptracer, Registers, Restart, Signal, Stop, Tracee};
+use privilege::{read_change, PRIVILEGE_SYSCALLS};
+pub use privilege::{PrivilegeChange, PrivilegeKind};
+#[cfg(feature = "struct-args")]
+pub use ptr::FixedArray;
+pub use ptr::{
+    alloc_ptr_to_ptr, bytes_of, origin_of, read_ptr_to_ptr, write_ptr_to_ptr, MemStats, Pod,
+    RemoteAllocator,
+};
+use ptr::{
+    discard_writes, drop_ptr_arrays, remove_ptr_to_ptr_entries, set_origins, set_remote_mem_guard,
+    set_verify_writes, take_decode_error, take_mem_stats, write_arg_string, MayBePtr, Ptr, Read,
+    RemoteMem, SharedAllocator, Write, WriteRemote,
+};
+#[cfg(feature = "iovec")]
+pub use ptr::{IoVec, IoVecs};
 use rand::Rng;
-use std::{cell::RefCell, collections::HashMap, env::current_exe, process::Command, rc::Rc};
-use syscall::{ReturnVariant, ReturnVariantWrapper, SysCall, SysCallWrapper};
+use register::Number;
+pub use register::RegisterValue;
+pub use simulate::{Simulated, Simulator};
+#[cfg(any(debug_assertions, feature = "debug-state"))]
+pub use state::{BlockedCall, PendingState};
+#[cfg(feature = "statx")]
+pub use statx::{Statx, StatxTimestamp};
+use std::{
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    env::current_exe,
+    mem::take,
+    os::unix::ffi::OsStrExt,
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+    rc::Rc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{channel, RecvTimeoutError},
+        Arc,
+    },
+    thread::{self, JoinHandle},
+    time::{Duration, Instant},
+};
+#[cfg(feature = "tokio")]
+pub use stream::{EventStream, PendingEvent};
+pub use syscall::Outcome;
+use syscall::{
+    Handler, PartialHandler, ReturnVariant, ReturnVariantWrapper, SysCall, SysCallWrapper,
+};
 /// A proc-macro that turns a rust fn into a syscall.
 ///
 /// See more details in examples.
 pub use syscall_attr::syscall;
-use tracing::debug;
+use trace::Tracer;
+use tracing::{debug, warn};
+use vdso::{hide_vdso, VDSO_SYSCALLS};
 
+mod clone;
+mod context;
+#[cfg(feature = "dirent")]
+mod dirent;
+mod error;
+mod event;
+mod fcntl;
+mod fd;
+pub mod flags;
+mod ioctl;
+mod launch;
+mod maps;
+mod output;
+mod path;
+mod privilege;
 mod ptr;
+mod register;
+mod simulate;
+#[cfg(any(debug_assertions, feature = "debug-state"))]
+mod state;
+#[cfg(feature = "statx")]
+mod statx;
+#[cfg(feature = "tokio")]
+mod stream;
 #[doc(hidden)]
 pub mod syscall;
+mod trace;
+mod vdso;
+
+type EventSink = Box<dyn FnMut(&SyscallEvent)>;
+type NewTraceeHandler = Box<dyn FnMut(i32, Option<i32>) -> bool>;
+type StepHandler = Box<dyn FnMut(&StepEvent)>;
+type ControlOp = Box<dyn FnOnce(&mut Interceptor) + Send>;
+/// fake sysno, return value, registers when entered and memory written at exit of a
+/// blocked syscall
+type BlockCall = (u64, u64, Registers, Vec<(u64, Vec<u8>)>);
+type DecodeErrorHandler = Rc<RefCell<dyn FnMut(&str, usize, &InterceptError) -> bool>>;
+type TaskFilter = Box<dyn FnMut(i32, i32) -> bool>;
+
+struct RawHandler {
+    /// `None` matches all syscalls
+    sysnos: Option<HashSet<u64>>,
+    f: Box<dyn FnMut(&SyscallEvent) -> Decision>,
+}
+
+/// What to do when a syscall already handled gets another handler registered by
+/// [`Interceptor::on`], [`Interceptor::on_fn`] or [`Interceptor::on_return_of`], see
+/// [`Interceptor::on_duplicate`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DuplicatePolicy {
+    /// chain the new handler after the existing ones
+    #[default]
+    Chain,
+    /// remove the existing handlers, only the new one is called
+    Replace,
+    /// ignore the new handler with a warning, the existing ones are kept
+    Reject,
+}
+
+/// Where calls are counted by [`Interceptor::on_nth`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NthScope {
+    /// calls of all tracees together
+    Session,
+    /// calls of each tracee (thread) separately, so the handler may run once for each
+    PerTracee,
+}
 
 /// Provide the main functionality for intercepting.
 pub struct Interceptor {
     ptracer: Ptracer,
+    cmd: Option<Command>,
+    /// see [`Interceptor::launch_spec`]
+    launch: LaunchSpec,
+    pid: Option<Pid>,
     syscalls: Vec<SysCallWrapper>,
-    block_calls: HashMap<u64, u64>,
-    contexts: Rc<RefCell<HashMap<String, PackedContext>>>,
-    remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+    /// id of the next handler in `syscalls`
+    next_id: u64,
+    duplicates: DuplicatePolicy,
+    /// see [`Interceptor::on_decode_error`]
+    decode_error: Option<DecodeErrorHandler>,
+    /// errno returned by syscalls not allowed, see [`Interceptor::default_block`]
+    default_block: Option<i32>,
+    /// allowed by [`Interceptor::allow`]
+    allowed: HashSet<u64>,
+    /// fake sysno, return value and registers when entered of the syscall blocked by
+    /// each tracee, registers are restored at exit except the return value
+    block_calls: HashMap<i32, BlockCall>,
+    /// new sysno and registers when entered of the syscall redirected by each tracee
+    redirects: HashMap<i32, (u64, Registers)>,
+    /// keyed by pid and handler id
+    contexts: Rc<RefCell<HashMap<(i32, u64), PackedContext>>>,
+    remote_mem: SharedAllocator,
+    /// remote memory is injected into the child by `preload`, rather than set by user
+    injects_remote_mem: bool,
+    preload: PathBuf,
+    remote_mem_prot: MapProt,
+    remote_mem_guard: Option<Duration>,
+    reset_remote_mem: bool,
+    inherit_preload: bool,
+    dry_run: bool,
+    read_only: bool,
+    best_effort: bool,
+    verify_writes: bool,
+    max_retries: usize,
+    mem_stats: MemStats,
+    /// syscalls retried in a row by each tracee, see [`Decision::Retry`]
+    retries: HashMap<i32, usize>,
+    disable_vdso: bool,
+    track_cwd: bool,
+    sinks: Vec<EventSink>,
+    raw_handlers: Vec<RawHandler>,
+    /// id and time of the syscall entered by each tracee, see [`SyscallEvent::call_id`]
+    calls: HashMap<i32, (u64, Instant)>,
+    last_call_id: u64,
+    /// how arguments of the syscall being entered are changed by handlers
+    arg_updates: [ArgUpdate; 6],
+    /// id of handlers called at enter and the raw arguments they got, in calling order
+    enter_args: HashMap<i32, Vec<(u64, [u64; 6])>>,
+    clone_flags: HashMap<i32, u64>,
+    tgids: HashMap<i32, i32>,
+    new_tracee_handlers: Vec<NewTraceeHandler>,
+    /// see [`Interceptor::filter_tasks`]
+    task_filter: Option<TaskFilter>,
+    /// tasks whose syscall being handled is not intercepted
+    skipped: HashSet<i32>,
+    /// tracees to detach at their next stop
+    untraced: HashSet<i32>,
+    step_handlers: Vec<StepHandler>,
+    /// steps requested at syscall enter, begin after the syscall returned
+    pending_steps: HashMap<i32, usize>,
+    /// steps left of tracees in single-step mode
+    steps: HashMap<i32, usize>,
+    /// changes requested by [`Controller`]s, applied at the next stop
+    control: Arc<Mutex<Vec<ControlOp>>>,
+    /// see [`Interceptor::capture_output`]
+    output: Option<CapturedOutput>,
+    /// threads draining stdout and stderr of the child into `output`
+    output_readers: Vec<JoinHandle<()>>,
 }
 
-struct PackedContext(
-    Box<dyn Context>,
-    Box<dyn Context>,
-    Box<dyn Context>,
-    Box<dyn Context>,
-    Box<dyn Context>,
-    Box<dyn Context>,
-);
+/// kept from enter until exit of a syscall handled by a typed handler.
+struct PackedContext {
+    /// arguments decoded at enter, `None` if none of them is changed by the handler
+    #[allow(dead_code)]
+    args: Option<[Box<dyn Context>; 6]>,
+    /// origin address of pointer arguments
+    buffers: [Option<u64>; 6],
+}
+
+/// what typed handlers did to a syscall at enter, see `Interceptor::run_pre`.
+enum Entered {
+    /// sent to kernel, `changed` if arguments are returned by any handler, and ids of
+    /// handlers called with the raw arguments they got, in calling order
+    Sent {
+        handlers: Vec<(u64, [u64; 6])>,
+        changed: bool,
+    },
+    /// blocked with the raw return value, and memory to write for it
+    Blocked {
+        ret: u64,
+        writes: Vec<(u64, Vec<u8>)>,
+    },
+}
 
 trait Context {}
 
 impl<T> Context for MayBePtr<T> {}
 
 impl Interceptor {
-    /// create child process by specific a [`std::process::Command`]
-    pub fn new(mut cmd: Command) -> Result<Self> {
-        let mut ptracer = Ptracer::new();
-        cmd.env(
-            "LD_PRELOAD",
-            current_exe()?.with_file_name("libinter_mem.so"),
-        );
-        let _child = ptracer.spawn(cmd)?;
+    /// create child process by specific a [`std::process::Command`], the child is
+    /// spawned when [`Interceptor::run`] is called.
+    pub fn new(cmd: Command) -> Result<Self, InterceptError> {
+        let launch = LaunchSpec::from_command(&cmd);
+        let preload = current_exe()
+            .map_err(|e| InterceptError::Other(e.into()))?
+            .with_file_name("libinter_mem.so");
 
         Ok(Self {
-            ptracer,
+            ptracer: Ptracer::new(),
+            cmd: Some(cmd),
+            launch,
+            pid: None,
             syscalls: Vec::new(),
+            next_id: 0,
+            duplicates: DuplicatePolicy::default(),
+            decode_error: None,
+            default_block: None,
+            allowed: HashSet::new(),
             block_calls: HashMap::new(),
+            redirects: HashMap::new(),
             contexts: Rc::new(RefCell::new(HashMap::new())),
             remote_mem: Rc::new(RefCell::new(None)),
+            injects_remote_mem: false,
+            preload,
+            remote_mem_prot: MapProt::PROT_READ | MapProt::PROT_WRITE,
+            remote_mem_guard: None,
+            reset_remote_mem: true,
+            inherit_preload: true,
+            dry_run: false,
+            read_only: false,
+            best_effort: false,
+            verify_writes: false,
+            max_retries: 16,
+            mem_stats: MemStats::default(),
+            retries: HashMap::new(),
+            disable_vdso: false,
+            track_cwd: false,
+            sinks: Vec::new(),
+            raw_handlers: Vec::new(),
+            calls: HashMap::new(),
+            last_call_id: 0,
+            arg_updates: Default::default(),
+            enter_args: HashMap::new(),
+            clone_flags: HashMap::new(),
+            tgids: HashMap::new(),
+            new_tracee_handlers: Vec::new(),
+            task_filter: None,
+            skipped: HashSet::new(),
+            untraced: HashSet::new(),
+            step_handlers: Vec::new(),
+            pending_steps: HashMap::new(),
+            steps: HashMap::new(),
+            control: Arc::new(Mutex::new(Vec::new())),
+            output: None,
+            output_readers: Vec::new(),
         })
     }
 
+    /// register a handler receiving every syscall enter and exit of all tracees,
+    /// it only observes and can not change anything.
+    ///
+    /// The exit event carries the time elapsed since the enter, which can be used for
+    /// profiling syscall latency.
+    pub fn on_event(&mut self, f: impl FnMut(&SyscallEvent) + 'static) -> &mut Self {
+        self.sinks.push(Box::new(f));
+        self
+    }
+
+    /// write a strace-like line `tgid pid name(args) = ret` for each syscall to the file
+    /// at `path`, e.g. `1234 1234 openat(AT_FDCWD, "/etc/hosts", 0x80000, 0) = 3`, failed
+    /// ones end with `= -errno (message)`.
+    ///
+    /// It's a handler registered by [`Interceptor::on_event`]. Arguments are formatted by
+    /// their [`ArgKind`], only strings are read from the tracee, once at enter, other
+    /// pointers are printed as addresses. Lines are written when calls return, so the
+    /// ones of threads may not be in the order they entered, `exit` and `exit_group` are
+    /// written at enter with `= ?`.
+    pub fn trace_to(&mut self, path: impl AsRef<Path>) -> Result<&mut Self, InterceptError> {
+        let path = path.as_ref();
+        let mut tracer = Tracer::create(path)
+            .with_context(|| format!("create trace file {}", path.display()))?;
+        Ok(self.on_event(move |e| tracer.on_event(e)))
+    }
+
+    /// register a handler called when a new tracee appears, with its pid and the pid of
+    /// its parent, which is `None` for the spawned child.
+    ///
+    /// New tracees are created by `fork`, `vfork` and `clone` (including threads) of
+    /// traced ones. If any handler returns `false`, the new tracee is detached and runs
+    /// without being intercepted, so are the ones it creates afterwards.
+    pub fn on_new_tracee(
+        &mut self,
+        f: impl FnMut(i32, Option<i32>) -> bool + 'static,
+    ) -> &mut Self {
+        self.new_tracee_handlers.push(Box::new(f));
+        self
+    }
+
+    /// register a handler called at each single-step stop, which is requested by
+    /// [`SyscallContext::single_step`] in a `#[syscall]` function.
+    pub fn on_step(&mut self, f: impl FnMut(&StepEvent) + 'static) -> &mut Self {
+        self.step_handlers.push(Box::new(f));
+        self
+    }
+
+    /// whether programs executed by the child inherit the injected `LD_PRELOAD`, default
+    /// is `true`.
+    ///
+    /// When disabled, the injected entry is removed from `envp` of every `execve` and
+    /// `execveat` in place, so only the spawned child loads `libinter_mem.so`, and
+    /// programs executed later can not use remote memory. Note the caller's environment
+    /// array is modified, it also takes effect if `execve` fails.
+    pub fn inherit_preload(&mut self, inherit: bool) -> &mut Self {
+        self.inherit_preload = inherit;
+        self
+    }
+
+    /// do not inject `libinter_mem.so` into the child.
+    ///
+    /// It's useful if handlers only read arguments or change them in place, which avoids
+    /// the injection and its startup cost. Changing a pointer argument to a new one
+    /// returns an error in this mode.
+    pub fn without_remote_mem(&mut self) -> &mut Self {
+        *self.remote_mem.borrow_mut() = Some(Box::new(RemoteMem::disabled()));
+        self
+    }
+
+    /// run handlers without applying their changes to the tracee, default is `false`.
+    ///
+    /// Changed arguments, blocking, changed return values and memory written into
+    /// target are discarded and reported by [`SyscallEvent::discarded`] to handlers
+    /// registered by [`Interceptor::on_event`], syscalls are always sent to kernel
+    /// unchanged. It's useful to validate a policy against a real target safely.
+    pub fn dry_run(&mut self, enable: bool) -> &mut Self {
+        self.dry_run = enable;
+        self
+    }
+
+    /// guarantee the target runs as it would untraced: registers and memory of the
+    /// target are never written, and handlers can only observe, e.g. for auditing in
+    /// production.
+    ///
+    /// Same as [`Interceptor::dry_run`], changes made by handlers (including blocking) are
+    /// discarded and reported by [`SyscallEvent::discarded`], and a warning is logged for
+    /// each of them. `libinter_mem.so` is not injected as [`Interceptor::without_remote_mem`]
+    /// does, and [`Interceptor::disable_vdso`] is ignored. Changes made through
+    /// [`SyscallContext::raw_tracee`] are not covered.
+    pub fn read_only(&mut self) -> &mut Self {
+        self.read_only = true;
+        self.without_remote_mem()
+    }
+
+    /// read back memory written into target and compare, a warning is logged if they
+    /// differ, default is `false`.
+    ///
+    /// It's a debug aid to confirm changes made by handlers (e.g. pointer arguments
+    /// rewritten in place or in remote memory) took effect, at the cost of extra reads.
+    pub fn verify_writes(&mut self, enable: bool) -> &mut Self {
+        self.verify_writes = enable;
+        self
+    }
+
+    /// log failures applying changes of handlers and keep tracing, instead of returning
+    /// them from [`Interceptor::run`], default is `false`.
+    ///
+    /// When memory or registers of a tracee can not be written (e.g. remote memory not
+    /// available or a transient ptrace error), a warning is logged and the syscall proceeds
+    /// unmodified, its exit handlers are skipped. Memory already written for it is not
+    /// restored. [`InterceptError::RemoteMemExhausted`] is still returned if
+    /// [`Interceptor::remote_mem_guard`] is set.
+    pub fn best_effort(&mut self, enable: bool) -> &mut Self {
+        self.best_effort = enable;
+        self
+    }
+
+    /// how many times in a row a syscall can be retried by [`Decision::Retry`] before
+    /// further retries are ignored with a warning, default is `16`, `0` disables retries.
+    pub fn max_retries(&mut self, n: usize) -> &mut Self {
+        self.max_retries = n;
+        self
+    }
+
+    /// hide the vDSO from programs executed by the child, default is `false`.
+    ///
+    /// Syscalls like `clock_gettime` and `gettimeofday` are usually served by the vDSO in
+    /// user space without entering kernel, so handlers of them are never called. When
+    /// enabled, the vDSO entry is removed from the auxiliary vector after each exec, libc
+    /// then falls back to real syscalls which can be intercepted, at the cost of slower
+    /// time queries. Programs reading the vDSO by other means are not affected.
+    pub fn disable_vdso(&mut self, enable: bool) -> &mut Self {
+        self.disable_vdso = enable;
+        self
+    }
+
+    /// cache the working directory of each tracee, default is `false`.
+    ///
+    /// Relative paths of [`Interceptor::on_path_syscalls`] and
+    /// [`SyscallContext::cwd`] read `/proc/<pid>/cwd` on each call, which is a lookup per
+    /// `openat`. When enabled, it's read once per tracee and read again after the tracee
+    /// or another thread of its process changed it by `chdir` or `fchdir`, it's kept over
+    /// `execve` as kernel does.
+    ///
+    /// The cache may be stale if it's changed in a way not seen, e.g. by a thread sharing
+    /// it but not traced (see [`Interceptor::on_new_tracee`]), or a syscall entered but
+    /// not returned yet by another thread. Threads not sharing it (made by `clone` without
+    /// `CLONE_FS` or `unshare`) are not told apart from the others.
+    pub fn track_cwd(&mut self, enable: bool) -> &mut Self {
+        self.track_cwd = enable;
+        self
+    }
+
+    /// protections of the memory block injected into the child, default is
+    /// `PROT_READ | PROT_WRITE`. The block is page aligned, `PROT_EXEC` can be added to
+    /// write code into it, it falls back to the default if denied by system policy.
+    pub fn remote_mem_prot(&mut self, prot: MapProt) -> &mut Self {
+        self.remote_mem_prot = prot;
+        self
+    }
+
+    /// allocate memory in the tracee by `allocator` when a pointer argument is changed to
+    /// a new one, instead of the ring over the block injected by `libinter_mem.so`, e.g.
+    /// an arena below the stack pointer or a block mapped by the tracee itself.
+    ///
+    /// `libinter_mem.so` is not injected, as memory is supplied by `allocator`, and
+    /// [`Interceptor::remote_mem_guard`] does not apply. [`RemoteAllocator::reset`] is
+    /// called before handlers of each syscall run, and [`RemoteAllocator::release`] after
+    /// it returned. Like the default one, a single allocator serves all tracees.
+    pub fn remote_allocator(&mut self, allocator: impl RemoteAllocator + 'static) -> &mut Self {
+        *self.remote_mem.borrow_mut() = Some(Box::new(allocator));
+        self
+    }
+
+    /// fail allocations of remote memory wrapping around within `window` since it last
+    /// wrapped, with [`InterceptError::RemoteMemExhausted`] returned by
+    /// [`Interceptor::run`], instead of reusing space which may still be in use, e.g. by
+    /// strings set by handlers for syscalls not returned yet.
+    ///
+    /// Remote memory is a small block reused as a ring, allocations beyond it made for a
+    /// single syscall always fail. Without it, a warning is logged if it wraps around
+    /// within a second, which usually means handlers allocate more than it can hold.
+    pub fn remote_mem_guard(&mut self, window: Duration) -> &mut Self {
+        self.remote_mem_guard = Some(window);
+        self
+    }
+
+    /// start remote memory over from its beginning once every syscall using it
+    /// returned, default is `true`.
+    ///
+    /// Space allocated for a syscall is only read by kernel until it returns, so each
+    /// syscall usually gets the whole block instead of the ring slowly filling up and
+    /// wrapping around. Allocations are kept per tracee, the block is not reused while a
+    /// syscall of another tracee using it is still in flight, e.g. a blocking `openat` of
+    /// another thread. When disabled, it's only reused by wrapping around. It's passed to
+    /// [`RemoteAllocator::release`].
+    pub fn reset_remote_mem(&mut self, enable: bool) -> &mut Self {
+        self.reset_remote_mem = enable;
+        self
+    }
+
     /// register syscall to interceptor
+    ///
+    /// A warning is logged if the handler does not declare the same number of arguments
+    /// as the syscall takes, the missing ones are passed to kernel unchanged.
+    ///
+    /// Multiple handlers of a syscall are chained by default (see
+    /// [`Interceptor::on_duplicate`] to replace or reject them), e.g. a logger and a policy:
+    /// - before the syscall, they run in registration order, each one gets the
+    ///   arguments changed by the previous ones;
+    /// - the first one blocking the syscall wins, the remaining ones are skipped, and no
+    ///   code after `real!()` runs;
+    /// - after the syscall, the ones called before it run in reverse order, each one
+    ///   gets the return value changed by the next one.
+    ///
+    /// `exit` and `exit_group` never return, the code after `real!()` is a no-op for
+    /// them. The one of `execve` runs in the new program if succeeded.
+    ///
+    /// `rt_sigreturn` restores all registers from the signal frame, changes to it (and
+    /// memory written while handling it) would corrupt the target, so they are discarded
+    /// with a warning, handlers can only observe it.
     pub fn on<R, A1, A2, A3, A4, A5, A6>(
         &mut self,
         syscall: &'static SysCall<R, A1, A2, A3, A4, A5, A6>,
@@ -133,167 +654,2116 @@ impl Interceptor {
         MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
         MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
     {
+        self.register(
+            syscall.name,
+            syscall.arity(),
+            move |a1, a2, a3, a4, a5, a6| syscall.call_pre(a1, a2, a3, a4, a5, a6),
+            move |r| syscall.call_post(r),
+        )
+    }
+
+    /// same as [`Interceptor::on`], but the handler only runs when `predicate` returns
+    /// `true` for raw arguments of the syscall, e.g. `|args| args[0] == 1` for `write` to
+    /// stdout, or the flags of `openat`. Pointers are not decoded before it's called, so
+    /// other syscalls are sent to kernel at low cost, as if the handler was not
+    /// registered.
+    ///
+    /// Arguments are the ones changed by handlers registered before it, and no code after
+    /// `real!()` runs if it returned `false`.
+    pub fn on_filtered<R, A1, A2, A3, A4, A5, A6>(
+        &mut self,
+        syscall: &'static SysCall<R, A1, A2, A3, A4, A5, A6>,
+        mut predicate: impl FnMut(&[u64; 6]) -> bool + 'static,
+    ) -> &mut Self
+    where
+        R: Number,
+        A1: Read,
+        A2: Read,
+        A3: Read,
+        A4: Read,
+        A5: Read,
+        A6: Read,
+        MayBePtr<<A1 as Read>::InnerType>: Write<A1> + Ptr<A1>,
+        MayBePtr<<A2 as Read>::InnerType>: Write<A2> + Ptr<A2>,
+        MayBePtr<<A3 as Read>::InnerType>: Write<A3> + Ptr<A3>,
+        MayBePtr<<A4 as Read>::InnerType>: Write<A4> + Ptr<A4>,
+        MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
+        MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
+    {
+        let last = self.next_id;
+        self.on(syscall);
+        self.set_filter(last, move |_, args| predicate(args))
+    }
+
+    /// same as [`Interceptor::on_fn`], but the closure only runs for the `n`th (from `1`)
+    /// call of syscall `name` counted in `scope`, e.g. to reproduce a bug at the 42nd
+    /// `openat`. Other calls are sent to kernel without decoding their arguments, as
+    /// [`Interceptor::on_filtered`] does.
+    ///
+    /// Calls are counted once they reach handlers, so the ones of tasks skipped by
+    /// [`Interceptor::filter_tasks`] or blocked by [`Interceptor::default_block`] are not
+    /// counted.
+    ///
+    /// ```rust,ignore
+    /// interceptor.on_nth("openat", 42, NthScope::Session, |dfd: i32, filename: *const c_char| {
+    ///     Outcome::<i32, (i32, *const c_char)>::Block(-libc::EMFILE)
+    /// });
+    /// ```
+    pub fn on_nth<Args, R, A1, A2, A3, A4, A5, A6>(
+        &mut self,
+        name: &'static str,
+        n: usize,
+        scope: NthScope,
+        f: impl Handler<Args, R, A1, A2, A3, A4, A5, A6> + 'static,
+    ) -> &mut Self
+    where
+        R: Number + 'static,
+        A1: Read + 'static,
+        A2: Read + 'static,
+        A3: Read + 'static,
+        A4: Read + 'static,
+        A5: Read + 'static,
+        A6: Read + 'static,
+        MayBePtr<<A1 as Read>::InnerType>: Write<A1> + Ptr<A1>,
+        MayBePtr<<A2 as Read>::InnerType>: Write<A2> + Ptr<A2>,
+        MayBePtr<<A3 as Read>::InnerType>: Write<A3> + Ptr<A3>,
+        MayBePtr<<A4 as Read>::InnerType>: Write<A4> + Ptr<A4>,
+        MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
+        MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
+    {
+        let last = self.next_id;
+        self.on_fn(name, f);
+        let mut counts = HashMap::<i32, usize>::new();
+        self.set_filter(last, move |pid, _| {
+            let key = match scope {
+                NthScope::Session => 0,
+                NthScope::PerTracee => pid,
+            };
+            let count = counts.entry(key).or_default();
+            *count = count.saturating_add(1);
+            *count == n
+        })
+    }
+
+    /// set `filter` of the handler just registered, whose id is after `last`. It's not
+    /// registered if rejected as a duplicate.
+    fn set_filter(
+        &mut self,
+        last: u64,
+        filter: impl FnMut(i32, &[u64; 6]) -> bool + 'static,
+    ) -> &mut Self {
+        if let Some(sc) = self.syscalls.last_mut().filter(|sc| sc.id > last) {
+            sc.filter = Some(Box::new(filter));
+        }
+        self
+    }
+
+    /// same as [`Interceptor::on`], but the handler is a closure taking typed arguments
+    /// of syscall `name`, which can capture state, e.g. a counter or a shared config.
+    ///
+    /// It runs before the syscall, where [`with_context`] is available, and returns
+    /// [`Outcome::Real`] with the arguments (may be changed) to send it to kernel, or
+    /// [`Outcome::Block`] to return a value directly. Missing arguments are not changed.
+    ///
+    /// ```rust,ignore
+    /// let mut count = 0;
+    /// interceptor.on_fn("openat", move |dfd: i32, filename: *const c_char, flags: i32| {
+    ///     count += 1;
+    ///     Outcome::Real((dfd, filename, flags))
+    /// });
+    /// ```
+    pub fn on_fn<Args, R, A1, A2, A3, A4, A5, A6>(
+        &mut self,
+        name: &'static str,
+        mut f: impl Handler<Args, R, A1, A2, A3, A4, A5, A6> + 'static,
+    ) -> &mut Self
+    where
+        R: Number + 'static,
+        A1: Read + 'static,
+        A2: Read + 'static,
+        A3: Read + 'static,
+        A4: Read + 'static,
+        A5: Read + 'static,
+        A6: Read + 'static,
+        MayBePtr<<A1 as Read>::InnerType>: Write<A1> + Ptr<A1>,
+        MayBePtr<<A2 as Read>::InnerType>: Write<A2> + Ptr<A2>,
+        MayBePtr<<A3 as Read>::InnerType>: Write<A3> + Ptr<A3>,
+        MayBePtr<<A4 as Read>::InnerType>: Write<A4> + Ptr<A4>,
+        MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
+        MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
+    {
+        self.register(
+            name,
+            f.arity(),
+            move |a1, a2, a3, a4, a5, a6| f.call(a1, a2, a3, a4, a5, a6),
+            |r| r,
+        )
+    }
+
+    /// same as [`Interceptor::on_fn`], but the closure returns [`Outcome::Real`] with an
+    /// `Option` of each argument, `None` sends the raw argument to kernel as is without
+    /// comparing it, e.g. a pointer whose buffer was changed by the closure is not
+    /// written back.
+    ///
+    /// ```rust,ignore
+    /// interceptor.on_fn_partial(
+    ///     "openat",
+    ///     |dfd: i32, filename: *const c_char, flags: i32| -> Outcome<i32, _> {
+    ///         Outcome::Real((None, None, Some(flags | libc::O_CLOEXEC)))
+    ///     },
+    /// );
+    /// ```
+    pub fn on_fn_partial<Args, R, A1, A2, A3, A4, A5, A6>(
+        &mut self,
+        name: &'static str,
+        mut f: impl PartialHandler<Args, R, A1, A2, A3, A4, A5, A6> + 'static,
+    ) -> &mut Self
+    where
+        R: Number + 'static,
+        A1: Read + 'static,
+        A2: Read + 'static,
+        A3: Read + 'static,
+        A4: Read + 'static,
+        A5: Read + 'static,
+        A6: Read + 'static,
+        MayBePtr<<A1 as Read>::InnerType>: Write<A1> + Ptr<A1>,
+        MayBePtr<<A2 as Read>::InnerType>: Write<A2> + Ptr<A2>,
+        MayBePtr<<A3 as Read>::InnerType>: Write<A3> + Ptr<A3>,
+        MayBePtr<<A4 as Read>::InnerType>: Write<A4> + Ptr<A4>,
+        MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
+        MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
+    {
+        self.register(
+            name,
+            f.arity(),
+            move |a1, a2, a3, a4, a5, a6| f.call(a1, a2, a3, a4, a5, a6),
+            |r| r,
+        )
+    }
+
+    fn register<R, A1, A2, A3, A4, A5, A6>(
+        &mut self,
+        name: &'static str,
+        arity: usize,
+        mut pre: impl FnMut(A1, A2, A3, A4, A5, A6) -> ReturnVariant<R, A1, A2, A3, A4, A5, A6>
+            + 'static,
+        post: impl Fn(R) -> R + 'static,
+    ) -> &mut Self
+    where
+        R: Number + 'static,
+        A1: Read + 'static,
+        A2: Read + 'static,
+        A3: Read + 'static,
+        A4: Read + 'static,
+        A5: Read + 'static,
+        A6: Read + 'static,
+        MayBePtr<<A1 as Read>::InnerType>: Write<A1> + Ptr<A1>,
+        MayBePtr<<A2 as Read>::InnerType>: Write<A2> + Ptr<A2>,
+        MayBePtr<<A3 as Read>::InnerType>: Write<A3> + Ptr<A3>,
+        MayBePtr<<A4 as Read>::InnerType>: Write<A4> + Ptr<A4>,
+        MayBePtr<<A5 as Read>::InnerType>: Write<A5> + Ptr<A5>,
+        MayBePtr<<A6 as Read>::InnerType>: Write<A6> + Ptr<A6>,
+    {
+        // missing arguments are left unchanged, but reading them as padding may hide a
+        // wrong signature
+        if let Some(n) = SYSCALL_ARGS.get(name).map(Vec::len).filter(|n| *n != arity) {
+            warn!(
+                "{} takes {} arguments, but its handler declares {}",
+                name, n, arity
+            );
+        }
+        if restores_registers(name) {
+            warn!(
+                "{} restores registers, changes made by handler are discarded",
+                name
+            );
+        }
+        if !self.admit(name) {
+            return self;
+        }
+
+        let id = self.next_handler_id();
         let contexts = self.contexts.clone();
         let remote_mem = self.remote_mem.clone();
+        let decode_error = self.decode_error.clone();
         self.syscalls.push(SysCallWrapper {
-            name: syscall.name,
+            id,
+            name,
             pre: Box::new(move |tracee, a1, a2, a3, a4, a5, a6| {
-                let mut a1 = A1::read(tracee, a1);
-                let mut a2 = A2::read(tracee, a2);
-                let mut a3 = A3::read(tracee, a3);
-                let mut a4 = A4::read(tracee, a4);
-                let mut a5 = A5::read(tracee, a5);
-                let mut a6 = A6::read(tracee, a6);
-                match syscall.call_pre(a1.get(), a2.get(), a3.get(), a4.get(), a5.get(), a6.get()) {
+                let raw = [a1, a2, a3, a4, a5, a6];
+                let mut failed = Vec::new();
+                let mut decoded = |index| {
+                    if let Some(addr) = take_decode_error() {
+                        failed.push((index, addr));
+                    }
+                };
+                take_decode_error();
+                let mut a1 = A1::read(tracee.get(), a1);
+                decoded(0);
+                let mut a2 = A2::read(tracee.get(), a2);
+                decoded(1);
+                let mut a3 = A3::read(tracee.get(), a3);
+                decoded(2);
+                let mut a4 = A4::read(tracee.get(), a4);
+                decoded(3);
+                let mut a5 = A5::read(tracee.get(), a5);
+                decoded(4);
+                let mut a6 = A6::read(tracee.get(), a6);
+                decoded(5);
+                if let Some(f) = &decode_error {
+                    let mut run = true;
+                    for (index, addr) in failed {
+                        run &= (f.borrow_mut())(name, index, &InterceptError::MemoryAccess(addr));
+                    }
+                    if !run {
+                        debug!("{} is sent to kernel without running handler", name);
+                        return Ok(ReturnVariantWrapper::Skipped);
+                    }
+                }
+                let addrs = [
+                    Ptr::<A1>::addrs(&a1),
+                    Ptr::<A2>::addrs(&a2),
+                    Ptr::<A3>::addrs(&a3),
+                    Ptr::<A4>::addrs(&a4),
+                    Ptr::<A5>::addrs(&a5),
+                    Ptr::<A6>::addrs(&a6),
+                ];
+                drop_ptr_arrays();
+                set_origins(addrs);
+                let ret = pre(a1.get(), a2.get(), a3.get(), a4.get(), a5.get(), a6.get());
+                set_origins([]);
+                match ret {
                     ReturnVariant::PackedArgs((r1, r2, r3, r4, r5, r6)) => {
-                        let pa = (
-                            a1.write(tracee, remote_mem.clone(), r1),
-                            a2.write(tracee, remote_mem.clone(), r2),
-                            a3.write(tracee, remote_mem.clone(), r3),
-                            a4.write(tracee, remote_mem.clone(), r4),
-                            a5.write(tracee, remote_mem.clone(), r5),
-                            a6.write(tracee, remote_mem.clone(), r6),
-                        );
-                        contexts.borrow_mut().insert(
-                            syscall.name.to_string(),
-                            PackedContext(
-                                Box::new(a1),
-                                Box::new(a2),
-                                Box::new(a3),
-                                Box::new(a4),
-                                Box::new(a5),
-                                Box::new(a6),
-                            ),
-                        );
-                        ReturnVariantWrapper::PackedArgs(pa)
+                        let mut pa = [
+                            a1.write(tracee.get(), remote_mem.clone(), r1)?,
+                            a2.write(tracee.get(), remote_mem.clone(), r2)?,
+                            a3.write(tracee.get(), remote_mem.clone(), r3)?,
+                            a4.write(tracee.get(), remote_mem.clone(), r4)?,
+                            a5.write(tracee.get(), remote_mem.clone(), r5)?,
+                            a6.write(tracee.get(), remote_mem.clone(), r6)?,
+                        ];
+                        for (index, value) in with_context(|ctx| ctx.take_arg_overrides()) {
+                            let update = if value == raw[index] {
+                                ArgUpdate::Unchanged
+                            } else {
+                                ArgUpdate::Changed(value)
+                            };
+                            pa[index] = Some((value, update));
+                        }
+                        for (index, data, write) in with_context(|ctx| ctx.take_arg_strings()) {
+                            pa[index] = Some(write_arg_string(
+                                tracee.get(),
+                                remote_mem.clone(),
+                                raw[index],
+                                &data,
+                                write,
+                            )?);
+                        }
+                        let changed = pa
+                            .iter()
+                            .any(|a| matches!(a, Some((_, u)) if *u != ArgUpdate::Unchanged));
+                        let buffers = addrs.map(|a| a.map(|(.., origin)| origin));
+                        // nothing to keep on the hot passthrough path
+                        if changed || buffers.iter().any(Option::is_some) {
+                            let args: Option<[Box<dyn Context>; 6]> = changed.then(|| {
+                                [
+                                    Box::new(a1) as Box<dyn Context>,
+                                    Box::new(a2),
+                                    Box::new(a3),
+                                    Box::new(a4),
+                                    Box::new(a5),
+                                    Box::new(a6),
+                                ]
+                            });
+                            contexts.borrow_mut().insert(
+                                (tracee.get().pid.as_raw(), id),
+                                PackedContext { args, buffers },
+                            );
+                        }
+                        Ok(ReturnVariantWrapper::PackedArgs(pa))
+                    }
+                    ReturnVariant::Normal(r) => {
+                        Ok(ReturnVariantWrapper::Normal(r.to_reg().as_unsigned()))
                     }
-                    ReturnVariant::Normal(r) => ReturnVariantWrapper::Normal(r.to_u64()),
                 }
             }),
-            post: Box::new(|u| syscall.call_post(R::from_u64(u)).to_u64()),
+            post: Box::new(move |u| {
+                post(R::from_reg(RegisterValue::from_unsigned(u)))
+                    .to_reg()
+                    .as_unsigned()
+            }),
+            filter: None,
         });
         self
     }
 
-    /// run the child process and begin intercepting
-    pub fn run(&mut self) -> Result<()> {
-        while let Some(mut tracee) = self.ptracer.wait()? {
-            self.on_stop(&mut tracee)?;
-            self.ptracer.restart(tracee, Restart::Syscall)?;
+    /// register a closure handling the return value of syscall `name` only, arguments
+    /// are not decoded. It's called after the syscall returned with the raw return value
+    /// as signed, e.g. `-2` for `ENOENT`, and the value it returns is passed to caller.
+    ///
+    /// It's chained with other handlers of the syscall, see [`Interceptor::on`].
+    pub fn on_return_of(
+        &mut self,
+        name: &'static str,
+        mut f: impl FnMut(i64) -> i64 + 'static,
+    ) -> &mut Self {
+        if !self.admit(name) {
+            return self;
         }
 
-        Ok(())
+        let id = self.next_handler_id();
+        self.syscalls.push(SysCallWrapper {
+            id,
+            name,
+            pre: Box::new(|_, _, _, _, _, _, _| Ok(ReturnVariantWrapper::PackedArgs([None; 6]))),
+            post: Box::new(move |u| {
+                let ret = f(RegisterValue::from_unsigned(u).as_signed());
+                RegisterValue::from_signed(ret).as_unsigned()
+            }),
+            filter: None,
+        });
+        self
     }
 
-    fn on_stop(&mut self, tracee: &mut Tracee) -> Result<()> {
-        let mut regs = tracee.registers()?;
-        let pc = regs.rip;
-        let Tracee { pid, stop, .. } = tracee;
+    /// register a catch-all handler receiving raw arguments of every syscall, which is
+    /// called at both enter and exit.
+    ///
+    /// Catch-all handlers run before the ones registered by [`Interceptor::on`], the
+    /// first [`Decision::Block`] or [`Decision::Redirect`] wins and the remaining handlers
+    /// are skipped.
+    pub fn on_all(&mut self, f: impl FnMut(&SyscallEvent) -> Decision + 'static) -> &mut Self {
+        self.raw_handlers.push(RawHandler {
+            sysnos: None,
+            f: Box::new(f),
+        });
+        self
+    }
 
-        match stop {
-            Stop::SyscallEnter => {
-                let syscall = SYSCALL_TABLE
-                    .get(&regs.orig_rax)
-                    .cloned()
-                    .unwrap_or_else(|| format!("unknown (syscall no = 0x{:x})", regs.orig_rax));
-                debug!(
-                    "pid = {}, pc = {:x}: [{}] {:?}\nregs: {:x?}",
-                    pid, pc, syscall, stop, regs
-                );
+    /// same as [`Interceptor::on_all`], but only syscalls whose name matches the glob
+    /// `pattern` are handled, e.g. `"*at"` or `"socket*"`. `*` matches any sequence and
+    /// `?` matches any single character.
+    pub fn on_matching(
+        &mut self,
+        pattern: &str,
+        f: impl FnMut(&SyscallEvent) -> Decision + 'static,
+    ) -> &mut Self {
+        let sysnos = SYSCALL_TABLE
+            .iter()
+            .filter(|(_, name)| glob_match(pattern.as_bytes(), name.as_bytes()))
+            .map(|(sysno, _)| *sysno)
+            .collect::<HashSet<_>>();
+        if sysnos.is_empty() {
+            warn!("no syscall matches pattern {}", pattern);
+        }
 
-                if let Some(sc) = self.syscalls.iter_mut().find(|sc| sc.name == syscall) {
-                    match (sc.pre)(
-                        tracee, regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9,
-                    ) {
-                        ReturnVariantWrapper::PackedArgs((r1, r2, r3, r4, r5, r6)) => {
-                            macro_rules! set_reg {
-                                ($r:path ,$n: tt) => {
-                                    paste! {
-                                        if let Some([<r $n>]) = [<r $n>] {
-                                            regs.$r = [<r $n>];
-                                        }
-                                    }
-                                };
-                            }
+        self.raw_handlers.push(RawHandler {
+            sysnos: Some(sysnos),
+            f: Box::new(f),
+        });
+        self
+    }
 
-                            set_reg!(rdi, 1);
-                            set_reg!(rsi, 2);
-                            set_reg!(rdx, 3);
-                            set_reg!(r10, 4);
-                            set_reg!(r8, 5);
-                            set_reg!(r9, 6);
-                            tracee.set_registers(regs)?;
-                            self.contexts.borrow_mut().remove(&syscall);
-                        }
-                        ReturnVariantWrapper::Normal(r) => {
-                            // syscall will be blocked, call a non-exists & random sysno,
-                            let sysno = 512 + rand::thread_rng().gen::<u16>() as u64;
-                            self.block_calls.insert(sysno, r);
-                            debug!(
-                                "block call change sysno {} -> {}. ret: {}",
-                                regs.orig_rax, sysno, r
-                            );
-                            regs.orig_rax = sysno;
-                            tracee.set_registers(regs)?;
-                        }
-                    }
-                }
+    /// block every syscall not allowed with `-errno`, e.g. `EPERM`, to run the target in a
+    /// strict sandbox. Syscalls are allowed by [`Interceptor::allow`], or by having a
+    /// handler registered by [`Interceptor::on`], [`Interceptor::on_fn`],
+    /// [`Interceptor::on_return_of`] or [`Interceptor::on_matching`], which decides what
+    /// to do with it. Catch-all handlers of [`Interceptor::on_all`] still see all
+    /// syscalls, the ones they don't block or redirect are blocked by this.
+    ///
+    /// `exit`, `exit_group` and `rt_sigreturn` are always allowed, the target can not
+    /// continue without them. Note that the dynamic loader and libc need quite a few
+    /// syscalls to start a program, e.g. `brk`, `mmap`, `mprotect`, `openat`, `read`,
+    /// `close` and `arch_prctl`.
+    pub fn default_block(&mut self, errno: i32) -> &mut Self {
+        self.default_block = Some(errno);
+        self
+    }
+
+    /// allow syscalls whose name matches the glob `pattern` in
+    /// [`Interceptor::default_block`] mode, see [`Interceptor::on_matching`] for the
+    /// syntax.
+    pub fn allow(&mut self, pattern: &str) -> &mut Self {
+        let sysnos = SYSCALL_TABLE
+            .iter()
+            .filter(|(_, name)| glob_match(pattern.as_bytes(), name.as_bytes()))
+            .map(|(sysno, _)| *sysno)
+            .collect::<Vec<_>>();
+        if sysnos.is_empty() {
+            warn!("no syscall matches pattern {}", pattern);
+        }
+
+        self.allowed.extend(sysnos);
+        self
+    }
+
+    /// errno to block syscall `sysno` named `syscall` with, if not allowed in
+    /// [`Interceptor::default_block`] mode
+    fn denied(&self, sysno: u64, syscall: &str) -> Option<i32> {
+        let errno = self.default_block?;
+        let allowed = self.allowed.contains(&sysno)
+            || NON_RETURNING.contains(&syscall)
+            || restores_registers(syscall)
+            || self.syscalls.iter().any(|sc| sc.name == syscall)
+            || self
+                .raw_handlers
+                .iter()
+                .any(|h| h.sysnos.as_ref().is_some_and(|s| s.contains(&sysno)));
+        (!allowed).then_some(errno)
+    }
+
+    /// block the first `times` syscalls whose name matches the glob `pattern` and return
+    /// `ret` directly, the following ones are sent to kernel, e.g. to fail the first
+    /// `openat` with `-ENOMEM` and test error handling paths of the target.
+    ///
+    /// Syscalls of all tracees are counted together. Handlers of
+    /// [`Interceptor::on_all`] and [`Interceptor::on_matching`] are `FnMut`, other
+    /// stateful policies can be built the same way by capturing the state in closure.
+    pub fn block_times(&mut self, pattern: &str, times: usize, ret: u64) -> &mut Self {
+        let mut left = times;
+        self.on_matching(pattern, move |e| {
+            if e.phase == Phase::Enter && left > 0 {
+                left -= 1;
+                Decision::Block(ret)
+            } else {
+                Decision::Continue
             }
-            Stop::SyscallExit => {
-                if let Some(block_call_ret) = self.block_calls.remove(&regs.orig_rax) {
-                    debug!(
-                        "block call sysno: {}, ret: {}",
-                        regs.orig_rax, block_call_ret
-                    );
-                    regs.rax = block_call_ret;
-                    tracee.set_registers(regs)?;
-                } else {
-                    let syscall = SYSCALL_TABLE
-                        .get(&regs.orig_rax)
-                        .cloned()
-                        .unwrap_or_else(|| format!("unknown (syscall no = 0x{:x})", regs.orig_rax));
-                    debug!(
-                        "pid = {}, pc = {:x}: [{}] {:?}\nregs: {:x?}",
-                        pid, pc, syscall, stop, regs
-                    );
+        })
+    }
 
-                    if let Some(sc) = self.syscalls.iter_mut().find(|sc| sc.name == syscall) {
-                        let ret = (sc.post)(regs.rax);
-                        regs.rax = ret;
-                        tracee.set_registers(regs)?;
-                    }
-                }
+    /// turn syscall `name` into a no-op returning `ret` without entering kernel, e.g. to
+    /// skip expensive but irrelevant `fsync` or `msync` with `0` when fuzzing.
+    ///
+    /// It's a shortcut of [`Interceptor::on_matching`] always returning
+    /// [`Decision::Block`], so handlers of the syscall registered by [`Interceptor::on`]
+    /// and catch-all ones registered after it are skipped.
+    pub fn noop(&mut self, name: &str, ret: u64) -> &mut Self {
+        self.on_matching(name, move |e| {
+            if e.phase == Phase::Enter {
+                Decision::Block(ret)
+            } else {
+                Decision::Continue
             }
-            _ => {}
-        }
+        })
+    }
 
-        Ok(())
+    /// pretend seccomp filters and strict mode installed by the target succeed without
+    /// installing them, so they can not deny or kill syscalls interceptor relies on, e.g.
+    /// a blocked syscall is sent to kernel as a nonexistent one, which an allowlist
+    /// filter usually kills. `seccomp` with `SECCOMP_SET_MODE_STRICT` or
+    /// `SECCOMP_SET_MODE_FILTER` and `prctl` with `PR_SET_SECCOMP` return `0`, a filter
+    /// requesting a notification fd by `SECCOMP_FILTER_FLAG_NEW_LISTENER` fails with
+    /// `EINVAL` as there is no fd to return. A warning is logged for each of them.
+    ///
+    /// It removes the sandbox of the target: the target believes it's confined while any
+    /// syscall is allowed, so only use it on trusted targets or where interceptor is the
+    /// sandbox, e.g. by denying syscalls itself. `PR_GET_SECCOMP` and
+    /// `/proc/<pid>/status` still report no filter. eBPF programs loaded by `bpf` are not
+    /// seccomp filters and are not affected, register a handler of `bpf` to deny them.
+    pub fn ignore_seccomp(&mut self) -> &mut Self {
+        self.on_fn("seccomp", |operation: u32, flags: u64, args: u64| {
+            if operation != libc::SECCOMP_SET_MODE_STRICT
+                && operation != libc::SECCOMP_SET_MODE_FILTER
+            {
+                return Outcome::Real((operation, flags, args));
+            }
+
+            let pid = with_context(|ctx| ctx.pid());
+            if flags & libc::SECCOMP_FILTER_FLAG_NEW_LISTENER != 0 {
+                warn!("pid = {}: seccomp filter with listener denied", pid);
+                Outcome::Block(-libc::EINVAL as i64)
+            } else {
+                warn!("pid = {}: seccomp mode {} ignored", pid, operation);
+                Outcome::Block(0)
+            }
+        })
+        .on_fn(
+            "prctl",
+            |option: PrctlOption, arg2: u64, arg3: u64, arg4: u64, arg5: u64| {
+                if option != PrctlOption::PR_SET_SECCOMP {
+                    return Outcome::Real((option, arg2, arg3, arg4, arg5));
+                }
+
+                let pid = with_context(|ctx| ctx.pid());
+                warn!("pid = {}: seccomp mode {} ignored", pid, arg2);
+                Outcome::Block(0i64)
+            },
+        )
     }
-}
 
-/// A fake macro that actually does nothing.
-/// It will be detected in `proc_macro_attribute` and changes intercept logic.
-#[macro_export]
-macro_rules! real {
-    ($($args: tt),*) => {
-        let _ = ($($args),*);
-    };
-}
+    /// register a closure handling all syscalls taking a path, e.g. `openat`, `stat`,
+    /// `faccessat`, `readlink` and `unlink`, which are normalized into a
+    /// [`PathSyscallCtx`], see [`PathOp`] for the full list. Return [`Outcome::Block`]
+    /// with e.g. `-EACCES` to deny one, or `Outcome::Real(())` to send it to kernel.
+    ///
+    /// It's registered as a handler of each syscall, so it's chained with the ones
+    /// registered by [`Interceptor::on`], see there. Paths can be changed by
+    /// [`SyscallContext::set_arg_string`] inside it.
+    pub fn on_path_syscalls(
+        &mut self,
+        f: impl FnMut(&PathSyscallCtx) -> Outcome<i64, ()> + 'static,
+    ) -> &mut Self {
+        let f = Rc::new(RefCell::new(f));
+        for sc in PATH_SYSCALLS.iter() {
+            if !SYSCALL_TABLE.values().any(|name| name == sc.name) {
+                continue;
+            }
 
-type SyscallTable = HashMap<u64, String>;
-static SYSCALL_TABLE: Lazy<SyscallTable> = Lazy::new(load_syscall_table);
-const SYSCALLS: &str = include_str!("data/syscalls_x64.tsv");
+            let f = f.clone();
+            let arity = SYSCALL_ARGS.get(sc.name).map(Vec::len).unwrap_or(6);
+            self.register(
+                sc.name,
+                arity,
+                move |_: u64, _: u64, _: u64, _: u64, _: u64, _: u64| {
+                    let ctx = with_context(|ctx| sc.read(ctx));
+                    match (f.borrow_mut())(&ctx) {
+                        Outcome::Real(()) => {
+                            ReturnVariant::PackedArgs((None, None, None, None, None, None))
+                        }
+                        Outcome::Block(r) => ReturnVariant::Normal(r),
+                    }
+                },
+                |r: i64| r,
+            );
+        }
+        self
+    }
 
-fn load_syscall_table() -> SyscallTable {
-    let mut syscalls = HashMap::new();
+    /// register a closure observing file descriptors created or closed by the tracee, e.g.
+    /// to keep a fd table, the syscalls are normalized into [`FdEvent`] after they
+    /// returned successfully, see [`FdOp`] for the full list. Failed ones are not reported.
+    ///
+    /// It's registered by [`Interceptor::on_return_of`] for each syscall, so it's chained
+    /// with other handlers of them. Fds created otherwise, e.g. by `fcntl` with `F_DUPFD`,
+    /// `perf_event_open` or received by `recvmsg`, and fds closed on `execve` are not
+    /// reported.
+    ///
+    /// ```rust,ignore
+    /// let mut fds = HashMap::new();
+    /// interceptor.on_fd_events(move |e: &FdEvent| match e.op {
+    ///     FdOp::Close => drop(fds.remove(&(e.pid, e.fd))),
+    ///     _ => drop(fds.insert((e.pid, e.fd), e.path_or_addr.clone())),
+    /// });
+    /// ```
+    pub fn on_fd_events(&mut self, f: impl FnMut(&FdEvent) + 'static) -> &mut Self {
+        let f = Rc::new(RefCell::new(f));
+        for &(name, op) in FD_SYSCALLS.iter() {
+            if !SYSCALL_TABLE.values().any(|n| n == name) {
+                continue;
+            }
 
-    for line in SYSCALLS.split_terminator('\n') {
-        let (call_no, name) = line
-            .split_once('\t')
-            .map(|(x, y)| (x.trim().parse::<u64>().unwrap(), y.trim().to_owned()))
-            .unwrap();
-        syscalls.insert(call_no, name);
+            let f = f.clone();
+            self.on_return_of(name, move |ret| {
+                if ret >= 0 {
+                    for event in with_context(|ctx| read_events(ctx, name, op, ret)) {
+                        (f.borrow_mut())(&event);
+                    }
+                }
+                ret
+            });
+        }
+        self
     }
 
-    syscalls
+    /// register a closure handling syscalls changing the credentials of the tracee, e.g.
+    /// to monitor privilege drops, `setuid`, `setgid`, `setreuid`, `setregid`,
+    /// `setresuid`, `setresgid` and `setgroups` are normalized into a [`PrivilegeChange`].
+    /// Return [`Outcome::Block`] with e.g. `-EPERM` to deny one, or `Outcome::Real(())`
+    /// to send it to kernel.
+    ///
+    /// A blocked syscall never enters kernel, so none of the ids is changed, even if
+    /// kernel would have applied some before failing. Credentials are per thread in
+    /// kernel, libc of a multi-threaded process makes the syscall in every thread once
+    /// the calling one succeeded and aborts if another one fails, so only block it for
+    /// the calling thread. Note libc signals the other threads by a realtime signal,
+    /// which `pete` fails to wait for, making [`Interceptor::run`] return an error.
+    /// `setfsuid` and `setfsgid` are not covered, as they can not report an error.
+    pub fn on_privilege_change(
+        &mut self,
+        f: impl FnMut(&PrivilegeChange) -> Outcome<i64, ()> + 'static,
+    ) -> &mut Self {
+        let f = Rc::new(RefCell::new(f));
+        for &(name, arity) in PRIVILEGE_SYSCALLS.iter() {
+            if !SYSCALL_TABLE.values().any(|n| n == name) {
+                continue;
+            }
+
+            let f = f.clone();
+            self.register(
+                name,
+                arity,
+                move |_: u64, _: u64, _: u64, _: u64, _: u64, _: u64| {
+                    let change = with_context(|ctx| read_change(ctx, name));
+                    match (f.borrow_mut())(&change) {
+                        Outcome::Real(()) => {
+                            ReturnVariant::PackedArgs((None, None, None, None, None, None))
+                        }
+                        Outcome::Block(r) => ReturnVariant::Normal(r),
+                    }
+                },
+                |r: i64| r,
+            );
+        }
+        self
+    }
+
+    /// set what happens when a handler is registered for a syscall which already has one,
+    /// [`DuplicatePolicy::Chain`] by default. It applies to the following registrations
+    /// only, including the ones of [`Interceptor::on_path_syscalls`].
+    pub fn on_duplicate(&mut self, policy: DuplicatePolicy) -> &mut Self {
+        self.duplicates = policy;
+        self
+    }
+
+    /// set a closure called when a pointer argument can not be fully read from the tracee
+    /// before a handler runs, e.g. unmapped memory or a string without NUL, with the
+    /// syscall name, index of the argument and [`InterceptError::MemoryAccess`] telling
+    /// the first address not readable. Return `true` to run the handler with what was
+    /// read, or `false` to send the syscall to kernel unchanged without running it (the
+    /// code after `real!()` neither).
+    ///
+    /// Without it, the handler runs with what was read, e.g. a truncated string. Like
+    /// [`Interceptor::on_duplicate`], it applies to the following registrations only,
+    /// including the ones of [`Interceptor::on_fn`].
+    ///
+    /// ```rust,ignore
+    /// interceptor.on_decode_error(|name, index, e| {
+    ///     warn!("{} argument {}: {}", name, index, e);
+    ///     false
+    /// });
+    /// ```
+    pub fn on_decode_error(
+        &mut self,
+        f: impl FnMut(&str, usize, &InterceptError) -> bool + 'static,
+    ) -> &mut Self {
+        self.decode_error = Some(Rc::new(RefCell::new(f)));
+        self
+    }
+
+    /// names of syscalls having handlers registered by [`Interceptor::on`],
+    /// [`Interceptor::on_fn`] and [`Interceptor::on_return_of`], each one once, in the
+    /// order they were first registered.
+    pub fn registered(&self) -> Vec<&str> {
+        let mut names = Vec::new();
+        for sc in &self.syscalls {
+            if !names.contains(&sc.name) {
+                names.push(sc.name);
+            }
+        }
+        names
+    }
+
+    /// whether a new handler of syscall `name` should be registered, by the policy of
+    /// duplicates
+    fn admit(&mut self, name: &str) -> bool {
+        if !SYSCALL_TABLE.values().any(|n| n == name) {
+            // registered anyway, but it never runs
+            warn!(
+                "{} is not a known syscall, its handler never runs, similar ones: {:?}",
+                name,
+                similar_syscalls(name)
+            );
+        }
+        if !self.syscalls.iter().any(|sc| sc.name == name) {
+            return true;
+        }
+
+        match self.duplicates {
+            DuplicatePolicy::Chain => true,
+            DuplicatePolicy::Replace => {
+                debug!("handlers of {} replaced", name);
+                self.off(name);
+                true
+            }
+            DuplicatePolicy::Reject => {
+                warn!("{} already has a handler, the new one is ignored", name);
+                false
+            }
+        }
+    }
+
+    /// register a closure handling `ioctl` with request `request` only, e.g.
+    /// `TIOCGWINSZ`, its third argument is decoded as `T`, which is the layout of the
+    /// request given by caller. Return [`Outcome::Block`] to return a value directly, or
+    /// `Outcome::Real(())` to send it to kernel, changes made to [`IoctlCtx::arg`] are
+    /// written back into target in both cases.
+    ///
+    /// Other requests are sent to kernel unchanged, register a handler of `ioctl` taking
+    /// raw `u64` arguments by [`Interceptor::on_fn`] to handle them. It's registered as a
+    /// handler of `ioctl`, so it's chained with the other ones, see [`Interceptor::on`].
+    ///
+    /// ```rust,ignore
+    /// #[repr(C)]
+    /// #[derive(Clone, Copy, Default)]
+    /// struct Winsize { row: u16, col: u16, xpixel: u16, ypixel: u16 }
+    /// unsafe impl Pod for Winsize {}
+    ///
+    /// interceptor.on_ioctl(libc::TIOCGWINSZ, |ioctl: &mut IoctlCtx<Winsize>| {
+    ///     ioctl.arg = Some(Winsize { row: 24, col: 80, ..Default::default() });
+    ///     Outcome::Block(0)
+    /// });
+    /// ```
+    pub fn on_ioctl<T: Pod>(
+        &mut self,
+        request: u64,
+        mut f: impl FnMut(&mut IoctlCtx<T>) -> Outcome<i64, ()> + 'static,
+    ) -> &mut Self {
+        self.register(
+            "ioctl",
+            3,
+            move |_: u64, _: u64, _: u64, _: u64, _: u64, _: u64| {
+                let unchanged = ReturnVariant::PackedArgs((None, None, None, None, None, None));
+                let Some(mut ioctl) = with_context(|ctx| IoctlCtx::read(ctx, request)) else {
+                    return unchanged;
+                };
+
+                let outcome = f(&mut ioctl);
+                if let Err(e) = with_context(|ctx| ioctl.store(ctx)) {
+                    warn!("pid = {}: write argument of ioctl error: {}", ioctl.pid, e);
+                }
+                match outcome {
+                    Outcome::Real(()) => unchanged,
+                    Outcome::Block(r) => ReturnVariant::Normal(r),
+                }
+            },
+            |r: i64| r,
+        )
+    }
+
+    /// register a closure handling `fcntl` with command `cmd` only, e.g. `F_SETFL`, its
+    /// third argument is decoded by the command, see [`FcntlArg`]. Return
+    /// [`Outcome::Block`] to return a value directly, or `Outcome::Real(())` to send it to
+    /// kernel, changes made to [`FcntlCtx::arg`] are applied in both cases.
+    ///
+    /// Other commands are sent to kernel unchanged, same as [`Interceptor::on_ioctl`].
+    ///
+    /// ```rust,ignore
+    /// interceptor.on_fcntl(libc::F_SETFL, |fcntl: &mut FcntlCtx| {
+    ///     if let FcntlArg::Int(flags) = &mut fcntl.arg {
+    ///         *flags &= !libc::O_NONBLOCK;
+    ///     }
+    ///     Outcome::Real(())
+    /// });
+    /// ```
+    pub fn on_fcntl(
+        &mut self,
+        cmd: i32,
+        mut f: impl FnMut(&mut FcntlCtx) -> Outcome<i64, ()> + 'static,
+    ) -> &mut Self {
+        self.register(
+            "fcntl",
+            3,
+            move |_: u64, _: u64, _: u64, _: u64, _: u64, _: u64| {
+                let Some(mut fcntl) = with_context(|ctx| FcntlCtx::read(ctx, cmd)) else {
+                    return ReturnVariant::PackedArgs((None, None, None, None, None, None));
+                };
+
+                let outcome = f(&mut fcntl);
+                let arg = with_context(|ctx| fcntl.store(ctx)).unwrap_or_else(|e| {
+                    warn!("pid = {}: write argument of fcntl error: {}", fcntl.pid, e);
+                    None
+                });
+                match outcome {
+                    Outcome::Real(()) => {
+                        ReturnVariant::PackedArgs((None, None, arg, None, None, None))
+                    }
+                    Outcome::Block(r) => ReturnVariant::Normal(r),
+                }
+            },
+            |r: i64| r,
+        )
+    }
+
+    fn next_handler_id(&mut self) -> u64 {
+        self.next_id += 1;
+        self.next_id
+    }
+
+    /// remove handlers of syscall `name` registered by [`Interceptor::on`],
+    /// [`Interceptor::on_fn`] and [`Interceptor::on_return_of`].
+    pub fn off(&mut self, name: &str) -> &mut Self {
+        self.syscalls.retain(|sc| sc.name != name);
+        self
+    }
+
+    /// intercept syscalls of a task only if `f` returns `true` for its tid and tgid, e.g.
+    /// the worker threads of a server but not the acceptor, as threads are tasks sharing
+    /// a tgid (the pid of the process) with their own tids.
+    ///
+    /// It's called at every syscall enter, so the set of tasks can change while running,
+    /// e.g. kept in a `Rc<RefCell<HashSet<i32>>>`. Syscalls of the other tasks are sent
+    /// to kernel unchanged and are not reported to any handler, including the ones of
+    /// [`Interceptor::on_event`].
+    pub fn filter_tasks(&mut self, f: impl FnMut(i32, i32) -> bool + 'static) -> &mut Self {
+        self.task_filter = Some(Box::new(f));
+        self
+    }
+
+    /// a handle to change this interceptor from other threads while it's running, e.g. to
+    /// register or remove handlers in an interactive tool, see [`Controller::apply`].
+    pub fn controller(&self) -> Controller {
+        Controller {
+            ops: self.control.clone(),
+        }
+    }
+
+    /// capture stdout and stderr of the child, which are piped and read in background,
+    /// the returned handle gets what's printed so far. It must be called before
+    /// [`Interceptor::run`].
+    ///
+    /// Other stdio settings of the [`Command`] passed to [`Interceptor::new`] (e.g. a
+    /// file or [`Stdio::null`]) are kept as is, but don't set [`Stdio::piped`] there, the
+    /// pipes are only read when captured by this.
+    ///
+    /// The run returns after all processes holding the pipes exit, e.g. a daemon
+    /// detached by [`Interceptor::on_new_tracee`] keeps it waiting.
+    pub fn capture_output(&mut self) -> CapturedOutput {
+        if let Some(cmd) = self.cmd.as_mut() {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        } else {
+            warn!("child is already spawned, its output is not captured");
+        }
+        self.output.get_or_insert_with(Default::default).clone()
+    }
+
+    /// memory mappings of tracee `pid` from `/proc/<pid>/maps`, e.g. to find out which
+    /// module a syscall is called from by [`MapEntry::contains`].
+    pub fn memory_maps(pid: i32) -> Result<Vec<MapEntry>, InterceptError> {
+        Ok(maps::read_maps(pid)?)
+    }
+
+    /// syscalls blocked and contexts kept until exit stops, e.g. applied by a
+    /// [`Controller`] to find entries leaked or stuck. Only available in debug builds or
+    /// with the `debug-state` feature.
+    #[cfg(any(debug_assertions, feature = "debug-state"))]
+    pub fn pending_state(&self) -> PendingState {
+        let mut blocked = self
+            .block_calls
+            .iter()
+            .map(|(pid, (fake_sysno, ret, entered, writes))| BlockedCall {
+                pid: *pid,
+                fake_sysno: *fake_sysno,
+                sysno: entered.orig_rax,
+                ret: *ret,
+                writes: writes.len(),
+            })
+            .collect::<Vec<_>>();
+        blocked.sort_by_key(|b| b.pid);
+        let mut contexts = self.contexts.borrow().keys().copied().collect::<Vec<_>>();
+        contexts.sort();
+        PendingState { blocked, contexts }
+    }
+
+    /// counters of memory of tracees read and written through ptrace so far, by handlers
+    /// decoding arguments and writing them back, e.g. to find handlers reading large
+    /// buffers or long strings (which are read 8 bytes at a time).
+    ///
+    /// They're updated after each stop, so ops applied by a [`Controller`] see the ones
+    /// of previous stops.
+    pub fn stats(&self) -> MemStats {
+        self.mem_stats
+    }
+
+    /// run the child process and begin intercepting.
+    ///
+    /// A tracee killed by others while stopped, e.g. in a handler, is forgotten, and the
+    /// session goes on with the rest.
+    pub fn run(&mut self) -> Result<(), InterceptError> {
+        Ok(self.run_until(None)?)
+    }
+
+    /// same as [`Interceptor::run`], but all traced processes are killed if the session
+    /// exceeds `dur`, and [`InterceptError::TimedOut`] is returned.
+    pub fn run_with_timeout(&mut self, dur: Duration) -> Result<(), InterceptError> {
+        let pid = self.spawn()?;
+        let watchdog = Arc::new(Watchdog {
+            timed_out: AtomicBool::new(false),
+            tracees: Mutex::new(HashSet::from([pid.as_raw()])),
+        });
+        let (tx, rx) = channel::<()>();
+        let handle = {
+            let watchdog = watchdog.clone();
+            thread::spawn(move || {
+                if let Err(RecvTimeoutError::Timeout) = rx.recv_timeout(dur) {
+                    watchdog.timed_out.store(true, Ordering::SeqCst);
+                    for pid in watchdog.tracees.lock().iter() {
+                        unsafe { libc::kill(*pid, libc::SIGKILL) };
+                    }
+                }
+            })
+        };
+
+        let result = self.run_until(Some(&watchdog));
+        drop(tx);
+        let _ = handle.join();
+        if watchdog.timed_out.load(Ordering::SeqCst) {
+            return Err(TimedOut(dur).into());
+        }
+
+        Ok(result?)
+    }
+
+    /// spawn a fresh child from the [`Command`] passed to [`Interceptor::new`] after the
+    /// last run returned, so [`Interceptor::run`] can be called again, e.g. to run the
+    /// same command repeatedly under the same handlers.
+    ///
+    /// Handlers and settings are kept, state of the last session is reset, including
+    /// remote memory injected into the old child. The new child is launched by
+    /// [`Interceptor::launch_spec`], other settings of the `Command` (e.g. stdio) are not
+    /// kept, output captured by [`Interceptor::capture_output`] is captured again.
+    pub fn respawn(&mut self) -> Result<(), InterceptError> {
+        if self.cmd.is_some() {
+            self.spawn()?;
+            return Ok(());
+        }
+
+        let mut launch = self.launch.clone();
+        if self.injects_remote_mem {
+            // injected again if still needed
+            launch
+                .envs
+                .retain(|(key, _)| key != "LD_PRELOAD" && key != MEM_PROT_ENV);
+        }
+        let mut cmd = launch.to_command();
+        if self.output.is_some() {
+            cmd.stdout(Stdio::piped()).stderr(Stdio::piped());
+        }
+        forget_cwd(self.tgids.keys().copied());
+        self.block_calls.clear();
+        self.redirects.clear();
+        self.contexts.borrow_mut().clear();
+        self.retries.clear();
+        self.calls.clear();
+        self.arg_updates = Default::default();
+        self.enter_args.clear();
+        self.clone_flags.clear();
+        self.tgids.clear();
+        self.skipped.clear();
+        self.untraced.clear();
+        self.pending_steps.clear();
+        self.steps.clear();
+        if self.injects_remote_mem {
+            *self.remote_mem.borrow_mut() = None;
+        }
+
+        self.cmd = Some(cmd);
+        self.pid = None;
+        self.spawn()?;
+        Ok(())
+    }
+
+    /// how the child is launched, i.e. program, arguments, environment variables and
+    /// working directory of the [`Command`] passed to [`Interceptor::new`], e.g. to log
+    /// it. Once spawned, it's the one actually launched, including `LD_PRELOAD` set to
+    /// inject remote memory.
+    pub fn launch_spec(&self) -> &LaunchSpec {
+        &self.launch
+    }
+
+    fn spawn(&mut self) -> Result<Pid> {
+        if let Some(mut cmd) = self.cmd.take() {
+            let injected = self.remote_mem.borrow().is_none();
+            self.injects_remote_mem = injected;
+            if injected && !self.preload.exists() {
+                // e.g. built without the `mem` sub-crate, interception still works
+                warn!(
+                    "{} not found, handlers can not change pointer arguments to new ones",
+                    self.preload.display()
+                );
+                *self.remote_mem.borrow_mut() = Some(Box::new(RemoteMem::missing(&self.preload)));
+            } else if injected {
+                cmd.env("LD_PRELOAD", &self.preload)
+                    .env(MEM_PROT_ENV, self.remote_mem_prot.bits().to_string());
+            }
+
+            if !self.disable_vdso || self.read_only {
+                self.warn_vdso_syscalls();
+            }
+
+            self.launch = LaunchSpec::from_command(&cmd);
+            let mut child = self.ptracer.spawn(cmd).map_err(|e| match e {
+                pete::Error::IO(e) if e.raw_os_error() == Some(libc::EPERM) => {
+                    InterceptError::PtracePermission
+                }
+                pete::Error::IO(e) => InterceptError::Spawn(e),
+                e => InterceptError::Other(e.into()),
+            })?;
+            if let Some(output) = &self.output {
+                self.output_readers = output.drain(&mut child);
+            }
+            self.pid = Some(Pid::from_raw(child.id() as i32));
+            self.new_tracee(child.id() as i32, None);
+        }
+
+        self.pid
+            .ok_or_else(|| anyhow::anyhow!("child process not spawned"))
+    }
+
+    fn run_until(&mut self, watchdog: Option<&Watchdog>) -> Result<()> {
+        self.spawn()?;
+        set_verify_writes(self.verify_writes);
+        set_remote_mem_guard(self.remote_mem_guard);
+        set_track_cwd(self.track_cwd);
+        // accesses made outside of this run are not counted
+        take_mem_stats();
+        while let Some(mut tracee) = self.ptracer.wait()? {
+            if self.untraced.remove(&tracee.pid.as_raw()) {
+                if let Some(watchdog) = watchdog {
+                    watchdog.tracees.lock().remove(&tracee.pid.as_raw());
+                }
+
+                self.detach(tracee);
+                continue;
+            }
+
+            if let Some(watchdog) = watchdog {
+                if watchdog.timed_out.load(Ordering::SeqCst) {
+                    // the tracee must be restarted from its exit stop to finish dying
+                    unsafe { libc::kill(tracee.pid.as_raw(), libc::SIGKILL) };
+                    let _ = self.ptracer.restart(tracee, Restart::Continue);
+                    continue;
+                }
+
+                let mut tracees = watchdog.tracees.lock();
+                match tracee.stop {
+                    Stop::Exiting { .. } | Stop::Signaling { .. } => {
+                        tracees.remove(&tracee.pid.as_raw());
+                    }
+                    Stop::Clone { new } | Stop::Fork { new } | Stop::Vfork { new } => {
+                        tracees.insert(tracee.pid.as_raw());
+                        tracees.insert(new.as_raw());
+                    }
+                    _ => {
+                        tracees.insert(tracee.pid.as_raw());
+                    }
+                }
+            }
+
+            let ops = take(&mut *self.control.lock());
+            for op in ops {
+                op(self);
+            }
+
+            let pid = tracee.pid.as_raw();
+            let stopped = match self.on_stop(&mut tracee) {
+                Err(e) if self.skips_failure(&e) && tracee.registers().is_ok() => {
+                    warn!("pid = {}: changes skipped: {:#}", pid, e);
+                    self.forget_syscall(pid);
+                    Ok(())
+                }
+                r => r,
+            };
+            self.mem_stats.add(take_mem_stats());
+            let died = match stopped {
+                // killed by others, e.g. `SIGKILL`, any operation on it fails, and its
+                // exit is reported later if at all
+                Err(e) if tracee_died(&e) || tracee.registers().is_err() => {
+                    warn!("pid = {}: tracee died while handled: {:#}", pid, e);
+                    true
+                }
+                Err(e) => return Err(e),
+                Ok(()) => {
+                    let restart = if self.steps.contains_key(&pid) {
+                        Restart::Step
+                    } else {
+                        Restart::Syscall
+                    };
+                    match self.ptracer.restart(tracee, restart) {
+                        Err(e) if e.tracee_died() => true,
+                        r => r.map(|_| false)?,
+                    }
+                }
+            };
+            if died {
+                self.forget_tracee(pid);
+                if let Some(watchdog) = watchdog {
+                    watchdog.tracees.lock().remove(&pid);
+                }
+            }
+        }
+
+        for reader in take(&mut self.output_readers) {
+            let _ = reader.join();
+        }
+        Ok(())
+    }
+
+    fn on_stop(&mut self, tracee: &mut Tracee) -> Result<()> {
+        let mut regs = tracee.registers()?;
+        let pc = regs.rip;
+        let Tracee { pid, stop, .. } = *tracee;
+
+        match stop {
+            Stop::SyscallEnter => {
+                let tgid = self.tgid(pid.as_raw());
+                if !self
+                    .task_filter
+                    .as_mut()
+                    .is_none_or(|f| f(pid.as_raw(), tgid))
+                {
+                    self.skipped.insert(pid.as_raw());
+                    return Ok(());
+                }
+                let syscall = syscall_name(regs.orig_rax);
+                debug!(
+                    "pid = {}, pc = {:x}: [{}] {:?}\nregs: {:x?}",
+                    pid, pc, syscall, stop, regs
+                );
+
+                if !self.sinks.is_empty() || !self.raw_handlers.is_empty() {
+                    self.last_call_id += 1;
+                    self.calls
+                        .insert(pid.as_raw(), (self.last_call_id, Instant::now()));
+                }
+
+                let origin = regs;
+                let discard = self.discards(&syscall);
+                let (dirty, mut discarded) =
+                    discard_writes(discard, || self.on_enter(tracee, &mut regs, &syscall));
+                if dirty? {
+                    if discard {
+                        discarded.extend(self.discard_registers(pid.as_raw(), &origin, &regs));
+                    } else {
+                        tracee.set_registers(regs)?;
+                    }
+                }
+
+                // flags decide whether the new task is a thread, see `Stop::Clone`
+                let sent = if discard { &origin } else { &regs };
+                let flags = match syscall.as_str() {
+                    "clone" => Some(sent.rdi),
+                    "clone3" => Some(CloneArgs::read_remote(tracee, sent.rdi, sent.rsi).flags),
+                    "fork" => Some(0),
+                    "vfork" => Some((libc::CLONE_VM | libc::CLONE_VFORK) as u64),
+                    _ => None,
+                };
+                if let Some(flags) = flags {
+                    self.clone_flags.insert(pid.as_raw(), flags);
+                }
+
+                if discard && !self.dry_run && !discarded.is_empty() {
+                    warn!("pid = {}: changes to {} discarded", pid, syscall);
+                }
+
+                if !self.sinks.is_empty() {
+                    self.emit(pid.as_raw(), Phase::Enter, &origin, discarded);
+                }
+
+                // no exit stop follows unless blocked, nothing to keep for it
+                if NON_RETURNING.contains(&syscall.as_str())
+                    && !self.block_calls.contains_key(&pid.as_raw())
+                    && !self.redirects.contains_key(&pid.as_raw())
+                {
+                    self.forget_syscall(pid.as_raw());
+                }
+            }
+            Stop::SyscallExit => {
+                self.tgid(pid.as_raw());
+                self.clone_flags.remove(&pid.as_raw());
+                if self.disable_vdso
+                    && !self.read_only
+                    && regs.rax == 0
+                    && matches!(syscall_name(regs.orig_rax).as_str(), "execve" | "execveat")
+                {
+                    match hide_vdso(tracee, regs.rsp) {
+                        Ok(true) => debug!("pid = {}: vDSO hidden", pid),
+                        Ok(false) => debug!("pid = {}: no vDSO found", pid),
+                        Err(e) => warn!("pid = {}: hide vDSO error: {:?}", pid, e),
+                    }
+                }
+                if self.track_cwd
+                    && regs.rax == 0
+                    && matches!(syscall_name(regs.orig_rax).as_str(), "chdir" | "fchdir")
+                {
+                    // threads of the process share the working directory
+                    let tgid = self.tgid(pid.as_raw());
+                    let threads = self.tgids.iter().filter(|(_, t)| **t == tgid);
+                    forget_cwd(threads.map(|(p, _)| *p));
+                }
+                if self.skipped.remove(&pid.as_raw()) {
+                    self.release_remote_mem(pid.as_raw());
+                    return Ok(());
+                }
+                let mut discarded = Vec::new();
+                // tracked by thread rather than `orig_rax`, which may not be the fake sysno
+                // any more, e.g. if a signal interrupted it
+                if let Some((sysno, block_call_ret, entered, writes)) =
+                    self.block_calls.remove(&pid.as_raw())
+                {
+                    if sysno != regs.orig_rax {
+                        debug!(
+                            "pid = {}: blocked call exits with sysno {}, entered as {}",
+                            pid, regs.orig_rax, sysno
+                        );
+                    }
+                    debug!("block call sysno: {}, ret: {}", sysno, block_call_ret);
+                    // the caller sees nothing changed but the return value and memory
+                    // written for it, and the blocked syscall is reported instead of the
+                    // fake one
+                    regs = entered;
+                    regs.rax = block_call_ret;
+                    tracee.set_registers(regs)?;
+                    for (addr, data) in writes {
+                        if let Err(e) = tracee.write_remote(addr, &data) {
+                            warn!("pid = {}: write 0x{:x} on block error: {:?}", pid, addr, e);
+                        }
+                    }
+                } else if let Some((sysno, entered)) = self
+                    .redirects
+                    .remove(&pid.as_raw())
+                    .filter(|(sysno, _)| *sysno == regs.orig_rax)
+                {
+                    // registers of a new program or restored from signal frame are kept
+                    let new = syscall_name(sysno);
+                    let replaced = (matches!(new.as_str(), "execve" | "execveat") && regs.rax == 0)
+                        || restores_registers(&new);
+                    if !replaced {
+                        let ret = regs.rax;
+                        regs = entered;
+                        regs.rax = ret;
+                        tracee.set_registers(regs)?;
+                    }
+                    // handlers of the new syscall did not run at enter, skip them and
+                    // report the redirected one, which pairs with the enter event
+                    regs.orig_rax = entered.orig_rax;
+                } else {
+                    let syscall = syscall_name(regs.orig_rax);
+                    debug!(
+                        "pid = {}, pc = {:x}: [{}] {:?}\nregs: {:x?}",
+                        pid, pc, syscall, stop, regs
+                    );
+
+                    // handlers not called at enter (e.g. registered after it) are skipped
+                    let entered = self.enter_args.remove(&pid.as_raw()).unwrap_or_default();
+                    let discard = self.discards(&syscall);
+                    let (ret, changes) = self.run_post(tracee, &regs, &syscall, entered, discard);
+                    discarded.extend(changes);
+
+                    if ret != regs.rax {
+                        if discard {
+                            discarded.push(Change::Ret {
+                                old: regs.rax,
+                                new: ret,
+                            });
+                        } else {
+                            regs.rax = ret;
+                            tracee.set_registers(regs)?;
+                        }
+                    }
+
+                    if discard && !self.dry_run && !discarded.is_empty() {
+                        warn!("pid = {}: changes to {} discarded", pid, syscall);
+                    }
+                }
+
+                let mut retry = None;
+                if self.raw_handlers.iter().any(|h| h.matches(regs.orig_rax)) {
+                    let event = self.event(pid.as_raw(), Phase::Exit, &regs);
+                    for h in &mut self.raw_handlers {
+                        if h.matches(regs.orig_rax) {
+                            if let Decision::Retry(args) = (h.f)(&event) {
+                                retry = retry.or(Some(args));
+                            }
+                        }
+                    }
+                }
+
+                if !self.sinks.is_empty() {
+                    self.emit(pid.as_raw(), Phase::Exit, &regs, discarded);
+                }
+
+                self.calls.remove(&pid.as_raw());
+                self.release_remote_mem(pid.as_raw());
+                if let Some(n) = self.pending_steps.remove(&pid.as_raw()).filter(|n| *n > 0) {
+                    debug!("pid = {}: single-step {} instructions", pid, n);
+                    self.steps.insert(pid.as_raw(), n);
+                }
+                match retry {
+                    Some(args) => self.retry(tracee, &regs, args)?,
+                    None => {
+                        self.retries.remove(&pid.as_raw());
+                    }
+                }
+            }
+            Stop::SignalDelivery {
+                signal: Signal::SIGTRAP,
+            } if self.steps.contains_key(&pid.as_raw()) => {
+                let remaining = self.steps.get(&pid.as_raw()).copied().unwrap_or(1) - 1;
+                if remaining == 0 {
+                    self.steps.remove(&pid.as_raw());
+                } else {
+                    self.steps.insert(pid.as_raw(), remaining);
+                }
+
+                let event = StepEvent {
+                    pid: pid.as_raw(),
+                    remaining,
+                    regs,
+                };
+                for h in &mut self.step_handlers {
+                    h(&event);
+                }
+            }
+            Stop::Clone { new } | Stop::Fork { new } | Stop::Vfork { new } => {
+                // `clone3` without `CLONE_THREAD` may also be reported as `Stop::Clone`
+                let flags = self.clone_flags.get(&pid.as_raw()).copied();
+                let tgid = match flags {
+                    Some(flags) if flags & libc::CLONE_THREAD as u64 != 0 => {
+                        self.tgid(pid.as_raw())
+                    }
+                    Some(_) => new.as_raw(),
+                    None => proc_tgid(new.as_raw()).unwrap_or(new.as_raw()),
+                };
+                debug!("pid = {}: new task {}, tgid = {}", pid, new, tgid);
+                self.tgids.insert(new.as_raw(), tgid);
+                self.new_tracee(new.as_raw(), Some(pid.as_raw()));
+            }
+            Stop::Exec { old } if old != pid => {
+                // a non-leader thread called `execve`, it takes over the pid of the leader
+                // and the exit stop is reported with the new pid
+                let (old, new) = (old.as_raw(), pid.as_raw());
+                self.forget_syscall(new);
+                if let Some(c) = self.calls.remove(&old) {
+                    self.calls.insert(new, c);
+                }
+                if let Some(args) = self.enter_args.remove(&old) {
+                    self.enter_args.insert(new, args);
+                }
+                if let Some(b) = self.block_calls.remove(&old) {
+                    self.block_calls.insert(new, b);
+                }
+                if let Some(r) = self.redirects.remove(&old) {
+                    self.redirects.insert(new, r);
+                }
+                let mut contexts = self.contexts.borrow_mut();
+                let moved = contexts
+                    .keys()
+                    .filter(|(p, _)| *p == old)
+                    .copied()
+                    .collect::<Vec<_>>();
+                for key in moved {
+                    if let Some(c) = contexts.remove(&key) {
+                        contexts.insert((new, key.1), c);
+                    }
+                }
+                self.tgids.remove(&old);
+            }
+            Stop::Exiting { .. } | Stop::Signaling { .. } => {
+                self.forget_tracee(pid.as_raw());
+            }
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    /// handle syscall enter, returns whether `regs` is changed.
+    fn on_enter(
+        &mut self,
+        tracee: &mut Tracee,
+        regs: &mut Registers,
+        syscall: &str,
+    ) -> Result<bool> {
+        let pid = tracee.pid.as_raw();
+        let origin = *regs;
+        if !self.inherit_preload {
+            let envp = match syscall {
+                "execve" => Some(regs.rdx),
+                "execveat" => Some(regs.r10),
+                _ => None,
+            };
+            if let Some(envp) = envp {
+                let mut entry = b"LD_PRELOAD=".to_vec();
+                entry.extend(self.preload.as_os_str().as_bytes());
+                entry.push(b'\0');
+                if remove_ptr_to_ptr_entries(tracee, envp, |e| e == entry)? {
+                    debug!("remove LD_PRELOAD from {} envp", syscall);
+                }
+            }
+        }
+
+        self.arg_updates = Default::default();
+        let mut dirty = false;
+        let mut blocked = None;
+        let mut redirected = None;
+        if self.raw_handlers.iter().any(|h| h.matches(regs.orig_rax)) {
+            let event = self.event(pid, Phase::Enter, regs);
+            for h in &mut self.raw_handlers {
+                if !h.matches(regs.orig_rax) {
+                    continue;
+                }
+
+                match (h.f)(&event) {
+                    Decision::Continue | Decision::Retry(_) => {}
+                    Decision::Modify(args) => {
+                        for (i, v) in args.iter().enumerate() {
+                            if let Some(v) = v.filter(|v| *v != event.args[i]) {
+                                self.arg_updates[i] = ArgUpdate::Changed(v);
+                            }
+                        }
+                        set_args(regs, args);
+                        dirty = true;
+                    }
+                    Decision::Block(r) => {
+                        blocked = Some(r);
+                        break;
+                    }
+                    Decision::Redirect { sysno, args } => {
+                        redirected = Some((sysno, args));
+                        break;
+                    }
+                }
+            }
+        }
+
+        if let Some(r) = blocked {
+            self.block(pid, origin, regs, r, Vec::new());
+            dirty = true;
+        } else if let Some((sysno, args)) = redirected {
+            self.redirects.insert(pid, (sysno, origin));
+            debug!("redirect sysno {} -> {}", regs.orig_rax, sysno);
+            regs.orig_rax = sysno;
+            set_args(regs, args.map(Some));
+            dirty = true;
+        } else if let Some(errno) = self.denied(regs.orig_rax, syscall) {
+            debug!("pid = {}: {} not allowed", pid, syscall);
+            let ret = RegisterValue::from_signed(-(errno as i64));
+            self.block(pid, origin, regs, ret.as_unsigned(), Vec::new());
+            dirty = true;
+        } else {
+            if let Some(mem) = self.remote_mem.borrow_mut().as_mut() {
+                mem.reset();
+            }
+
+            match self.run_pre(tracee, regs, syscall)? {
+                Entered::Sent { handlers, changed } => {
+                    dirty |= changed;
+                    if !handlers.is_empty() {
+                        self.enter_args.insert(pid, handlers);
+                    }
+                }
+                Entered::Blocked { ret, writes } => {
+                    self.block(pid, origin, regs, ret, writes);
+                    dirty = true;
+                }
+            }
+        }
+
+        Ok(dirty)
+    }
+
+    /// run the pre part of `#[syscall]` handlers of `syscall` at enter in registration
+    /// order, `regs` are changed by arguments they returned. It stops at the first one
+    /// blocking the syscall, and nothing after `real!()` runs then.
+    fn run_pre(
+        &mut self,
+        tracee: &mut Tracee,
+        regs: &mut Registers,
+        syscall: &str,
+    ) -> Result<Entered> {
+        let pid = tracee.pid.as_raw();
+        let mut handlers = Vec::new();
+        let mut changed = false;
+        for sc in self.syscalls.iter_mut().filter(|sc| sc.name == syscall) {
+            let args = [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9];
+            if !sc.filter.as_mut().is_none_or(|f| f(pid, &args)) {
+                continue;
+            }
+            let (ret, requests) = enter_context(
+                tracee,
+                syscall,
+                Phase::Enter,
+                args,
+                [None; 6],
+                *regs,
+                |tracee| (sc.pre)(tracee, args[0], args[1], args[2], args[3], args[4], args[5]),
+            );
+            if let Some(n) = requests.steps {
+                self.pending_steps.insert(pid, n);
+            }
+            match ret? {
+                _ if requests.restore => {
+                    debug!("pid = {}: registers restored by handler", pid);
+                    handlers.push((sc.id, args));
+                }
+                ReturnVariantWrapper::PackedArgs(pa) => {
+                    handlers.push((sc.id, args));
+                    set_args(regs, pa.map(|a| a.map(|(v, _)| v)));
+                    for (i, a) in pa.into_iter().enumerate() {
+                        match a {
+                            Some((_, ArgUpdate::Unchanged)) | None => {}
+                            Some((_, update)) => self.arg_updates[i] = update,
+                        }
+                    }
+                    changed = true;
+                }
+                ReturnVariantWrapper::Skipped => {}
+                ReturnVariantWrapper::Normal(ret) => {
+                    self.contexts.borrow_mut().retain(|(p, _), _| *p != pid);
+                    return Ok(Entered::Blocked {
+                        ret,
+                        writes: requests.block_writes,
+                    });
+                }
+            }
+        }
+
+        Ok(Entered::Sent { handlers, changed })
+    }
+
+    /// run the post part of `#[syscall]` handlers called at enter in reverse order, with
+    /// `regs` at exit. Returns the raw return value passed to caller, and changes
+    /// discarded if `discard`.
+    fn run_post(
+        &mut self,
+        tracee: &mut Tracee,
+        regs: &Registers,
+        syscall: &str,
+        handlers: Vec<(u64, [u64; 6])>,
+        discard: bool,
+    ) -> (u64, Vec<Change>) {
+        let pid = tracee.pid.as_raw();
+        let mut discarded = Vec::new();
+        let mut ret = regs.rax;
+        for (id, args) in handlers.into_iter().rev() {
+            let buffers = self
+                .contexts
+                .borrow_mut()
+                .remove(&(pid, id))
+                .map(|c| c.buffers)
+                .unwrap_or_default();
+            let Some(sc) = self.syscalls.iter_mut().find(|sc| sc.id == id) else {
+                continue;
+            };
+
+            let mut current = *regs;
+            current.rax = ret;
+            let ((r, requests), changes) = discard_writes(discard, || {
+                enter_context(tracee, syscall, Phase::Exit, args, buffers, current, |_| {
+                    (sc.post)(ret)
+                })
+            });
+            discarded.extend(changes);
+            if let Some(n) = requests.steps {
+                self.pending_steps.insert(pid, n);
+            }
+            if requests.restore {
+                debug!("pid = {}: registers restored by handler", pid);
+            } else {
+                ret = r;
+            }
+        }
+
+        (ret, discarded)
+    }
+
+    /// handlers of syscalls served by the vDSO are likely never called
+    fn warn_vdso_syscalls(&self) {
+        let names = self.syscalls.iter().map(|sc| sc.name.to_owned()).chain(
+            self.raw_handlers
+                .iter()
+                .flat_map(|h| h.sysnos.iter().flatten())
+                .map(|sysno| syscall_name(*sysno)),
+        );
+        let mut warned = HashSet::new();
+        for name in names {
+            if VDSO_SYSCALLS.contains(&name.as_str()) && warned.insert(name.clone()) {
+                warn!(
+                    "{} is usually served by the vDSO without entering kernel, enable `disable_vdso` to intercept it",
+                    name
+                );
+            }
+        }
+    }
+
+    fn new_tracee(&mut self, pid: i32, parent: Option<i32>) {
+        let mut follow = true;
+        for h in &mut self.new_tracee_handlers {
+            follow &= h(pid, parent);
+        }
+
+        if !follow {
+            debug!("pid = {}: not followed, detach at next stop", pid);
+            self.untraced.insert(pid);
+        }
+    }
+
+    fn detach(&mut self, tracee: Tracee) {
+        let pid = tracee.pid.as_raw();
+        let sig = tracee.pending.map(|s| s as libc::c_int).unwrap_or(0);
+        // SAFETY: tracee is in ptrace-stop, pending signal is delivered after detached
+        if unsafe { libc::ptrace(libc::PTRACE_DETACH, pid, 0, sig) } != 0 {
+            warn!(
+                "detach pid {} error: {}",
+                pid,
+                std::io::Error::last_os_error()
+            );
+        }
+
+        self.forget_tracee(pid);
+    }
+
+    /// send the syscall which just returned with `regs` to kernel again, with raw
+    /// arguments changed by `args`, by rewinding the tracee to the `syscall` instruction.
+    ///
+    /// x86_64 `syscall` is always 2 bytes (`0f 05`), which is checked before rewinding,
+    /// since the tracee may not return to it, e.g. a successful `execve`.
+    fn retry(
+        &mut self,
+        tracee: &mut Tracee,
+        regs: &Registers,
+        args: [Option<u64>; 6],
+    ) -> Result<()> {
+        const SYSCALL_INSN: [u8; 2] = [0x0f, 0x05];
+
+        let pid = tracee.pid.as_raw();
+        let syscall = syscall_name(regs.orig_rax);
+        let retries = self.retries.remove(&pid).unwrap_or_default();
+        if retries >= self.max_retries {
+            warn!(
+                "pid = {}: {} retried {} times, not retried",
+                pid, syscall, retries
+            );
+            return Ok(());
+        }
+        if self.discards(&syscall) {
+            warn!("pid = {}: retry of {} discarded", pid, syscall);
+            return Ok(());
+        }
+        let pc = regs.rip.wrapping_sub(SYSCALL_INSN.len() as u64);
+        if tracee.read_memory(pc, SYSCALL_INSN.len()).ok().as_deref() != Some(&SYSCALL_INSN[..]) {
+            warn!(
+                "pid = {}: no syscall instruction at {:x}, {} not retried",
+                pid, pc, syscall
+            );
+            return Ok(());
+        }
+
+        debug!("pid = {}: retry {} at {:x}", pid, syscall, pc);
+        let mut regs = *regs;
+        regs.rip = pc;
+        regs.rax = regs.orig_rax;
+        set_args(&mut regs, args);
+        tracee.set_registers(regs)?;
+        self.retries.insert(pid, retries + 1);
+        Ok(())
+    }
+
+    /// whether `e` of handling a stop is only logged, see [`Interceptor::best_effort`]
+    fn skips_failure(&self, e: &anyhow::Error) -> bool {
+        let guarded = self.remote_mem_guard.is_some()
+            && matches!(
+                e.downcast_ref::<InterceptError>(),
+                Some(InterceptError::RemoteMemExhausted { .. })
+            );
+        self.best_effort && !guarded && !tracee_died(e)
+    }
+
+    /// drop what is kept at syscall enter of `pid` for its exit, which will never come.
+    fn forget_syscall(&mut self, pid: i32) {
+        self.calls.remove(&pid);
+        self.enter_args.remove(&pid);
+        self.block_calls.remove(&pid);
+        self.redirects.remove(&pid);
+        self.clone_flags.remove(&pid);
+        self.pending_steps.remove(&pid);
+        self.retries.remove(&pid);
+        self.skipped.remove(&pid);
+        self.contexts.borrow_mut().retain(|(p, _), _| *p != pid);
+        self.release_remote_mem(pid);
+    }
+
+    /// space of remote memory allocated for the syscall of `pid` is no longer used
+    fn release_remote_mem(&mut self, pid: i32) {
+        if !self.reset_remote_mem {
+            return;
+        }
+        if let Some(mem) = self.remote_mem.borrow_mut().as_mut() {
+            mem.release(pid);
+        }
+    }
+
+    /// clean up all state kept for `pid`, which exited, died or is detached
+    fn forget_tracee(&mut self, pid: i32) {
+        self.forget_syscall(pid);
+        forget_cwd([pid]);
+        self.steps.remove(&pid);
+        self.tgids.remove(&pid);
+    }
+
+    /// thread group id of `pid`, same as `pid` for the main thread.
+    fn tgid(&mut self, pid: i32) -> i32 {
+        *self
+            .tgids
+            .entry(pid)
+            .or_insert_with(|| proc_tgid(pid).unwrap_or(pid))
+    }
+
+    /// `entered` are registers when the syscall entered, before changed by handlers
+    fn block(
+        &mut self,
+        pid: i32,
+        entered: Registers,
+        regs: &mut Registers,
+        r: u64,
+        writes: Vec<(u64, Vec<u8>)>,
+    ) {
+        // syscall will be blocked, call a non-exists & random sysno,
+        let sysno = 512 + rand::thread_rng().gen::<u16>() as u64;
+        self.block_calls.insert(pid, (sysno, r, entered, writes));
+        debug!(
+            "block call change sysno {} -> {}. ret: {}",
+            regs.orig_rax, sysno, r
+        );
+        regs.orig_rax = sysno;
+    }
+
+    /// whether changes to `syscall` made by handlers are discarded
+    fn discards(&self, syscall: &str) -> bool {
+        self.dry_run || self.read_only || restores_registers(syscall)
+    }
+
+    /// restore `regs` changed by handlers, and returns the changes.
+    fn discard_registers(&mut self, pid: i32, origin: &Registers, regs: &Registers) -> Vec<Change> {
+        let mut changes = Vec::new();
+        let old = [
+            origin.rdi, origin.rsi, origin.rdx, origin.r10, origin.r8, origin.r9,
+        ];
+        let new = [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9];
+        for (index, (old, new)) in old.into_iter().zip(new).enumerate() {
+            if old != new {
+                changes.push(Change::Arg { index, old, new });
+            }
+        }
+
+        if regs.orig_rax != origin.orig_rax {
+            if let Some((_, r, _, writes)) = self.block_calls.remove(&pid) {
+                changes.push(Change::Block(r));
+                changes.extend(
+                    writes
+                        .into_iter()
+                        .map(|(addr, data)| Change::Memory { addr, data }),
+                );
+            } else if self.redirects.remove(&pid).is_some() {
+                changes.push(Change::Sysno {
+                    old: origin.orig_rax,
+                    new: regs.orig_rax,
+                });
+            }
+        }
+
+        changes
+    }
+
+    fn emit(&mut self, pid: i32, phase: Phase, regs: &Registers, discarded: Vec<Change>) {
+        let mut event = self.event(pid, phase, regs);
+        event.discarded = discarded;
+        for sink in &mut self.sinks {
+            sink(&event);
+        }
+    }
+
+    fn event(&self, pid: i32, phase: Phase, regs: &Registers) -> SyscallEvent {
+        let (ret, duration) = match phase {
+            Phase::Enter => (None, None),
+            Phase::Exit => (
+                Some(regs.rax),
+                self.calls.get(&pid).map(|(_, t)| t.elapsed()),
+            ),
+        };
+        SyscallEvent {
+            pid,
+            tgid: self.tgids.get(&pid).copied().unwrap_or(pid),
+            name: SYSCALL_TABLE
+                .get(&regs.orig_rax)
+                .map(String::as_str)
+                .unwrap_or("unknown"),
+            sysno: regs.orig_rax,
+            call_id: self.calls.get(&pid).map_or(0, |(id, _)| *id),
+            phase,
+            args: [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9],
+            pc: regs.rip,
+            arg_updates: match phase {
+                Phase::Enter => self.arg_updates,
+                Phase::Exit => Default::default(),
+            },
+            arg_kinds: SYSCALL_TABLE
+                .get(&regs.orig_rax)
+                .and_then(|name| SYSCALL_ARGS.get(name.as_str()))
+                .map_or(&[], Vec::as_slice),
+            ret,
+            duration,
+            discarded: Vec::new(),
+        }
+    }
+}
+
+impl RawHandler {
+    fn matches(&self, sysno: u64) -> bool {
+        self.sysnos.as_ref().is_none_or(|s| s.contains(&sysno))
+    }
+}
+
+fn set_args(regs: &mut Registers, args: [Option<u64>; 6]) {
+    macro_rules! set_reg {
+        ($r:ident, $n: tt) => {
+            if let Some(v) = args[$n] {
+                regs.$r = v;
+            }
+        };
+    }
+
+    set_reg!(rdi, 0);
+    set_reg!(rsi, 1);
+    set_reg!(rdx, 2);
+    set_reg!(r10, 3);
+    set_reg!(r8, 4);
+    set_reg!(r9, 5);
+}
+
+fn glob_match(pattern: &[u8], name: &[u8]) -> bool {
+    match (pattern.first(), name.first()) {
+        (None, None) => true,
+        (Some(b'*'), _) => {
+            glob_match(&pattern[1..], name) || (!name.is_empty() && glob_match(pattern, &name[1..]))
+        }
+        (Some(b'?'), Some(_)) => glob_match(&pattern[1..], &name[1..]),
+        (Some(p), Some(n)) if p == n => glob_match(&pattern[1..], &name[1..]),
+        _ => false,
+    }
+}
+
+/// A handle to change an [`Interceptor`] from other threads, see
+/// [`Interceptor::controller`].
+///
+/// Handlers are not shared between threads, changes are queued and applied by the
+/// interceptor itself at the next stop of any tracee, before it's handled.
+#[derive(Clone)]
+pub struct Controller {
+    ops: Arc<Mutex<Vec<ControlOp>>>,
+}
+
+impl Controller {
+    /// run `f` with the interceptor at the next stop, e.g. to register handlers by
+    /// [`Interceptor::on_fn`] or remove them by [`Interceptor::off`]. Handlers created
+    /// inside `f` do not need to be `Send`.
+    ///
+    /// A syscall already entered when a handler is registered is not handled at its exit.
+    /// Nothing is applied while all tracees are blocked in syscalls, e.g. `wait4`.
+    pub fn apply(&self, f: impl FnOnce(&mut Interceptor) + Send + 'static) {
+        self.ops.lock().push(Box::new(f));
+    }
+}
+
+struct Watchdog {
+    timed_out: AtomicBool,
+    tracees: Mutex<HashSet<i32>>,
+}
+
+/// A fake macro that actually does nothing.
+/// It will be detected in `proc_macro_attribute` and changes intercept logic.
+///
+/// It's detected as a top-level statement of a `#[syscall]` fn, called by a path ending
+/// with `real`, e.g. `interceptor_rs::real!()`. Otherwise, e.g. renamed by `use ... as`
+/// or nested in a block, it's expanded here and warns, as the syscall would be blocked.
+#[macro_export]
+macro_rules! real {
+    ($($args: tt),*) => {
+        let _ = $crate::REAL_NOT_DETECTED;
+        let _ = ($($args),*);
+    };
+}
+
+#[doc(hidden)]
+#[deprecated(
+    note = "`real!()` is not detected by `#[syscall]`, so the syscall is blocked, call it as a top-level statement by a path ending with `real`"
+)]
+pub const REAL_NOT_DETECTED: () = ();
+
+/// whether `e` is caused by the tracee being gone, i.e. `ESRCH`
+fn tracee_died(e: &anyhow::Error) -> bool {
+    e.chain().any(|e| {
+        e.downcast_ref::<pete::Error>()
+            .is_some_and(pete::Error::tracee_died)
+            || e.downcast_ref::<std::io::Error>()
+                .is_some_and(|e| e.raw_os_error() == Some(libc::ESRCH))
+    })
+}
+
+/// known syscalls at most 2 edits away from `name`, closest first, e.g. `openat` for
+/// `openatt`
+fn similar_syscalls(name: &str) -> Vec<&'static str> {
+    let mut similar = SYSCALL_TABLE
+        .values()
+        .map(|n| (edit_distance(name.as_bytes(), n.as_bytes()), n.as_str()))
+        .filter(|(d, _)| *d <= 2)
+        .collect::<Vec<_>>();
+    similar.sort();
+    similar.into_iter().take(3).map(|(_, n)| n).collect()
+}
+
+/// Levenshtein distance of `a` and `b`
+fn edit_distance(a: &[u8], b: &[u8]) -> usize {
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (i, x) in a.iter().enumerate() {
+        let mut diag = row[0];
+        row[0] = i + 1;
+        for (j, y) in b.iter().enumerate() {
+            let next = (diag + usize::from(x != y))
+                .min(row[j] + 1)
+                .min(row[j + 1] + 1);
+            diag = row[j + 1];
+            row[j + 1] = next;
+        }
+    }
+    row[b.len()]
+}
+
+/// number of syscall `name` on the target architecture, by the table handlers are
+/// registered against, `None` if it's not known, e.g. `Some(257)` for `openat` on
+/// x86_64 and `Some(56)` on aarch64.
+pub fn syscall_number(name: &str) -> Option<u64> {
+    SYSCALL_NUMBERS.get(name).copied()
+}
+
+fn syscall_name(sysno: u64) -> String {
+    SYSCALL_TABLE
+        .get(&sysno)
+        .cloned()
+        .unwrap_or_else(|| format!("unknown (syscall no = 0x{:x})", sysno))
+}
+
+/// syscalls without exit stop when succeed
+const NON_RETURNING: [&str; 2] = ["exit", "exit_group"];
+
+/// syscalls restoring registers of caller, e.g. from signal frame
+fn restores_registers(syscall: &str) -> bool {
+    syscall == "rt_sigreturn"
+}
+
+type SyscallTable = HashMap<u64, String>;
+static SYSCALL_TABLE: Lazy<SyscallTable> = Lazy::new(load_syscall_table);
+static SYSCALL_NUMBERS: Lazy<HashMap<&str, u64>> = Lazy::new(load_syscall_numbers);
+static SYSCALL_ARGS: Lazy<HashMap<&str, Vec<ArgKind>>> = Lazy::new(load_syscall_args);
+/// syscall number, name and kinds of arguments, `-` if unknown (not implemented), blank
+/// lines and lines starting with `#` are ignored. Tables of other architectures are
+/// generated by `data/generate.py`
+#[cfg(not(any(
+    feature = "host-syscalls",
+    target_arch = "aarch64",
+    target_arch = "x86"
+)))]
+const SYSCALLS: &str = include_str!("data/syscalls_x64.tsv");
+#[cfg(all(not(feature = "host-syscalls"), target_arch = "aarch64"))]
+const SYSCALLS: &str = include_str!("data/syscalls_aarch64.tsv");
+#[cfg(all(not(feature = "host-syscalls"), target_arch = "x86"))]
+const SYSCALLS: &str = include_str!("data/syscalls_x86.tsv");
+/// generated by `build.rs` from kernel headers of the build host
+#[cfg(feature = "host-syscalls")]
+const SYSCALLS: &str = include_str!(concat!(env!("OUT_DIR"), "/syscalls.tsv"));
+
+/// number, name and kinds of arguments (if any) of syscalls in `table`, malformed lines
+/// are skipped with a warning.
+pub(crate) fn syscall_entries(table: &str) -> impl Iterator<Item = (u64, &str, Option<&str>)> {
+    table.lines().enumerate().filter_map(|(i, line)| {
+        let line = line.trim_end_matches('\r');
+        if line.trim().is_empty() || line.trim_start().starts_with('#') {
+            return None;
+        }
+
+        let mut fields = line.split('\t');
+        let call_no = fields.next().and_then(|no| no.trim().parse::<u64>().ok());
+        let name = fields.next().map(str::trim).filter(|name| !name.is_empty());
+        match (call_no, name) {
+            (Some(call_no), Some(name)) => Some((call_no, name, fields.next())),
+            _ => {
+                warn!("malformed syscall table line {}: {:?}", i + 1, line);
+                None
+            }
+        }
+    })
+}
+
+fn load_syscall_table() -> SyscallTable {
+    syscall_entries(SYSCALLS)
+        .map(|(call_no, name, _)| (call_no, name.to_owned()))
+        .collect()
+}
+
+/// numbers by name, the lowest one is taken if a name appears more than once, e.g. the
+/// x32 variants of `ioctl` or `execve` numbered from 512 on x86_64
+fn load_syscall_numbers() -> HashMap<&'static str, u64> {
+    let mut numbers = HashMap::new();
+    for (call_no, name, _) in syscall_entries(SYSCALLS) {
+        numbers
+            .entry(name)
+            .and_modify(|no: &mut u64| *no = (*no).min(call_no))
+            .or_insert(call_no);
+    }
+    numbers
+}
+
+fn load_syscall_args() -> HashMap<&'static str, Vec<ArgKind>> {
+    syscall_entries(SYSCALLS)
+        .filter_map(|(_, name, kinds)| {
+            let kinds = kinds?
+                .split_terminator(',')
+                .map(|kind| match kind.trim() {
+                    "int" => Some(ArgKind::Int),
+                    "fd" => Some(ArgKind::Fd),
+                    "ptr" => Some(ArgKind::Ptr),
+                    "str" => Some(ArgKind::Str),
+                    "flags" => Some(ArgKind::Flags),
+                    _ => None,
+                })
+                .collect::<Option<_>>()?;
+            Some((name, kinds))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::syscall_entries;
+
+    /// number of `name` in `table`
+    fn number(table: &str, name: &str) -> Option<u64> {
+        syscall_entries(table).find_map(|(no, n, _)| (n == name).then_some(no))
+    }
+
+    #[test]
+    fn bundled_syscall_tables() {
+        let x64 = include_str!("data/syscalls_x64.tsv");
+        assert_eq!(number(x64, "read"), Some(0));
+        assert_eq!(number(x64, "openat"), Some(257));
+        assert_eq!(
+            syscall_entries(x64).find(|(_, n, _)| *n == "write"),
+            Some((1, "write", Some("fd,ptr,int")))
+        );
+
+        let aarch64 = include_str!("data/syscalls_aarch64.tsv");
+        assert_eq!(number(aarch64, "openat"), Some(56));
+        assert_eq!(number(aarch64, "newfstatat"), Some(79));
+        assert_eq!(number(aarch64, "clone3"), Some(435));
+        // only the `*at` variants exist
+        assert_eq!(number(aarch64, "open"), None);
+
+        let x86 = include_str!("data/syscalls_x86.tsv");
+        assert_eq!(number(x86, "openat"), Some(295));
+        assert_eq!(number(x86, "open"), Some(5));
+
+        // every line but comments is an entry
+        for table in [x64, aarch64, x86] {
+            let lines = table.lines().filter(|l| !l.starts_with('#')).count();
+            assert_eq!(syscall_entries(table).count(), lines);
+        }
+    }
+
+    #[test]
+    fn malformed_syscall_table_lines() {
+        let table = "# comment\n\n0\tread\tfd,ptr,int\r\nx\tbad\n1\n2\topen\n";
+        assert_eq!(
+            syscall_entries(table).collect::<Vec<_>>(),
+            [(0, "read", Some("fd,ptr,int")), (2, "open", None)]
+        );
+    }
 }
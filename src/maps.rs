@@ -0,0 +1,66 @@
+use anyhow::{anyhow, Context, Result};
+use std::{fs::read_to_string, str::FromStr};
+
+/// A line of `/proc/<pid>/maps`, see [`Interceptor::memory_maps`](crate::Interceptor::memory_maps).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MapEntry {
+    pub start: u64,
+    pub end: u64,
+    /// e.g. `r-xp`
+    pub perms: String,
+    pub offset: u64,
+    /// file path or pseudo name like `[heap]`, `None` for anonymous mapping
+    pub path: Option<String>,
+}
+
+impl MapEntry {
+    pub fn contains(&self, addr: u64) -> bool {
+        self.start <= addr && addr < self.end
+    }
+
+    pub fn is_executable(&self) -> bool {
+        self.perms.as_bytes().get(2) == Some(&b'x')
+    }
+}
+
+impl FromStr for MapEntry {
+    type Err = anyhow::Error;
+
+    /// parse a line like
+    /// `7f1c2a000000-7f1c2a022000 r--p 00000000 08:01 1234   /usr/lib/libc.so.6`
+    fn from_str(line: &str) -> Result<Self> {
+        let mut rest = line;
+        let mut fields = [""; 5];
+        for field in &mut fields {
+            rest = rest.trim_start();
+            let end = rest.find(' ').unwrap_or(rest.len());
+            (*field, rest) = rest.split_at(end);
+        }
+
+        let [range, perms, offset, _dev, _inode] = fields;
+        let (start, end) = range
+            .split_once('-')
+            .ok_or_else(|| anyhow!("invalid address range in maps line: {}", line))?;
+        let hex = |s: &str| {
+            u64::from_str_radix(s, 16).with_context(|| format!("invalid maps line: {}", line))
+        };
+        // path may contain spaces, it's the rest of the line
+        let path = rest.trim_start();
+
+        Ok(Self {
+            start: hex(start)?,
+            end: hex(end)?,
+            perms: perms.to_owned(),
+            offset: hex(offset)?,
+            path: (!path.is_empty()).then(|| path.to_owned()),
+        })
+    }
+}
+
+pub(crate) fn read_maps(pid: i32) -> Result<Vec<MapEntry>> {
+    read_to_string(format!("/proc/{}/maps", pid))
+        .with_context(|| format!("read memory maps of pid {} error", pid))?
+        .lines()
+        .map(str::parse)
+        .collect()
+}
@@ -0,0 +1,56 @@
+use parking_lot::Mutex;
+use std::{
+    io::{ErrorKind, Read},
+    process::Child,
+    sync::Arc,
+    thread::{self, JoinHandle},
+};
+
+/// stdout and stderr of the child captured by
+/// [`Interceptor::capture_output`](crate::Interceptor::capture_output), it can be cloned
+/// and read from other threads while the interceptor is running.
+#[derive(Debug, Clone, Default)]
+pub struct CapturedOutput {
+    stdout: Arc<Mutex<Vec<u8>>>,
+    stderr: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CapturedOutput {
+    /// stdout printed so far, all of it once [`Interceptor::run`](crate::Interceptor::run)
+    /// returned
+    pub fn stdout(&self) -> Vec<u8> {
+        self.stdout.lock().clone()
+    }
+
+    /// same as [`CapturedOutput::stdout`], but of stderr
+    pub fn stderr(&self) -> Vec<u8> {
+        self.stderr.lock().clone()
+    }
+
+    /// drain pipes of `child` into the buffers in background, until all processes
+    /// holding them exit
+    pub(crate) fn drain(&self, child: &mut Child) -> Vec<JoinHandle<()>> {
+        let mut readers = Vec::new();
+        if let Some(stdout) = child.stdout.take() {
+            readers.push(drain_into(stdout, self.stdout.clone()));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            readers.push(drain_into(stderr, self.stderr.clone()));
+        }
+        readers
+    }
+}
+
+fn drain_into(mut pipe: impl Read + Send + 'static, buf: Arc<Mutex<Vec<u8>>>) -> JoinHandle<()> {
+    thread::spawn(move || {
+        let mut chunk = [0; 4096];
+        loop {
+            match pipe.read(&mut chunk) {
+                Ok(0) => break,
+                Ok(n) => buf.lock().extend_from_slice(&chunk[..n]),
+                Err(e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(_) => break,
+            }
+        }
+    })
+}
@@ -0,0 +1,182 @@
+use crate::{ptr::ReadRemote, SyscallContext};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    ffi::OsStr,
+    fs::read_link,
+    os::unix::ffi::OsStrExt,
+    path::{Component, Path, PathBuf},
+};
+
+thread_local! {
+    /// working directory of each tracee, `None` if not tracked, see
+    /// [`Interceptor::track_cwd`](crate::Interceptor::track_cwd)
+    static CWDS: RefCell<Option<HashMap<i32, PathBuf>>> = const { RefCell::new(None) };
+}
+
+/// What a path syscall does with the path, see [`PathSyscallCtx::op`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PathOp {
+    /// `open`, `creat`, `openat` and `openat2`
+    Open,
+    /// `stat`, and `newfstatat` / `statx` following symlinks
+    Stat,
+    /// `lstat`, and `newfstatat` / `statx` with `AT_SYMLINK_NOFOLLOW`
+    Lstat,
+    /// `access`, `faccessat` and `faccessat2`
+    Access,
+    /// `readlink` and `readlinkat`
+    Readlink,
+    /// `unlink`, `unlinkat` and `rmdir`
+    Unlink,
+}
+
+/// A path syscall normalized by
+/// [`Interceptor::on_path_syscalls`](crate::Interceptor::on_path_syscalls).
+#[derive(Debug, Clone)]
+pub struct PathSyscallCtx {
+    /// pid of the tracee
+    pub pid: i32,
+    /// syscall name, e.g. `newfstatat`
+    pub name: &'static str,
+    pub op: PathOp,
+    /// path as passed by the caller, may be relative
+    pub path: PathBuf,
+    /// directory fd of `*at` syscalls, may be `AT_FDCWD`, `None` for the others
+    pub dirfd: Option<i32>,
+    /// `path` joined to the directory it's relative to (`dirfd` or working directory of
+    /// the tracee), `.` and `..` are removed lexically and symlinks are not followed
+    pub resolved_path: PathBuf,
+}
+
+/// A path syscall: name, operation, index of path, index of dirfd and index of flags
+/// telling `AT_SYMLINK_NOFOLLOW`.
+pub(crate) struct PathSyscall {
+    pub(crate) name: &'static str,
+    op: PathOp,
+    path: usize,
+    dirfd: Option<usize>,
+    nofollow: Option<usize>,
+}
+
+const fn path_syscall(
+    name: &'static str,
+    op: PathOp,
+    path: usize,
+    dirfd: Option<usize>,
+    nofollow: Option<usize>,
+) -> PathSyscall {
+    PathSyscall {
+        name,
+        op,
+        path,
+        dirfd,
+        nofollow,
+    }
+}
+
+pub(crate) static PATH_SYSCALLS: [PathSyscall; 16] = [
+    path_syscall("open", PathOp::Open, 0, None, None),
+    path_syscall("creat", PathOp::Open, 0, None, None),
+    path_syscall("openat", PathOp::Open, 1, Some(0), None),
+    path_syscall("openat2", PathOp::Open, 1, Some(0), None),
+    path_syscall("stat", PathOp::Stat, 0, None, None),
+    path_syscall("lstat", PathOp::Lstat, 0, None, None),
+    path_syscall("newfstatat", PathOp::Stat, 1, Some(0), Some(3)),
+    path_syscall("statx", PathOp::Stat, 1, Some(0), Some(2)),
+    path_syscall("access", PathOp::Access, 0, None, None),
+    path_syscall("faccessat", PathOp::Access, 1, Some(0), None),
+    path_syscall("faccessat2", PathOp::Access, 1, Some(0), None),
+    path_syscall("readlink", PathOp::Readlink, 0, None, None),
+    path_syscall("readlinkat", PathOp::Readlink, 1, Some(0), None),
+    path_syscall("unlink", PathOp::Unlink, 0, None, None),
+    path_syscall("unlinkat", PathOp::Unlink, 1, Some(0), None),
+    // same as `unlinkat` with `AT_REMOVEDIR`
+    path_syscall("rmdir", PathOp::Unlink, 0, None, None),
+];
+
+impl PathSyscall {
+    /// normalize the syscall being handled in `ctx`
+    pub(crate) fn read(&self, ctx: &mut SyscallContext) -> PathSyscallCtx {
+        let pid = ctx.pid();
+        let addr = ctx.arg(self.path);
+        let mut path = if addr == 0 {
+            Vec::new()
+        } else {
+            ctx.raw_tracee().read_bytes_with_nul(addr)
+        };
+        if path.last() == Some(&0) {
+            path.pop();
+        }
+        let path = PathBuf::from(OsStr::from_bytes(&path));
+        let dirfd = self.dirfd.map(|i| ctx.arg(i) as i32);
+        let op = match self.nofollow {
+            Some(i) if ctx.arg(i) as i32 & libc::AT_SYMLINK_NOFOLLOW != 0 => PathOp::Lstat,
+            _ => self.op,
+        };
+
+        PathSyscallCtx {
+            pid,
+            name: self.name,
+            op,
+            resolved_path: resolve(pid, dirfd.unwrap_or(libc::AT_FDCWD), &path),
+            path,
+            dirfd,
+        }
+    }
+}
+
+/// `path` relative to `dirfd` of tracee `pid`, left relative if the directory is unknown,
+/// e.g. the tracee exited.
+fn resolve(pid: i32, dirfd: i32, path: &Path) -> PathBuf {
+    let base = if path.is_absolute() {
+        PathBuf::from("/")
+    } else if dirfd == libc::AT_FDCWD {
+        cwd(pid).unwrap_or_default()
+    } else {
+        read_link(format!("/proc/{}/fd/{}", pid, dirfd)).unwrap_or_default()
+    };
+
+    let mut resolved = PathBuf::new();
+    for c in base.join(path).components() {
+        match c {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                resolved.pop();
+            }
+            c => resolved.push(c),
+        }
+    }
+    resolved
+}
+
+pub(crate) fn set_track_cwd(enable: bool) {
+    CWDS.with(|c| *c.borrow_mut() = enable.then(HashMap::new));
+}
+
+/// working directory of tracee `pid`, read from `/proc` once and cached if tracked
+pub(crate) fn cwd(pid: i32) -> Option<PathBuf> {
+    let read = || read_link(format!("/proc/{}/cwd", pid)).ok();
+    CWDS.with(|c| match c.borrow_mut().as_mut() {
+        Some(cwds) => match cwds.get(&pid) {
+            Some(cwd) => Some(cwd.clone()),
+            None => {
+                let cwd = read()?;
+                cwds.insert(pid, cwd.clone());
+                Some(cwd)
+            }
+        },
+        None => read(),
+    })
+}
+
+/// drop cached working directory of `pids`, e.g. changed by `chdir` or exited
+pub(crate) fn forget_cwd(pids: impl IntoIterator<Item = i32>) {
+    CWDS.with(|c| {
+        if let Some(cwds) = c.borrow_mut().as_mut() {
+            for pid in pids {
+                cwds.remove(&pid);
+            }
+        }
+    });
+}
@@ -0,0 +1,84 @@
+use crate::SyscallContext;
+
+/// What ids a syscall changes, see [`PrivilegeChange::kind`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PrivilegeKind {
+    /// `setuid`, `setreuid` and `setresuid`
+    Uid,
+    /// `setgid`, `setregid` and `setresgid`
+    Gid,
+    /// `setgroups`
+    Groups,
+}
+
+/// A privilege change normalized by
+/// [`Interceptor::on_privilege_change`](crate::Interceptor::on_privilege_change), ids are
+/// the ones requested, kernel decides whether they are allowed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrivilegeChange {
+    /// pid of the tracee
+    pub pid: i32,
+    /// syscall name, e.g. `setresuid`
+    pub name: &'static str,
+    pub kind: PrivilegeKind,
+    /// new real id, `None` if kept (`-1`). `setuid` and `setgid` request all three, but
+    /// only the effective one is changed for an unprivileged caller
+    pub real: Option<u32>,
+    pub effective: Option<u32>,
+    pub saved: Option<u32>,
+    /// new supplementary groups of [`PrivilegeKind::Groups`], empty for the others
+    pub groups: Vec<u32>,
+}
+
+/// syscalls reported by
+/// [`Interceptor::on_privilege_change`](crate::Interceptor::on_privilege_change) and
+/// their number of arguments
+pub(crate) static PRIVILEGE_SYSCALLS: [(&str, usize); 7] = [
+    ("setuid", 1),
+    ("setgid", 1),
+    ("setreuid", 2),
+    ("setregid", 2),
+    ("setresuid", 3),
+    ("setresgid", 3),
+    ("setgroups", 2),
+];
+
+/// `NGROUPS_MAX` of kernel
+const NGROUPS_MAX: usize = 65536;
+
+/// normalize the syscall `name` being handled in `ctx`
+pub(crate) fn read_change(ctx: &mut SyscallContext, name: &'static str) -> PrivilegeChange {
+    // `-1` keeps the id, it's `uid_t` so the upper 32 bits are ignored
+    let id = |i: usize| Some(ctx.arg(i) as u32).filter(|id| *id != u32::MAX);
+    let (real, effective, saved) = match name {
+        "setuid" | "setgid" => (id(0), id(0), id(0)),
+        "setreuid" | "setregid" => (id(0), id(1), None),
+        "setresuid" | "setresgid" => (id(0), id(1), id(2)),
+        _ => (None, None, None),
+    };
+    let kind = match name {
+        "setgroups" => PrivilegeKind::Groups,
+        _ if name.ends_with("gid") => PrivilegeKind::Gid,
+        _ => PrivilegeKind::Uid,
+    };
+    let groups = match kind {
+        PrivilegeKind::Groups if ctx.arg(1) != 0 => {
+            let size = (ctx.arg(0) as u32 as usize).min(NGROUPS_MAX);
+            ctx.read_memory(ctx.arg(1), size * 4)
+                .chunks_exact(4)
+                .map(|g| u32::from_ne_bytes([g[0], g[1], g[2], g[3]]))
+                .collect()
+        }
+        _ => Vec::new(),
+    };
+
+    PrivilegeChange {
+        pid: ctx.pid(),
+        name,
+        kind,
+        real,
+        effective,
+        saved,
+        groups,
+    }
+}
@@ -1,28 +1,91 @@
+#[cfg(any(feature = "struct-args", feature = "iovec"))]
+use crate::SyscallContext;
+use crate::{
+    clone::proc_tgid,
+    flags::{
+        Domain, MapProt, MountFlags, OpenFlags, PrctlOption, SockType, StatxMask, UmountFlags,
+    },
+    register::{Number, RegisterValue},
+    syscall::Unused,
+    ArgUpdate, ArgWrite, Change, InterceptError,
+};
+use anyhow::{anyhow, Context, Result};
+use inter_mem::MemBlockInfo;
 use pete::Tracee;
+#[cfg(any(feature = "struct-args", feature = "iovec"))]
+use std::ops::{Deref, DerefMut};
 use std::{
-    cell::RefCell,
-    ffi::{c_char, CStr, CString},
-    fs::read,
+    cell::{Cell, RefCell},
+    collections::{HashMap, HashSet},
+    ffi::{c_char, CString},
+    fs::{read, remove_file},
     mem::size_of,
+    path::Path,
     rc::Rc,
     thread::sleep,
-    time::Duration,
+    time::{Duration, Instant},
 };
-use tracing::warn;
+use tracing::{debug, warn};
+
+/// wrapping around remote memory within it logs a warning, unless
+/// [`Interceptor::remote_mem_guard`](crate::Interceptor::remote_mem_guard) is set
+const DEFAULT_GUARD_WINDOW: Duration = Duration::from_secs(1);
 
+/// A memory block in target used as a ring, space is reused once it wraps around, but
+/// allocations made for the syscall being handled are never reused. It starts over from
+/// the beginning once no syscall using it is in flight, see
+/// [`Interceptor::reset_remote_mem`](crate::Interceptor::reset_remote_mem).
 pub struct RemoteMem {
     base: usize,
     offset: usize,
     max: usize,
+    /// why remote memory is not available
+    disabled: Option<String>,
+    /// offset where allocations for the syscall being handled begin
+    pinned: usize,
+    /// allocations for the syscall being handled wrapped around
+    wrapped: bool,
+    /// when allocations began from the start of the block
+    cycle_start: Option<Instant>,
+    /// tracees with syscalls in flight using the block
+    live: HashSet<i32>,
+    /// protections of the block, it's executable if requested and allowed
+    #[allow(dead_code)]
+    prot: MapProt,
 }
 
 impl RemoteMem {
-    fn new(pid: i32) -> Self {
+    /// remote memory not injected, any allocation fails.
+    pub(crate) fn disabled() -> Self {
+        Self::unavailable("remote memory is disabled".to_owned())
+    }
+
+    /// `libinter_mem.so` at `preload` does not exist, any allocation fails.
+    pub(crate) fn missing(preload: &Path) -> Self {
+        Self::unavailable(format!(
+            "remote memory is unavailable, {} not found",
+            preload.display()
+        ))
+    }
+
+    fn unavailable(reason: String) -> Self {
+        Self {
+            base: 0,
+            offset: 0,
+            max: 0,
+            disabled: Some(reason),
+            pinned: 0,
+            wrapped: false,
+            cycle_start: None,
+            live: HashSet::new(),
+            prot: MapProt::empty(),
+        }
+    }
+
+    fn new(pid: i32) -> Result<Self> {
         let mut retry = 5;
         loop {
-            match read(inter_mem::mem_block_info_file().with_extension(pid.to_string()))
-                .map(|b| usize::from_le_bytes(b.try_into().unwrap_or_default()))
-            {
+            match read(inter_mem::mem_block_info_file().with_extension(pid.to_string())) {
                 Err(e) => {
                     if retry >= 0 {
                         warn!("remote memory not ready try again, error: {:?}", e);
@@ -30,17 +93,136 @@ impl RemoteMem {
                         sleep(Duration::from_millis(50));
                         continue;
                     } else {
-                        panic!("remote memory can not setup");
+                        return Err(InterceptError::RemoteMemUnavailable(format!(
+                            "remote memory can not setup for pid {}: {}",
+                            pid, e
+                        ))
+                        .into());
                     }
                 }
-                Ok(base) => {
-                    return Self {
-                        base,
+                Ok(data) => {
+                    let _ = remove_file(
+                        inter_mem::mem_block_info_file().with_extension(pid.to_string()),
+                    );
+                    let info = MemBlockInfo::from_bytes(&data)
+                        .filter(|info| info.base != 0)
+                        .with_context(|| format!("remote memory not mapped for pid {}", pid))?;
+                    let prot = MapProt::from_bits_retain(info.prot);
+                    debug!(
+                        "remote memory of pid {}: 0x{:x}, len: {}, prot: {:?}",
+                        pid, info.base, info.len, prot
+                    );
+                    return Ok(Self {
+                        base: info.base,
                         offset: 0,
-                        max: inter_mem::MEM_BLOCK_SIZE,
-                    };
+                        max: info.len,
+                        disabled: None,
+                        pinned: 0,
+                        wrapped: false,
+                        cycle_start: None,
+                        live: HashSet::new(),
+                        prot,
+                    });
+                }
+            }
+        }
+    }
+}
+
+/// Strategy allocating memory in the tracee for pointer arguments changed to new ones,
+/// e.g. a string set by [`SyscallContext::set_arg_string`](crate::SyscallContext::set_arg_string)
+/// longer than the original one, see
+/// [`Interceptor::remote_allocator`](crate::Interceptor::remote_allocator).
+///
+/// The default one is a ring over the block injected by `libinter_mem.so`.
+pub trait RemoteAllocator {
+    /// address of `len` bytes writable in the tracee, which must stay untouched until the
+    /// syscall being handled returns
+    fn alloc(&mut self, tracee: &mut Tracee, len: usize) -> Result<u64, InterceptError>;
+
+    /// a new syscall is being handled, space allocated before may be reused
+    fn reset(&mut self);
+
+    /// the syscall of tracee `pid` returned or never will, space allocated for it is no
+    /// longer used. Only called if [`Interceptor::reset_remote_mem`](crate::Interceptor::reset_remote_mem)
+    /// is enabled
+    fn release(&mut self, _pid: i32) {}
+}
+
+/// shared by handlers of an interceptor, `None` until the default one is set up
+pub(crate) type SharedAllocator = Rc<RefCell<Option<Box<dyn RemoteAllocator>>>>;
+
+impl RemoteAllocator for RemoteMem {
+    fn alloc(&mut self, tracee: &mut Tracee, size: usize) -> Result<u64, InterceptError> {
+        if let Some(reason) = &self.disabled {
+            return Err(InterceptError::RemoteMemUnavailable(format!(
+                "{}, pointer argument can not be changed to a new one",
+                reason
+            )));
+        }
+
+        if size > self.max {
+            return Err(InterceptError::WriteTooLarge {
+                size,
+                max: self.max,
+            });
+        }
+
+        let now = Instant::now();
+        let cycle_start = *self.cycle_start.get_or_insert(now);
+        let limit = if self.wrapped { self.pinned } else { self.max };
+        let mut start = self.offset;
+        if start + size > limit {
+            if self.wrapped || size > self.pinned {
+                // by a single syscall
+                return Err(InterceptError::RemoteMemExhausted {
+                    size,
+                    max: self.max,
+                });
+            }
+
+            // the whole block is allocated since the last wrap, space may still be used by
+            // syscalls not returned yet, e.g. of other threads
+            let (window, fail) = REMOTE_MEM_GUARD
+                .with(Cell::get)
+                .map_or((DEFAULT_GUARD_WINDOW, false), |w| (w, true));
+            let elapsed = now - cycle_start;
+            if elapsed < window {
+                if fail {
+                    return Err(InterceptError::RemoteMemExhausted {
+                        size,
+                        max: self.max,
+                    });
                 }
+                warn!(
+                    "remote memory of {} bytes wrapped around within {:?}, space still in use may \
+                    be overwritten, handlers may allocate more than it can hold",
+                    self.max, elapsed
+                );
             }
+
+            start = 0;
+            self.wrapped = true;
+            self.cycle_start = Some(now);
+        }
+        self.offset = start + size;
+        self.live.insert(tracee.pid.as_raw());
+
+        Ok((self.base + start) as u64)
+    }
+
+    fn reset(&mut self) {
+        self.pinned = self.offset;
+        self.wrapped = false;
+    }
+
+    fn release(&mut self, pid: i32) {
+        // space of the others may still be read by kernel
+        if self.live.remove(&pid) && self.live.is_empty() {
+            self.offset = 0;
+            self.pinned = 0;
+            self.wrapped = false;
+            self.cycle_start = None;
         }
     }
 }
@@ -79,14 +261,109 @@ pub fn write_ptr_to_ptr(p: *const *const c_char, v: Vec<Vec<u8>>) {
     let addr = p as usize;
     let mut offset = 0usize;
     v.into_iter().for_each(|x| unsafe {
-        std::ptr::copy_nonoverlapping(x.as_ptr() as *const u8, (addr + offset) as *mut u8, x.len());
+        std::ptr::copy_nonoverlapping(x.as_ptr(), (addr + offset) as *mut u8, x.len());
         offset += x.len()
     });
 }
 
-pub trait Number {
-    fn from_u64(u: u64) -> Self;
-    fn to_u64(self) -> u64;
+/// help to build a new ptr to ptr, used to add or remove entries, e.g. in `execve`'s `envp`.
+/// Entries may end with a NUL, but must not be empty or contain other NULs, which would
+/// cut the array.
+///
+/// Assign the returned ptr to the `*const *const c_char` argument in the same handler
+/// call, interceptor will rebuild the array in target and release this memory after
+/// written back. It's released when the next handler is called if not assigned.
+pub fn alloc_ptr_to_ptr(v: Vec<Vec<u8>>) -> Result<*const *const c_char, InterceptError> {
+    let mut entries = Vec::with_capacity(v.len());
+    let mut buf = Vec::new();
+    for (i, mut x) in v.into_iter().enumerate() {
+        if x.last() != Some(&b'\0') {
+            x.push(b'\0');
+        }
+        if x.len() == 1 || x[..x.len() - 1].contains(&b'\0') {
+            return Err(anyhow!("entry {} of ptr to ptr is empty or contains NUL", i).into());
+        }
+        buf.extend(&x);
+        entries.push(x);
+    }
+    buf.push(b'\0');
+
+    let buf = buf.into_boxed_slice();
+    let p = buf.as_ptr() as *const *const c_char;
+    PTR_ARRAYS.with(|a| a.borrow_mut().insert(p as usize, (buf, entries)));
+    Ok(p)
+}
+
+/// release arrays built by [`alloc_ptr_to_ptr`] but not assigned to any argument
+pub(crate) fn drop_ptr_arrays() {
+    PTR_ARRAYS.with(|a| a.borrow_mut().clear());
+}
+
+fn write_remote_ptr_to_ptr(
+    remote: &mut Tracee,
+    remote_mem: SharedAllocator,
+    entries: &[Vec<u8>],
+) -> Result<u64> {
+    // layout: "aaaa\0bbbb\0" | padding | ptr1, ptr2, NULL
+    let strings_len = entries.iter().map(Vec::len).sum::<usize>();
+    let array_offset = (strings_len + size_of::<u64>() - 1) & !(size_of::<u64>() - 1);
+    let size = array_offset + (entries.len() + 1) * size_of::<u64>();
+    let base = alloc_remote_mem(remote, remote_mem, size + size_of::<u64>() - 1)?;
+    let base = ((base + size_of::<u64>() - 1) & !(size_of::<u64>() - 1)) as u64;
+
+    let mut data = vec![0u8; size];
+    let mut offset = 0usize;
+    for (i, entry) in entries.iter().enumerate() {
+        data[offset..offset + entry.len()].copy_from_slice(entry);
+        let ptr = array_offset + i * size_of::<u64>();
+        data[ptr..ptr + size_of::<u64>()].copy_from_slice(&(base + offset as u64).to_le_bytes());
+        offset += entry.len();
+    }
+
+    remote
+        .write_remote(base, &data)
+        .context("write remote memory for ptr to ptr error")?;
+    Ok(base + array_offset as u64)
+}
+
+/// remove entries matching `f` from a ptr to ptr array inside target, e.g. `execve`'s
+/// `envp`. Following pointers are moved forward in place, so no extra memory is needed.
+pub(crate) fn remove_ptr_to_ptr_entries(
+    remote: &mut Tracee,
+    addr: u64,
+    f: impl Fn(&[u8]) -> bool,
+) -> Result<bool> {
+    if addr == 0 {
+        return Ok(false);
+    }
+
+    let mut ptrs = Vec::new();
+    let mut removed = false;
+    while let Some(ptr) = remote.read_u64(addr + (ptrs.len() * size_of::<u64>()) as u64) {
+        if ptr == 0 {
+            break;
+        }
+
+        ptrs.push(ptr);
+    }
+
+    let mut data = Vec::with_capacity((ptrs.len() + 1) * size_of::<u64>());
+    for ptr in ptrs {
+        if f(&remote.read_bytes_with_nul(ptr)) {
+            removed = true;
+        } else {
+            data.extend(ptr.to_le_bytes());
+        }
+    }
+
+    if removed {
+        data.extend(0u64.to_le_bytes());
+        remote
+            .write_remote(addr, &data)
+            .context("write remote memory for ptr to ptr error")?;
+    }
+
+    Ok(removed)
 }
 
 impl Read for *const *const c_char {
@@ -100,21 +377,36 @@ impl Read for *const *const c_char {
 
         let mut offset = 0usize;
         loop {
-            let mut buf = vec![0; size_of::<u64>()];
-            let n = remote
-                .read_memory_mut(u + offset as u64, &mut buf)
-                .unwrap_or_default();
-            let ptr = &buf[..n];
+            // NULL array is allowed, e.g. `execve(path, NULL, NULL)`
+            if u == 0 {
+                break;
+            }
+
+            // only a NULL entry terminates the array, others mean it is not readable
+            let ptr = match remote.read_u64(u + offset as u64) {
+                Some(0) => break,
+                Some(ptr) => ptr,
+                None => {
+                    warn!("ptr to ptr 0x{:x} truncated at entry {}", u, offset / 8);
+                    decode_failed(u + offset as u64);
+                    break;
+                }
+            };
             offset += size_of::<u64>();
-            let pdata =
-                remote.read_bytes_with_nul(u64::from_le_bytes(ptr.try_into().unwrap_or_default()));
+            let mut pdata = remote.read_bytes_with_nul(ptr);
             if pdata.is_empty() {
-                mbp.inner.push(b'\0');
+                warn!("ptr to ptr 0x{:x} truncated at entry {}", u, offset / 8 - 1);
+                decode_failed(ptr);
                 break;
             }
 
+            if pdata.last() != Some(&b'\0') {
+                decode_failed(ptr + pdata.len() as u64);
+                pdata.push(b'\0');
+            }
             mbp.inner.extend(pdata);
         }
+        mbp.inner.push(b'\0');
 
         mbp
     }
@@ -133,7 +425,12 @@ struct MayBePtrIter<'a> {
 }
 
 impl MayBePtr<Vec<u8>> {
-    fn iter(&self) -> MayBePtrIter {
+    /// bytes read from target as is, with the NUL if it's found, see [`bytes_of`]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.inner
+    }
+
+    fn iter(&self) -> MayBePtrIter<'_> {
         MayBePtrIter {
             offset: 0,
             inner: self,
@@ -142,7 +439,10 @@ impl MayBePtr<Vec<u8>> {
 }
 
 impl<'a> LendingIterator for MayBePtrIter<'a> {
-    type Item<'i> = &'i [u8] where Self: 'i;
+    type Item<'i>
+        = &'i [u8]
+    where
+        Self: 'i;
 
     fn next<'i>(&'i mut self) -> Option<Self::Item<'i>> {
         let mut next = 0;
@@ -164,30 +464,37 @@ impl Write<*const *const c_char> for MayBePtr<Vec<u8>> {
     fn write(
         &mut self,
         remote: &mut Tracee,
-        _remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+        remote_mem: SharedAllocator,
         v: Option<*const *const c_char>,
-    ) -> Option<u64> {
+    ) -> Result<Option<(u64, ArgUpdate)>> {
         if let Some(v) = v {
             if self.inner.as_ptr() != v as *const u8 {
-                panic!("*const *const c_char doesn't support change pointer");
+                // pointer changed, meaning user built a new array by `alloc_ptr_to_ptr`,
+                // rebuild the whole array in remote memory and repoint the argument.
+                let (_, entries) = PTR_ARRAYS
+                    .with(|a| a.borrow_mut().remove(&(v as usize)))
+                    .context("ptr to ptr changed to one not built by alloc_ptr_to_ptr")?;
+                let addr = write_remote_ptr_to_ptr(remote, remote_mem, &entries)?;
+                return Ok(Some((addr, ArgUpdate::Reallocated(addr))));
             }
 
+            let mut update = ArgUpdate::Unchanged;
             let mut offset = 0usize;
             let mut iter = self.iter();
             loop {
-                let mut buf = vec![0; size_of::<u64>()];
-                let n = remote
-                    .read_memory_mut(self.origin + offset as u64, &mut buf)
+                let addr = remote
+                    .read_u64(self.origin + offset as u64)
                     .unwrap_or_default();
-                let ptr = &buf[..n];
                 offset += size_of::<u64>();
-                let addr = u64::from_le_bytes(ptr.try_into().unwrap_or_default());
                 if addr != 0 {
                     let next = iter.next();
                     if let Some(next) = next {
-                        remote
-                            .write_memory(addr, next)
-                            .expect("write remote memory for ptr to ptr error");
+                        if remote.read_remote(addr, next.len()) != next {
+                            remote
+                                .write_remote(addr, next)
+                                .context("write remote memory for ptr to ptr error")?;
+                            update = ArgUpdate::RewrittenInPlace;
+                        }
                     } else {
                         break;
                     }
@@ -196,9 +503,9 @@ impl Write<*const *const c_char> for MayBePtr<Vec<u8>> {
                 }
             }
 
-            Some(self.origin)
+            Ok(Some((self.origin, update)))
         } else {
-            None
+            Ok(None)
         }
     }
 }
@@ -207,22 +514,147 @@ impl Ptr<*const *const c_char> for MayBePtr<Vec<u8>> {
     fn get(&self) -> *const *const c_char {
         self.inner.as_ptr() as *const *const c_char
     }
+
+    fn addrs(&self) -> Option<(usize, usize, u64)> {
+        Some((self.inner.as_ptr() as usize, self.inner.len(), self.origin))
+    }
+}
+
+pub(crate) trait WriteRemote {
+    fn write_remote(&mut self, addr: u64, data: &[u8]) -> Result<usize>;
+}
+
+impl WriteRemote for Tracee {
+    /// same as `write_memory`, but only recorded in dry-run mode, see [`discard_writes`].
+    fn write_remote(&mut self, addr: u64, data: &[u8]) -> Result<usize> {
+        if writes_discarded() {
+            // in place write backs usually keep the content, they are not changes
+            if self.read_remote(addr, data.len()) != data {
+                DISCARDED.with(|d| {
+                    if let Some(changes) = d.borrow_mut().as_mut() {
+                        changes.push(Change::Memory {
+                            addr,
+                            data: data.to_vec(),
+                        });
+                    }
+                });
+            }
+
+            return Ok(data.len());
+        }
+
+        // read-only pages (e.g. code) can only be written through `/proc/<pid>/mem`
+        let mut n = vm_write(self.pid.as_raw(), addr, data).unwrap_or_default();
+        if n < data.len() {
+            n += self
+                .write_memory(addr + n as u64, &data[n..])
+                .map_err(|_| InterceptError::MemoryAccess(addr + n as u64))?;
+        }
+        count_mem(|s| {
+            s.writes += 1;
+            s.written_bytes += n as u64;
+        });
+        if VERIFY_WRITES.with(Cell::get) {
+            let mut back = vec![0; n];
+            let m = self.read_full(addr, &mut back);
+            if let Some(i) = (0..n).find(|i| *i >= m || back[*i] != data[*i]) {
+                warn!(
+                    "pid = {}: write to 0x{:x} not landed at offset {}, expected {:x?}, read back {:x?}",
+                    self.pid,
+                    addr,
+                    i,
+                    &data[i..n],
+                    &back[i..m.max(i)]
+                );
+            }
+        }
+
+        Ok(n)
+    }
 }
 
-trait ReadRemote {
+/// read with a single `process_vm_readv`, which is much cheaper than opening
+/// `/proc/<pid>/mem` for each access. It stops at the first unreadable page, and fails if
+/// not permitted, e.g. by seccomp of a container.
+fn vm_read(pid: i32, addr: u64, buf: &mut [u8]) -> Option<usize> {
+    let local = libc::iovec {
+        iov_base: buf.as_mut_ptr() as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let remote = libc::iovec {
+        iov_base: addr as *mut libc::c_void,
+        iov_len: buf.len(),
+    };
+    let n = unsafe { libc::process_vm_readv(pid, &local, 1, &remote, 1, 0) };
+    (n >= 0).then_some(n as usize)
+}
+
+/// same as [`vm_read`], but writes, pages not writable by the tracee are not written.
+fn vm_write(pid: i32, addr: u64, data: &[u8]) -> Option<usize> {
+    let local = libc::iovec {
+        iov_base: data.as_ptr() as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    let remote = libc::iovec {
+        iov_base: addr as *mut libc::c_void,
+        iov_len: data.len(),
+    };
+    let n = unsafe { libc::process_vm_writev(pid, &local, 1, &remote, 1, 0) };
+    (n >= 0).then_some(n as usize)
+}
+
+pub(crate) trait ReadRemote {
     fn read_bytes_with_nul(&mut self, addr: u64) -> Vec<u8>;
+    fn read_full(&mut self, addr: u64, buf: &mut [u8]) -> usize;
+
+    /// read `len` bytes, shorter if the rest is not readable
+    fn read_remote(&mut self, addr: u64, len: usize) -> Vec<u8> {
+        let mut buf = vec![0; len];
+        let n = self.read_full(addr, &mut buf);
+        buf.truncate(n);
+        buf
+    }
+
+    /// `None` if not all 8 bytes are readable
+    fn read_u64(&mut self, addr: u64) -> Option<u64> {
+        let mut buf = [0; size_of::<u64>()];
+        if self.read_full(addr, &mut buf) == buf.len() {
+            Some(u64::from_le_bytes(buf))
+        } else {
+            None
+        }
+    }
 }
 
 impl ReadRemote for Tracee {
+    /// a short read (e.g. crossing page boundary) is continued from where it stopped,
+    /// returns less than `buf.len()` only if the rest is not readable.
+    fn read_full(&mut self, addr: u64, buf: &mut [u8]) -> usize {
+        let mut n = 0;
+        while n < buf.len() {
+            let at = addr + n as u64;
+            let read = vm_read(self.pid.as_raw(), at, &mut buf[n..])
+                .or_else(|| self.read_memory_mut(at, &mut buf[n..]).ok())
+                .unwrap_or_default();
+            count_mem(|s| {
+                s.reads += 1;
+                s.read_bytes += read as u64;
+            });
+            if read == 0 {
+                break;
+            }
+            n += read;
+        }
+        n
+    }
+
     fn read_bytes_with_nul(&mut self, addr: u64) -> Vec<u8> {
         let mut data = Vec::new();
         if addr != 0 {
             let mut offset = 0usize;
             loop {
                 let mut buf = vec![0; size_of::<u64>()];
-                let n = self
-                    .read_memory_mut(addr + offset as u64, &mut buf)
-                    .unwrap_or_default();
+                let n = self.read_full(addr + offset as u64, &mut buf);
                 if n == 0 {
                     break;
                 }
@@ -232,7 +664,7 @@ impl ReadRemote for Tracee {
                     break;
                 }
 
-                offset += size_of::<u64>();
+                offset += n;
                 data.extend(buf);
             }
         }
@@ -246,10 +678,11 @@ macro_rules! ptr_impl {
             type InnerType = Vec<u8>;
 
             fn read(remote: &mut Tracee, u: u64) -> MayBePtr<Vec<u8>> {
-                MayBePtr {
-                    inner: remote.read_bytes_with_nul(u),
-                    origin: u,
+                let inner = remote.read_bytes_with_nul(u);
+                if u != 0 && inner.last() != Some(&0) {
+                    decode_failed(u + inner.len() as u64);
                 }
+                MayBePtr { inner, origin: u }
             }
         }
 
@@ -257,34 +690,47 @@ macro_rules! ptr_impl {
             fn get(&self) -> $t {
                 self.inner.as_ptr() as $t
             }
+
+            fn addrs(&self) -> Option<(usize, usize, u64)> {
+                Some((self.inner.as_ptr() as usize, self.inner.len(), self.origin))
+            }
         }
 
         impl Write<$t> for MayBePtr<Vec<u8>> {
             fn write(
                 &mut self,
                 remote: &mut Tracee,
-                remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+                remote_mem: SharedAllocator,
                 v: Option<$t>,
-            ) -> Option<u64> {
+            ) -> Result<Option<(u64, ArgUpdate)>> {
                 if let Some(v) = v {
                     if self.inner.as_ptr() == v as *const u8 {
-                        // origin inner's pointer not changed by argument
+                        // origin inner's pointer not changed by argument, only write back
+                        // if the content is changed
+                        let current = remote.read_remote(self.origin, self.inner.len());
+                        if current == self.inner {
+                            return Ok(Some((self.origin, ArgUpdate::Unchanged)));
+                        }
+
                         remote
-                            .write_memory(self.origin, &self.inner)
-                            .expect("write origin memory error");
-                        Some(self.origin)
+                            .write_remote(self.origin, &self.inner)
+                            .context("write origin memory error")?;
+                        Ok(Some((self.origin, ArgUpdate::RewrittenInPlace)))
                     } else {
                         // pointer changed, meaning user allocate new memory in rust
-                        let c = unsafe { CStr::from_ptr(v).to_bytes_with_nul() };
-                        let remote_addr = alloc_remote_mem(remote, remote_mem, c.len()) as u64;
+                        let c = unsafe { CString::from_raw(v as *mut c_char) };
+                        let c = c.as_bytes_with_nul();
+                        if discard_alloc(c) {
+                            return Ok(Some((self.origin, ArgUpdate::Unchanged)));
+                        }
+                        let remote_addr = alloc_remote_mem(remote, remote_mem, c.len())? as u64;
                         remote
-                            .write_memory(remote_addr, &c)
-                            .expect("write remote memory error");
-                        drop(unsafe { CString::from_raw(v as *mut c_char) });
-                        Some(remote_addr)
+                            .write_remote(remote_addr, c)
+                            .context("write remote memory error")?;
+                        Ok(Some((remote_addr, ArgUpdate::Reallocated(remote_addr))))
                     }
                 } else {
-                    None
+                    Ok(None)
                 }
             }
         }
@@ -293,36 +739,77 @@ macro_rules! ptr_impl {
 
 fn alloc_remote_mem(
     remote: &mut Tracee,
-    remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+    remote_mem: SharedAllocator,
     size: usize,
-) -> usize {
+) -> Result<usize> {
     let mut mem = remote_mem.borrow_mut();
     if mem.is_none() {
-        *mem = Some(RemoteMem::new(remote.pid.as_raw()));
+        // info file is named by pid of the process, not the thread
+        let pid = remote.pid.as_raw();
+        *mem = Some(Box::new(RemoteMem::new(proc_tgid(pid).unwrap_or(pid))?));
     }
 
-    let mut mem = mem.as_mut().unwrap();
-    if size > mem.max {
-        panic!("changed content is too large");
-    }
+    let addr = mem.as_mut().unwrap().alloc(remote, size)?;
+    Ok(addr as usize)
+}
 
-    let addr = mem.base + mem.offset;
-    if mem.offset + size > mem.max {
-        mem.offset = 0;
-    } else {
-        mem.offset += size;
-    }
+/// record `data` as [`Change::Alloc`] instead of allocating remote memory for it inside
+/// [`discard_writes`], remote memory may not be available, e.g. in read-only mode.
+fn discard_alloc(data: &[u8]) -> bool {
+    DISCARDED.with(|d| {
+        let mut d = d.borrow_mut();
+        if let Some(changes) = d.as_mut() {
+            changes.push(Change::Alloc {
+                data: data.to_vec(),
+            });
+        }
+        d.is_some()
+    })
+}
 
-    addr
+/// write string `data` (with NUL) set by [`SyscallContext::set_arg_string`] in place at
+/// `origin`, or into remote memory, returns the new value of the argument and how it's
+/// changed.
+pub(crate) fn write_arg_string(
+    remote: &mut Tracee,
+    remote_mem: SharedAllocator,
+    origin: u64,
+    data: &[u8],
+    write: ArgWrite,
+) -> Result<(u64, ArgUpdate)> {
+    let (addr, update) = match write {
+        ArgWrite::InPlace => (origin, ArgUpdate::RewrittenInPlace),
+        ArgWrite::Allocated if discard_alloc(data) => return Ok((origin, ArgUpdate::Unchanged)),
+        ArgWrite::Allocated => {
+            let addr = alloc_remote_mem(remote, remote_mem, data.len())? as u64;
+            (addr, ArgUpdate::Reallocated(addr))
+        }
+    };
+    remote
+        .write_remote(addr, data)
+        .with_context(|| format!("write string argument to 0x{:x} error", addr))?;
+    Ok((addr, update))
 }
 
 pub trait Write<T> {
+    /// write `v` back to target, returns the new value of the argument and how it's
+    /// changed, `None` if `v` is not given.
     fn write(
         &mut self,
         remote: &mut Tracee,
-        remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+        remote_mem: SharedAllocator,
         v: Option<T>,
-    ) -> Option<u64>;
+    ) -> Result<Option<(u64, ArgUpdate)>>;
+}
+
+/// the raw value `origin` is kept if a non-pointer argument is unchanged, the upper bits
+/// of a register may not be the same as the sign extension of a 32-bit argument
+fn value_update(origin: u64, changed: bool, v: u64) -> (u64, ArgUpdate) {
+    if changed {
+        (v, ArgUpdate::Changed(v))
+    } else {
+        (origin, ArgUpdate::Unchanged)
+    }
 }
 
 macro_rules! not_ptr_impl {
@@ -332,7 +819,7 @@ macro_rules! not_ptr_impl {
 
             fn read(_: &mut Tracee, u: u64) -> MayBePtr<Self::InnerType> {
                 MayBePtr {
-                    inner: u as $t,
+                    inner: <$t>::from_reg(RegisterValue::from_unsigned(u)),
                     origin: u,
                 }
             }
@@ -342,10 +829,42 @@ macro_rules! not_ptr_impl {
             fn write(
                 &mut self,
                 _remote: &mut Tracee,
-                _remote_mem: Rc<RefCell<Option<RemoteMem>>>,
+                _remote_mem: SharedAllocator,
                 v: Option<$t>,
-            ) -> Option<u64> {
-                v.map(|x| x as u64)
+            ) -> Result<Option<(u64, ArgUpdate)>> {
+                Ok(v.map(|x| value_update(self.origin, x != self.inner, x.to_reg().as_unsigned())))
+            }
+        }
+
+        impl Ptr<$t> for MayBePtr<$t> {
+            fn get(&self) -> $t {
+                self.inner
+            }
+        }
+    };
+}
+
+macro_rules! flags_impl {
+    ($t: ty, $bits: ty) => {
+        impl Read for $t {
+            type InnerType = $t;
+
+            fn read(_: &mut Tracee, u: u64) -> MayBePtr<Self::InnerType> {
+                MayBePtr {
+                    inner: <$t>::from_reg(RegisterValue::from_unsigned(u)),
+                    origin: u,
+                }
+            }
+        }
+
+        impl Write<$t> for MayBePtr<$t> {
+            fn write(
+                &mut self,
+                _remote: &mut Tracee,
+                _remote_mem: SharedAllocator,
+                v: Option<$t>,
+            ) -> Result<Option<(u64, ArgUpdate)>> {
+                Ok(v.map(|x| value_update(self.origin, x != self.inner, x.to_reg().as_unsigned())))
             }
         }
 
@@ -356,12 +875,46 @@ macro_rules! not_ptr_impl {
         }
 
         impl Number for $t {
-            fn from_u64(u: u64) -> Self {
-                u as Self
+            fn from_reg(r: RegisterValue) -> Self {
+                <$t>::from_bits_retain(<$bits>::from_reg(r))
+            }
+
+            fn to_reg(self) -> RegisterValue {
+                self.bits().to_reg()
+            }
+        }
+    };
+}
+
+macro_rules! value_impl {
+    ($t: ty, $v: ty) => {
+        impl Read for $t {
+            type InnerType = $t;
+
+            fn read(_: &mut Tracee, u: u64) -> MayBePtr<Self::InnerType> {
+                MayBePtr {
+                    inner: Self(<$v>::from_reg(RegisterValue::from_unsigned(u))),
+                    origin: u,
+                }
             }
+        }
 
-            fn to_u64(self) -> u64 {
-                self as u64
+        impl Write<$t> for MayBePtr<$t> {
+            fn write(
+                &mut self,
+                _remote: &mut Tracee,
+                _remote_mem: SharedAllocator,
+                v: Option<$t>,
+            ) -> Result<Option<(u64, ArgUpdate)>> {
+                Ok(v.map(|x| {
+                    value_update(self.origin, x != self.inner, x.0.to_reg().as_unsigned())
+                }))
+            }
+        }
+
+        impl Ptr<$t> for MayBePtr<$t> {
+            fn get(&self) -> $t {
+                self.inner
             }
         }
     };
@@ -379,9 +932,461 @@ not_ptr_impl!(u16);
 not_ptr_impl!(u32);
 not_ptr_impl!(u64);
 not_ptr_impl!(usize);
+flags_impl!(OpenFlags, i32);
+flags_impl!(MapProt, i32);
+flags_impl!(SockType, i32);
+flags_impl!(MountFlags, u64);
+flags_impl!(UmountFlags, i32);
+flags_impl!(StatxMask, u32);
+value_impl!(Domain, i32);
+value_impl!(PrctlOption, i32);
+
+impl Read for Unused {
+    type InnerType = Unused;
+
+    fn read(_: &mut Tracee, u: u64) -> MayBePtr<Self::InnerType> {
+        MayBePtr {
+            inner: Unused,
+            origin: u,
+        }
+    }
+}
+
+impl Write<Unused> for MayBePtr<Unused> {
+    fn write(
+        &mut self,
+        _remote: &mut Tracee,
+        _remote_mem: SharedAllocator,
+        _v: Option<Unused>,
+    ) -> Result<Option<(u64, ArgUpdate)>> {
+        Ok(None)
+    }
+}
+
+impl Ptr<Unused> for MayBePtr<Unused> {
+    fn get(&self) -> Unused {
+        Unused
+    }
+}
+
+/// Plain old data which can be copied from / to target byte by byte.
+///
+/// # Safety
+/// Any bit pattern must be a valid value of the type.
+pub unsafe trait Pod: Copy + Default + 'static {}
+
+macro_rules! pod_impl {
+    ($($t: ty),*) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+pod_impl!(i8, i16, i32, i64, isize, u8, u16, u32, u64, usize);
+
+pub(crate) fn pod_bytes<T: Pod>(v: &[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(v.as_ptr() as *const u8, std::mem::size_of_val(v)) }
+}
+
+#[cfg(feature = "struct-args")]
+/// An argument pointing to `N` elements of `T`, e.g. `pipe2`'s `int fds[2]`.
+///
+/// Elements are read when syscall entered, and written back if changed by handler.
+/// Outputs filled by kernel can be read after `real!()` by [`FixedArray::read_arg`].
+#[derive(Debug, Clone, Copy)]
+pub struct FixedArray<T: Pod, const N: usize> {
+    addr: u64,
+    data: [T; N],
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> FixedArray<T, N> {
+    /// origin address inside target
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// read the array pointed by argument at `index` of the syscall being handled.
+    pub fn read_arg(ctx: &mut SyscallContext, index: usize) -> Self {
+        let addr = ctx.arg(index);
+        let mut data = [T::default(); N];
+        let buf = ctx.read_memory(addr, size_of::<[T; N]>());
+        unsafe {
+            std::ptr::copy_nonoverlapping(buf.as_ptr(), data.as_mut_ptr() as *mut u8, buf.len());
+        }
+
+        Self { addr, data }
+    }
+
+    /// write the array back to target.
+    pub fn store(&self, ctx: &mut SyscallContext) -> Result<(), InterceptError> {
+        ctx.write_memory(self.addr, pod_bytes(&self.data))
+    }
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> Deref for FixedArray<T, N> {
+    type Target = [T; N];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> DerefMut for FixedArray<T, N> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.data
+    }
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> Read for FixedArray<T, N> {
+    type InnerType = FixedArray<T, N>;
+
+    fn read(remote: &mut Tracee, u: u64) -> MayBePtr<Self::InnerType> {
+        let mut data = [T::default(); N];
+        if u != 0 {
+            let buf = remote.read_remote(u, size_of::<[T; N]>());
+            if buf.len() < size_of::<[T; N]>() {
+                decode_failed(u + buf.len() as u64);
+            }
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    buf.as_ptr(),
+                    data.as_mut_ptr() as *mut u8,
+                    buf.len(),
+                );
+            }
+        }
+
+        MayBePtr {
+            inner: FixedArray { addr: u, data },
+            origin: u,
+        }
+    }
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> Write<FixedArray<T, N>> for MayBePtr<FixedArray<T, N>> {
+    fn write(
+        &mut self,
+        remote: &mut Tracee,
+        _remote_mem: SharedAllocator,
+        v: Option<FixedArray<T, N>>,
+    ) -> Result<Option<(u64, ArgUpdate)>> {
+        if let Some(v) = v {
+            if self.origin != 0 && pod_bytes(&v.data) != pod_bytes(&self.inner.data) {
+                remote
+                    .write_remote(self.origin, pod_bytes(&v.data))
+                    .context("write remote memory for fixed array error")?;
+                return Ok(Some((self.origin, ArgUpdate::RewrittenInPlace)));
+            }
+
+            Ok(Some((self.origin, ArgUpdate::Unchanged)))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
+#[cfg(feature = "struct-args")]
+impl<T: Pod, const N: usize> Ptr<FixedArray<T, N>> for MayBePtr<FixedArray<T, N>> {
+    fn get(&self) -> FixedArray<T, N> {
+        self.inner
+    }
+}
+
+#[cfg(feature = "iovec")]
+/// An element of the iovec array passed to `readv`/`writev` and their `p*` variants.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct IoVec {
+    /// address of the buffer inside target
+    pub base: u64,
+    pub len: u64,
+}
+
+#[cfg(feature = "iovec")]
+unsafe impl Pod for IoVec {}
+
+#[cfg(feature = "iovec")]
+/// Max elements of an iovec array accepted by kernel, `UIO_MAXIOV`.
+const IOV_MAX: usize = 1024;
+
+#[cfg(feature = "iovec")]
+/// The iovec array argument of `readv`, `writev`, `preadv`, `pwritev`, `preadv2` and
+/// `pwritev2`, with the file offset and flags of the `p*` variants.
+///
+/// The count of elements is taken from the argument next to the array, so the array
+/// must be read by [`IoVecs::read_arg`] within a handler.
+#[derive(Debug, Clone)]
+pub struct IoVecs {
+    addr: u64,
+    iovecs: Vec<IoVec>,
+    offset: Option<i64>,
+    flags: Option<i32>,
+}
+
+#[cfg(feature = "iovec")]
+impl IoVecs {
+    /// origin address of the array inside target
+    pub fn addr(&self) -> u64 {
+        self.addr
+    }
+
+    /// file offset of `preadv`, `pwritev`, `preadv2` and `pwritev2`, `None` for others.
+    ///
+    /// The offset is passed in two registers as low and high parts, on x86_64 the low one
+    /// holds the whole offset. `-1` of the `*v2` variants means the current file offset.
+    pub fn offset(&self) -> Option<i64> {
+        self.offset
+    }
+
+    /// `RWF_*` flags of `preadv2` and `pwritev2`, `None` for others.
+    pub fn flags(&self) -> Option<i32> {
+        self.flags
+    }
+
+    /// read the array pointed by argument at `index` of the syscall being handled, the
+    /// argument at `index + 1` is the count of elements.
+    pub fn read_arg(ctx: &mut SyscallContext, index: usize) -> Self {
+        let addr = ctx.arg(index);
+        let mut count = ctx.arg(index + 1) as usize;
+        if count > IOV_MAX {
+            warn!("iovec count {} exceeds {}, truncated", count, IOV_MAX);
+            count = IOV_MAX;
+        }
+
+        let mut iovecs = vec![IoVec::default(); count];
+        if addr != 0 {
+            let buf = ctx.read_memory(addr, size_of::<IoVec>() * count);
+            iovecs.truncate(buf.len() / size_of::<IoVec>());
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    buf.as_ptr(),
+                    iovecs.as_mut_ptr() as *mut u8,
+                    iovecs.len() * size_of::<IoVec>(),
+                );
+            }
+        } else {
+            iovecs.clear();
+        }
+
+        // fd, iov, iovcnt, pos_l, pos_h[, flags]
+        let (offset, flags) = match ctx.name() {
+            "preadv" | "pwritev" => (Some(pos_from_hilo(ctx.arg(3), ctx.arg(4))), None),
+            "preadv2" | "pwritev2" => (
+                Some(pos_from_hilo(ctx.arg(3), ctx.arg(4))),
+                Some(ctx.arg(5) as i32),
+            ),
+            _ => (None, None),
+        };
+
+        Self {
+            addr,
+            iovecs,
+            offset,
+            flags,
+        }
+    }
+
+    /// read content of the buffers, at most `limit` bytes in total, e.g. the return value
+    /// of `readv` after `real!()`.
+    pub fn read_bufs(&self, ctx: &mut SyscallContext, limit: usize) -> Vec<Vec<u8>> {
+        let mut left = limit;
+        self.iovecs
+            .iter()
+            .map(|iov| {
+                let len = (iov.len as usize).min(left);
+                left -= len;
+                ctx.read_memory(iov.base, len)
+            })
+            .collect()
+    }
+
+    /// write the array back to target, e.g. after lengths are changed.
+    pub fn store(&self, ctx: &mut SyscallContext) -> Result<(), InterceptError> {
+        ctx.write_memory(self.addr, pod_bytes(&self.iovecs))
+    }
+}
+
+#[cfg(feature = "iovec")]
+/// offset passed as low and high parts, see `pos_from_hilo` of kernel.
+fn pos_from_hilo(low: u64, high: u64) -> i64 {
+    if size_of::<usize>() == size_of::<u64>() {
+        low as i64
+    } else {
+        ((high << 32) | (low & 0xffff_ffff)) as i64
+    }
+}
+
+#[cfg(feature = "iovec")]
+impl Deref for IoVecs {
+    type Target = [IoVec];
+
+    fn deref(&self) -> &Self::Target {
+        &self.iovecs
+    }
+}
+
+#[cfg(feature = "iovec")]
+impl DerefMut for IoVecs {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.iovecs
+    }
+}
 
 pub trait Ptr<T> {
     fn get(&self) -> T;
+
+    /// local address and length of the content read from target and its origin address
+    /// in target, `None` if it is not a pointer.
+    fn addrs(&self) -> Option<(usize, usize, u64)> {
+        None
+    }
+}
+
+/// content of an array built by [`alloc_ptr_to_ptr`], and its entries with NUL
+type PtrArray = (Box<[u8]>, Vec<Vec<u8>>);
+
+thread_local! {
+    /// arrays built by [`alloc_ptr_to_ptr`] by address
+    static PTR_ARRAYS: RefCell<HashMap<usize, PtrArray>> =
+        RefCell::new(HashMap::new());
+    static ORIGINS: RefCell<Vec<(usize, usize, u64)>> = const { RefCell::new(Vec::new()) };
+    static DISCARDED: RefCell<Option<Vec<Change>>> = const { RefCell::new(None) };
+    static VERIFY_WRITES: Cell<bool> = const { Cell::new(false) };
+    /// window of [`Interceptor::remote_mem_guard`](crate::Interceptor::remote_mem_guard)
+    static REMOTE_MEM_GUARD: Cell<Option<Duration>> = const { Cell::new(None) };
+    static MEM_STATS: Cell<MemStats> = const { Cell::new(MemStats::new()) };
+    /// first address not readable while decoding an argument
+    static DECODE_ERROR: Cell<Option<u64>> = const { Cell::new(None) };
+}
+
+/// the argument being decoded is not fully readable from `addr`
+fn decode_failed(addr: u64) {
+    DECODE_ERROR.with(|e| {
+        if e.get().is_none() {
+            e.set(Some(addr));
+        }
+    });
+}
+
+/// address where decoding the last argument failed, see [`decode_failed`]
+pub(crate) fn take_decode_error() -> Option<u64> {
+    DECODE_ERROR.with(|e| e.take())
+}
+
+/// Counters of memory accesses to target through ptrace, see
+/// [`Interceptor::stats`](crate::Interceptor::stats).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MemStats {
+    /// read operations, reading a string takes one per 8 bytes
+    pub reads: u64,
+    pub read_bytes: u64,
+    pub writes: u64,
+    pub written_bytes: u64,
+}
+
+impl MemStats {
+    const fn new() -> Self {
+        Self {
+            reads: 0,
+            read_bytes: 0,
+            writes: 0,
+            written_bytes: 0,
+        }
+    }
+
+    pub(crate) fn add(&mut self, other: MemStats) {
+        self.reads += other.reads;
+        self.read_bytes += other.read_bytes;
+        self.writes += other.writes;
+        self.written_bytes += other.written_bytes;
+    }
+}
+
+fn count_mem(f: impl FnOnce(&mut MemStats)) {
+    MEM_STATS.with(|c| {
+        let mut stats = c.get();
+        f(&mut stats);
+        c.set(stats);
+    });
+}
+
+/// counters of accesses made by this thread since the last call
+pub(crate) fn take_mem_stats() -> MemStats {
+    MEM_STATS.with(Cell::take)
+}
+
+/// whether writes to target are read back and compared, see
+/// [`Interceptor::verify_writes`](crate::Interceptor::verify_writes).
+pub(crate) fn set_verify_writes(enable: bool) {
+    VERIFY_WRITES.with(|v| v.set(enable));
+}
+
+/// window within which wrapping around remote memory fails, see
+/// [`Interceptor::remote_mem_guard`](crate::Interceptor::remote_mem_guard).
+pub(crate) fn set_remote_mem_guard(window: Option<Duration>) {
+    REMOTE_MEM_GUARD.with(|g| g.set(window));
+}
+
+/// run `f`, if `enable`, writes to target made by [`WriteRemote`] are not applied, but
+/// returned as changes.
+pub(crate) fn discard_writes<R>(enable: bool, f: impl FnOnce() -> R) -> (R, Vec<Change>) {
+    if !enable {
+        return (f(), Vec::new());
+    }
+
+    DISCARDED.with(|d| *d.borrow_mut() = Some(Vec::new()));
+    let r = f();
+    let changes = DISCARDED
+        .with(|d| d.borrow_mut().take())
+        .unwrap_or_default();
+    (r, changes)
+}
+
+/// whether called inside [`discard_writes`] which is enabled
+pub(crate) fn writes_discarded() -> bool {
+    DISCARDED.with(|d| d.borrow().is_some())
+}
+
+pub(crate) fn set_origins(addrs: impl IntoIterator<Item = Option<(usize, usize, u64)>>) {
+    ORIGINS.with(|o| *o.borrow_mut() = addrs.into_iter().flatten().collect());
+}
+
+/// get the origin address inside target of a pointer argument, e.g. to correlate with
+/// `/proc/<pid>/maps`.
+///
+/// Pointer arguments are copied from target before passing to a `#[syscall]` function,
+/// this helper maps the copied one back. It is only valid inside the function, and
+/// returns `None` for pointers not passed in as arguments.
+pub fn origin_of<T>(p: *const T) -> Option<u64> {
+    ORIGINS.with(|o| {
+        o.borrow()
+            .iter()
+            .find(|(local, ..)| *local == p as usize)
+            .map(|(.., origin)| *origin)
+    })
+}
+
+/// get the bytes read from target for a string argument exactly, with the NUL if it's
+/// found, e.g. to debug non-UTF-8 paths or binary data passed as `char *`, which
+/// [`CStr`](std::ffi::CStr) would cut or overrun if it's not terminated (memory after it
+/// not readable).
+///
+/// Same as [`origin_of`], it is only valid inside the function, and returns `None` for
+/// pointers not passed in as arguments.
+pub fn bytes_of(p: *const c_char) -> Option<Vec<u8>> {
+    ORIGINS.with(|o| {
+        o.borrow()
+            .iter()
+            .find(|(local, ..)| *local == p as usize)
+            // SAFETY: the content is alive until the function returns
+            .map(|(local, len, _)| unsafe {
+                std::slice::from_raw_parts(*local as *const u8, *len).to_vec()
+            })
+    })
 }
 
 pub struct MayBePtr<T> {
@@ -0,0 +1,81 @@
+/// A raw 64-bit register holding a syscall argument or return value, converted from and
+/// to Rust values with explicit signedness.
+///
+/// Signed values are sign-extended, e.g. `-2` of an `i32` is `0xffff_ffff_ffff_fffe`,
+/// unsigned ones are zero-extended. Reading a narrower value takes the low bits, as
+/// kernel does for `int` arguments whose upper 32 bits are undefined.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct RegisterValue(u64);
+
+impl RegisterValue {
+    pub const fn from_signed(v: i64) -> Self {
+        Self(v as u64)
+    }
+
+    pub const fn from_unsigned(v: u64) -> Self {
+        Self(v)
+    }
+
+    pub const fn from_ptr(p: usize) -> Self {
+        Self(p as u64)
+    }
+
+    /// the register as is
+    pub const fn as_unsigned(self) -> u64 {
+        self.0
+    }
+
+    pub const fn as_signed(self) -> i64 {
+        self.0 as i64
+    }
+
+    pub const fn as_ptr(self) -> usize {
+        self.0 as usize
+    }
+
+    /// low 32 bits as signed, e.g. an `int` argument
+    pub const fn as_i32(self) -> i32 {
+        self.0 as i32
+    }
+
+    /// low 32 bits as unsigned, e.g. an `unsigned int` argument
+    pub const fn as_u32(self) -> u32 {
+        self.0 as u32
+    }
+}
+
+/// Values converted from and to a register, implemented for integers and the flag types
+/// of [`flags`](crate::flags).
+pub trait Number {
+    fn from_reg(r: RegisterValue) -> Self;
+    fn to_reg(self) -> RegisterValue;
+}
+
+macro_rules! number_impl {
+    ($from: ident, $as: ident: $($t: ty),*) => {
+        $(
+            impl Number for $t {
+                fn from_reg(r: RegisterValue) -> Self {
+                    r.$as() as Self
+                }
+
+                fn to_reg(self) -> RegisterValue {
+                    RegisterValue::$from(self as _)
+                }
+            }
+        )*
+    };
+}
+
+number_impl!(from_signed, as_signed: i8, i16, i32, i64, isize);
+number_impl!(from_unsigned, as_unsigned: u8, u16, u32, u64, usize);
+
+impl Number for RegisterValue {
+    fn from_reg(r: RegisterValue) -> Self {
+        r
+    }
+
+    fn to_reg(self) -> RegisterValue {
+        self
+    }
+}
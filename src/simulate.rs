@@ -0,0 +1,96 @@
+use crate::{ptr::WriteRemote, set_args, syscall_number, Entered, InterceptError, Interceptor};
+use anyhow::anyhow;
+use pete::{Pid, Registers, Stop, Tracee};
+use std::{
+    ops::{Deref, DerefMut},
+    process::{self, Command},
+};
+
+/// Runs handlers against syscalls made up by the test, without spawning or tracing a
+/// child, e.g. to unit test the logic of a policy.
+///
+/// Handlers are registered on the [`Interceptor`] it derefs to. Pointer arguments point
+/// into the current process, which plays the tracee, so they are decoded and written
+/// back by the same path as a real run, but a pointer argument can not be changed to a
+/// new one as there is no remote memory. Only handlers registered by
+/// [`Interceptor::on`], [`Interceptor::on_fn`], [`Interceptor::on_return_of`] and the
+/// ones built on them run, and
+/// [`SyscallContext::restore_registers`](crate::SyscallContext::restore_registers) fails.
+///
+/// ```rust,ignore
+/// let mut sim = Simulator::new()?;
+/// sim.on(&openat);
+/// let path = CString::new("/etc/shadow")?;
+/// let args = [libc::AT_FDCWD as u64, path.as_ptr() as u64, 0, 0, 0, 0];
+/// assert_eq!(sim.call("openat", args, 3)?, Simulated::Blocked(-libc::EACCES as u64));
+/// ```
+pub struct Simulator {
+    interceptor: Interceptor,
+}
+
+/// What handlers did to a syscall simulated by [`Simulator::call`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Simulated {
+    /// the syscall would be sent to kernel with the raw arguments, and the raw return
+    /// value is passed to caller after handlers ran
+    Returned { args: [u64; 6], ret: u64 },
+    /// the syscall is blocked by a handler with the raw return value, memory set by
+    /// [`SyscallContext::write_on_block`](crate::SyscallContext::write_on_block) is written
+    Blocked(u64),
+}
+
+impl Simulator {
+    pub fn new() -> Result<Self, InterceptError> {
+        let mut interceptor = Interceptor::new(Command::new("true"))?;
+        interceptor.without_remote_mem();
+        Ok(Self { interceptor })
+    }
+
+    /// run handlers of syscall `name` made with raw `args`, `ret` is the raw value
+    /// returned by kernel if it's not blocked.
+    pub fn call(
+        &mut self,
+        name: &str,
+        args: [u64; 6],
+        ret: u64,
+    ) -> Result<Simulated, InterceptError> {
+        let sysno = syscall_number(name).ok_or_else(|| anyhow!("unknown syscall {}", name))?;
+        let pid = process::id() as i32;
+        let mut tracee = Tracee::new(Pid::from_raw(pid), None, Stop::SyscallEnter);
+        // SAFETY: registers are plain integers
+        let mut regs: Registers = unsafe { std::mem::zeroed() };
+        regs.orig_rax = sysno;
+        set_args(&mut regs, args.map(Some));
+
+        let interceptor = &mut self.interceptor;
+        let handlers = match interceptor.run_pre(&mut tracee, &mut regs, name)? {
+            Entered::Sent { handlers, .. } => handlers,
+            Entered::Blocked { ret, writes } => {
+                for (addr, data) in writes {
+                    tracee.write_remote(addr, &data)?;
+                }
+                return Ok(Simulated::Blocked(ret));
+            }
+        };
+
+        let sent = [regs.rdi, regs.rsi, regs.rdx, regs.r10, regs.r8, regs.r9];
+        tracee.stop = Stop::SyscallExit;
+        regs.rax = ret;
+        let (ret, _) = interceptor.run_post(&mut tracee, &regs, name, handlers, false);
+        Ok(Simulated::Returned { args: sent, ret })
+    }
+}
+
+impl Deref for Simulator {
+    type Target = Interceptor;
+
+    fn deref(&self) -> &Self::Target {
+        &self.interceptor
+    }
+}
+
+impl DerefMut for Simulator {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        &mut self.interceptor
+    }
+}
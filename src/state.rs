@@ -0,0 +1,26 @@
+/// Syscalls in flight kept by an interceptor until their exit stops, see
+/// [`Interceptor::pending_state`](crate::Interceptor::pending_state). Entries left after
+/// the tracee returned from the syscall are leaked.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PendingState {
+    /// syscalls blocked by handlers, sorted by pid
+    pub blocked: Vec<BlockedCall>,
+    /// pid and handler id of contexts kept for handlers after `real!()`, sorted
+    pub contexts: Vec<(i32, u64)>,
+}
+
+/// A syscall blocked by a handler, its sysno is replaced by a fake one and registers are
+/// restored at exit except the return value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BlockedCall {
+    pub pid: i32,
+    /// nonexistent sysno sent to kernel instead
+    pub fake_sysno: u64,
+    /// sysno when entered
+    pub sysno: u64,
+    /// raw return value passed to caller at exit
+    pub ret: u64,
+    /// number of memory writes made at exit, see
+    /// [`SyscallContext::write_on_block`](crate::SyscallContext::write_on_block)
+    pub writes: usize,
+}
@@ -0,0 +1,106 @@
+use crate::{
+    flags::StatxMask,
+    ptr::{pod_bytes, Pod},
+    InterceptError, SyscallContext,
+};
+use std::mem::size_of;
+
+/// A timestamp of [`Statx`], `struct statx_timestamp`.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct StatxTimestamp {
+    pub sec: i64,
+    pub nsec: u32,
+    reserved: i32,
+}
+
+/// The `struct statx` filled by `statx`, read after `real!()` by [`Statx::read_arg`] and
+/// written back by [`Statx::store_arg`], e.g. to fake the size of a file.
+///
+/// Kernel only fills the fields in [`Statx::valid`], callers ignore the others, so add
+/// the bit of a field faked but not filled, e.g. when `mask` of `statx` does not
+/// request it.
+///
+/// ```rust,ignore
+/// #[syscall]
+/// fn statx(dfd: i32, path: *const c_char, flags: i32, mask: StatxMask, buf: u64) -> i32 {
+///     let ret = real!(dfd, path, flags, mask, buf);
+///     if ret == 0 {
+///         with_context(|ctx| {
+///             let mut st = Statx::read_arg(ctx, 4);
+///             st.size = 0;
+///             st.mask |= StatxMask::STATX_SIZE.bits();
+///             st.store_arg(ctx, 4)
+///         })
+///         .unwrap();
+///     }
+///     ret
+/// }
+/// ```
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Statx {
+    /// fields filled, see [`Statx::valid`]
+    pub mask: u32,
+    pub blksize: u32,
+    /// `STATX_ATTR_*` flags
+    pub attributes: u64,
+    pub nlink: u32,
+    pub uid: u32,
+    pub gid: u32,
+    /// file type and mode
+    pub mode: u16,
+    spare0: u16,
+    pub ino: u64,
+    pub size: u64,
+    pub blocks: u64,
+    /// `STATX_ATTR_*` flags supported in [`Statx::attributes`]
+    pub attributes_mask: u64,
+    pub atime: StatxTimestamp,
+    pub btime: StatxTimestamp,
+    pub ctime: StatxTimestamp,
+    pub mtime: StatxTimestamp,
+    pub rdev_major: u32,
+    pub rdev_minor: u32,
+    pub dev_major: u32,
+    pub dev_minor: u32,
+    pub mnt_id: u64,
+    /// fields added by newer kernels, kept as is
+    spare: [u64; 13],
+}
+
+const _: () = assert!(size_of::<Statx>() == 256);
+
+unsafe impl Pod for Statx {}
+
+impl Statx {
+    /// read the buffer pointed by argument at `index` of the syscall being handled, it's
+    /// filled by kernel once `statx` returned `0`. Bytes not readable are left zero.
+    pub fn read_arg(ctx: &mut SyscallContext, index: usize) -> Self {
+        let mut st = Self::default();
+        let addr = ctx.arg(index);
+        if addr != 0 {
+            let buf = ctx.read_memory(addr, size_of::<Self>());
+            // SAFETY: `Statx` is plain integers, `buf` is not longer than it
+            unsafe {
+                std::ptr::copy_nonoverlapping(
+                    buf.as_ptr(),
+                    &mut st as *mut Self as *mut u8,
+                    buf.len(),
+                );
+            }
+        }
+
+        st
+    }
+
+    /// write it back to the buffer pointed by argument at `index`.
+    pub fn store_arg(&self, ctx: &mut SyscallContext, index: usize) -> Result<(), InterceptError> {
+        ctx.write_memory(ctx.arg(index), pod_bytes(std::slice::from_ref(self)))
+    }
+
+    /// fields filled by kernel, the others are meaningless
+    pub fn valid(&self) -> StatxMask {
+        StatxMask::from_bits_retain(self.mask)
+    }
+}
@@ -0,0 +1,120 @@
+use crate::{Decision, InterceptError, Interceptor, Phase, SyscallEvent};
+use futures_core::Stream;
+use std::{
+    pin::Pin,
+    process::Command,
+    task::{Context, Poll},
+    thread,
+};
+use tokio::sync::{mpsc, oneshot};
+
+/// events waiting to be consumed before the tracee is blocked
+const CAPACITY: usize = 64;
+
+/// Syscalls of a child as an async [`Stream`], the ptrace loop runs on a dedicated
+/// thread, see [`EventStream::spawn`]. Available with feature `tokio`.
+pub struct EventStream {
+    events: mpsc::Receiver<PendingEvent>,
+    result: oneshot::Receiver<Result<(), InterceptError>>,
+}
+
+/// A syscall stop waiting for a [`Decision`] of the consumer of [`EventStream`].
+///
+/// The tracee is stopped until it's replied or dropped, dropping it sends the syscall to
+/// kernel unchanged. Only [`Phase::Enter`] takes a decision, the one replied at
+/// [`Phase::Exit`] is ignored and the tracee doesn't wait for it.
+#[derive(Debug)]
+pub struct PendingEvent {
+    pub event: SyscallEvent,
+    reply: Option<oneshot::Sender<Decision>>,
+}
+
+impl PendingEvent {
+    /// apply `decision` and restart the tracee
+    pub fn reply(mut self, decision: Decision) {
+        if let Some(reply) = self.reply.take() {
+            let _ = reply.send(decision);
+        }
+    }
+}
+
+impl EventStream {
+    /// spawn `cmd` on a new thread, and stream its syscalls whose name matches the glob
+    /// `pattern` (see [`Interceptor::on_matching`]).
+    ///
+    /// `setup` configures the interceptor on that thread before it runs, e.g. to register
+    /// other handlers, since an interceptor must be used by the thread spawning the
+    /// child. Catch-all handlers registered there run before the stream sees a syscall.
+    ///
+    /// ```rust,ignore
+    /// let mut events = EventStream::spawn(cmd, "openat", |i| {
+    ///     i.without_remote_mem();
+    /// });
+    /// while let Some(pending) = events.next().await {
+    ///     pending.reply(Decision::Continue);
+    /// }
+    /// events.wait().await?;
+    /// ```
+    pub fn spawn(
+        cmd: Command,
+        pattern: &str,
+        setup: impl FnOnce(&mut Interceptor) + Send + 'static,
+    ) -> Self {
+        let (events_tx, events) = mpsc::channel(CAPACITY);
+        let (result_tx, result) = oneshot::channel();
+        let pattern = pattern.to_owned();
+        thread::spawn(move || {
+            let run = || {
+                let mut interceptor = Interceptor::new(cmd)?;
+                setup(&mut interceptor);
+                interceptor.on_matching(&pattern, move |event| {
+                    let (reply, decision) = match event.phase {
+                        Phase::Enter => {
+                            let (tx, rx) = oneshot::channel();
+                            (Some(tx), Some(rx))
+                        }
+                        Phase::Exit => (None, None),
+                    };
+                    let pending = PendingEvent {
+                        event: event.clone(),
+                        reply,
+                    };
+                    // the stream is dropped, nothing to wait for
+                    if events_tx.blocking_send(pending).is_err() {
+                        return Decision::Continue;
+                    }
+                    decision
+                        .and_then(|d| d.blocking_recv().ok())
+                        .unwrap_or(Decision::Continue)
+                });
+                interceptor.run()
+            };
+            let _ = result_tx.send(run());
+        });
+
+        Self { events, result }
+    }
+
+    /// the next syscall stop, `None` once all tracees exited
+    pub async fn next(&mut self) -> Option<PendingEvent> {
+        self.events.recv().await
+    }
+
+    /// wait for the run to finish and get its result, syscalls not consumed yet are sent
+    /// to kernel unchanged.
+    pub async fn wait(self) -> Result<(), InterceptError> {
+        let Self { events, result } = self;
+        drop(events);
+        result
+            .await
+            .unwrap_or_else(|_| Err(anyhow::anyhow!("interceptor thread panicked").into()))
+    }
+}
+
+impl Stream for EventStream {
+    type Item = PendingEvent;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.events.poll_recv(cx)
+    }
+}
@@ -1,5 +1,7 @@
 #![allow(clippy::type_complexity)]
 
+use crate::{context::TraceePtr, ArgUpdate};
+use anyhow::Result;
 use paste::paste;
 
 pub enum PassthroughVariant<A1, A2, A3, A4, A5, A6> {
@@ -45,6 +47,11 @@ trait VariantInto<A1, A2, A3, A4, A5, A6> {
     fn to_pa(self) -> PackedArgs<A1, A2, A3, A4, A5, A6>;
 }
 
+/// same as [`VariantInto`], but for a tuple of `Option`, `None` keeps the argument
+trait PartialInto<A1, A2, A3, A4, A5, A6> {
+    fn to_partial_pa(self) -> PackedArgs<A1, A2, A3, A4, A5, A6>;
+}
+
 macro_rules! impl_variant_into {
     ($($n: literal),* | $($m: tt),*) => {
         paste! {
@@ -53,6 +60,14 @@ macro_rules! impl_variant_into {
                     ($(Some(self.$n),)* $($m,)*)
                 }
             }
+
+            impl<A0, A1, A2, A3, A4, A5> PartialInto<A0, A1, A2, A3, A4, A5>
+                for ($(Option<[<A $n>]>,)*)
+            {
+                fn to_partial_pa(self) -> PackedArgs<A0, A1, A2, A3, A4, A5> {
+                    ($(self.$n,)* $($m,)*)
+                }
+            }
         }
     };
 }
@@ -109,25 +124,252 @@ impl<R, A1, A2, A3, A4, A5, A6> SysCall<R, A1, A2, A3, A4, A5, A6> {
     pub fn call_post(&self, r: R) -> R {
         (self.post)(r)
     }
+
+    /// number of arguments declared by the handler
+    pub fn arity(&self) -> usize {
+        match &self.pre {
+            Variant::Passthrough(pv) => match pv {
+                PassthroughVariant::Func0(_) => 0,
+                PassthroughVariant::Func1(_) => 1,
+                PassthroughVariant::Func2(_) => 2,
+                PassthroughVariant::Func3(_) => 3,
+                PassthroughVariant::Func4(_) => 4,
+                PassthroughVariant::Func5(_) => 5,
+                PassthroughVariant::Func6(_) => 6,
+            },
+            Variant::Block(bv) => match bv {
+                BlockVariant::Func0(_) => 0,
+                BlockVariant::Func1(_) => 1,
+                BlockVariant::Func2(_) => 2,
+                BlockVariant::Func3(_) => 3,
+                BlockVariant::Func4(_) => 4,
+                BlockVariant::Func5(_) => 5,
+                BlockVariant::Func6(_) => 6,
+            },
+        }
+    }
+}
+
+/// Placeholder of arguments a hand-written [`SysCall`] doesn't declare, it's passed to
+/// kernel unchanged, see [`SysCall::new2`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Unused;
+
+fn identity<R>(r: R) -> R {
+    r
+}
+
+macro_rules! unused {
+    ($u: ident) => {
+        Unused
+    };
+}
+
+macro_rules! impl_new {
+    ($n: literal, $($a: ident),* | $($u: ident),*) => {
+        paste! {
+            #[doc = "A [`SysCall`] taking " $n " argument(s), the others are [`Unused`]."]
+            pub type [<SysCall $n>]<R, $($a),*> = SysCall<R, $($a,)* $(unused!($u)),*>;
+
+            impl<R, $($a),*> SysCall<R, $($a,)* $(unused!($u)),*> {
+                #[doc = "a syscall `name` taking " $n " argument(s) without `#[syscall]`, `pre`"]
+                /// runs before it and returns the arguments sent to kernel, `post` gets and
+                /// returns the return value.
+                ///
+                /// ```rust,ignore
+                /// static DUP2: SysCall2<i32, i32, i32> =
+                ///     SysCall::new2("dup2", |old, new| (old, new), |r| r);
+                /// interceptor.on(&DUP2);
+                /// ```
+                pub const fn [<new $n>](
+                    name: &'static str,
+                    pre: fn($($a),*) -> ($($a,)*),
+                    post: fn(R) -> R,
+                ) -> Self {
+                    SysCall {
+                        name,
+                        pre: Variant::Passthrough(PassthroughVariant::[<Func $n>](pre)),
+                        post,
+                    }
+                }
+
+                #[doc = "same as [`SysCall::new" $n "`], but the syscall is not sent to"]
+                /// kernel, the value returned by `f` is returned to caller directly.
+                pub const fn [<block $n>](name: &'static str, f: fn($($a),*) -> R) -> Self {
+                    SysCall {
+                        name,
+                        pre: Variant::Block(BlockVariant::[<Func $n>](f)),
+                        post: identity,
+                    }
+                }
+            }
+        }
+    };
+}
+
+/// A [`SysCall`] taking no argument.
+pub type SysCall0<R> = SysCall<R, Unused, Unused, Unused, Unused, Unused, Unused>;
+
+impl<R> SysCall0<R> {
+    /// a syscall `name` taking no argument without `#[syscall]`, `pre` runs before it,
+    /// `post` gets and returns the return value.
+    pub const fn new0(name: &'static str, pre: fn(), post: fn(R) -> R) -> Self {
+        SysCall {
+            name,
+            pre: Variant::Passthrough(PassthroughVariant::Func0(pre)),
+            post,
+        }
+    }
+
+    /// same as [`SysCall::new0`], but the syscall is not sent to kernel, the value
+    /// returned by `f` is returned to caller directly.
+    pub const fn block0(name: &'static str, f: fn() -> R) -> Self {
+        SysCall {
+            name,
+            pre: Variant::Block(BlockVariant::Func0(f)),
+            post: identity,
+        }
+    }
 }
 
+impl_new!(1, A1 | _u2, _u3, _u4, _u5, _u6);
+impl_new!(2, A1, A2 | _u3, _u4, _u5, _u6);
+impl_new!(3, A1, A2, A3 | _u4, _u5, _u6);
+impl_new!(4, A1, A2, A3, A4 | _u5, _u6);
+impl_new!(5, A1, A2, A3, A4, A5 | _u6);
+impl_new!(6, A1, A2, A3, A4, A5, A6 |);
+
+/// Outcome of a closure registered by [`Interceptor::on_fn`](crate::Interceptor::on_fn),
+/// `Args` is the tuple of its arguments.
+pub enum Outcome<R, Args> {
+    /// send the syscall to kernel with the arguments, which may be changed
+    Real(Args),
+    /// syscall will not be sent to kernel, the value is returned to caller directly
+    Block(R),
+}
+
+/// Closures taking typed syscall arguments, implemented for `FnMut` with up to 6
+/// arguments returning [`Outcome`], missing arguments are padded with `u64` like
+/// `#[syscall]` does.
+pub trait Handler<Args, R, A1, A2, A3, A4, A5, A6> {
+    fn call(
+        &mut self,
+        a1: A1,
+        a2: A2,
+        a3: A3,
+        a4: A4,
+        a5: A5,
+        a6: A6,
+    ) -> ReturnVariant<R, A1, A2, A3, A4, A5, A6>;
+
+    /// number of arguments of the closure
+    fn arity(&self) -> usize;
+}
+
+macro_rules! padding {
+    ($d: ident) => {
+        u64
+    };
+}
+
+macro_rules! impl_handler {
+    ($($a: ident $v: ident),* | $($d: ident),*) => {
+        impl<F, R, $($a),*> Handler<($($a,)*), R, $($a,)* $(padding!($d)),*> for F
+        where
+            F: FnMut($($a),*) -> Outcome<R, ($($a,)*)>,
+        {
+            fn call(
+                &mut self,
+                $($v: $a,)*
+                $($d: u64,)*
+            ) -> ReturnVariant<R, $($a,)* $(padding!($d)),*> {
+                match self($($v),*) {
+                    Outcome::Real(args) => ReturnVariant::PackedArgs(args.to_pa()),
+                    Outcome::Block(r) => ReturnVariant::Normal(r),
+                }
+            }
+
+            fn arity(&self) -> usize {
+                <[&str]>::len(&[$(stringify!($a)),*])
+            }
+        }
+    };
+}
+
+impl_handler!(| _d1, _d2, _d3, _d4, _d5, _d6);
+impl_handler!(A1 a1 | _d2, _d3, _d4, _d5, _d6);
+impl_handler!(A1 a1, A2 a2 | _d3, _d4, _d5, _d6);
+impl_handler!(A1 a1, A2 a2, A3 a3 | _d4, _d5, _d6);
+impl_handler!(A1 a1, A2 a2, A3 a3, A4 a4 | _d5, _d6);
+impl_handler!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5 | _d6);
+impl_handler!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5, A6 a6 |);
+
+/// Closures taking typed syscall arguments like [`Handler`], but returning
+/// [`Outcome::Real`] with an `Option` of each argument, `None` keeps the raw argument as
+/// is, e.g. a pointer whose buffer was changed by the closure is not written back.
+pub trait PartialHandler<Args, R, A1, A2, A3, A4, A5, A6> {
+    fn call(
+        &mut self,
+        a1: A1,
+        a2: A2,
+        a3: A3,
+        a4: A4,
+        a5: A5,
+        a6: A6,
+    ) -> ReturnVariant<R, A1, A2, A3, A4, A5, A6>;
+
+    /// number of arguments of the closure
+    fn arity(&self) -> usize;
+}
+
+macro_rules! impl_partial_handler {
+    ($($a: ident $v: ident),* | $($d: ident),*) => {
+        impl<F, R, $($a),*> PartialHandler<($($a,)*), R, $($a,)* $(padding!($d)),*> for F
+        where
+            F: FnMut($($a),*) -> Outcome<R, ($(Option<$a>,)*)>,
+        {
+            fn call(
+                &mut self,
+                $($v: $a,)*
+                $($d: u64,)*
+            ) -> ReturnVariant<R, $($a,)* $(padding!($d)),*> {
+                match self($($v),*) {
+                    Outcome::Real(args) => ReturnVariant::PackedArgs(args.to_partial_pa()),
+                    Outcome::Block(r) => ReturnVariant::Normal(r),
+                }
+            }
+
+            fn arity(&self) -> usize {
+                <[&str]>::len(&[$(stringify!($a)),*])
+            }
+        }
+    };
+}
+
+impl_partial_handler!(| _d1, _d2, _d3, _d4, _d5, _d6);
+impl_partial_handler!(A1 a1 | _d2, _d3, _d4, _d5, _d6);
+impl_partial_handler!(A1 a1, A2 a2 | _d3, _d4, _d5, _d6);
+impl_partial_handler!(A1 a1, A2 a2, A3 a3 | _d4, _d5, _d6);
+impl_partial_handler!(A1 a1, A2 a2, A3 a3, A4 a4 | _d5, _d6);
+impl_partial_handler!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5 | _d6);
+impl_partial_handler!(A1 a1, A2 a2, A3 a3, A4 a4, A5 a5, A6 a6 |);
+
 pub(crate) enum ReturnVariantWrapper {
-    PackedArgs(
-        (
-            Option<u64>,
-            Option<u64>,
-            Option<u64>,
-            Option<u64>,
-            Option<u64>,
-            Option<u64>,
-        ),
-    ),
+    /// new raw arguments and how they are changed, `None` keeps the original one
+    PackedArgs([Option<(u64, ArgUpdate)>; 6]),
     Normal(u64),
+    /// handler is not run, e.g. arguments can not be decoded, nor the code after `real!()`
+    Skipped,
 }
 
 pub(crate) struct SysCallWrapper {
+    /// unique among handlers of an interceptor
+    pub(crate) id: u64,
     pub(crate) name: &'static str,
-    pub(crate) pre:
-        Box<dyn Fn(&mut pete::Tracee, u64, u64, u64, u64, u64, u64) -> ReturnVariantWrapper>,
-    pub(crate) post: Box<dyn Fn(u64) -> u64>,
+    pub(crate) pre: Box<
+        dyn FnMut(&mut TraceePtr, u64, u64, u64, u64, u64, u64) -> Result<ReturnVariantWrapper>,
+    >,
+    pub(crate) post: Box<dyn FnMut(u64) -> u64>,
+    /// predicate on pid and raw arguments, the handler is skipped if it returns `false`
+    pub(crate) filter: Option<Box<dyn FnMut(i32, &[u64; 6]) -> bool>>,
 }
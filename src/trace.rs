@@ -0,0 +1,107 @@
+use crate::{as_errno, ArgKind, Phase, SyscallEvent};
+use std::{
+    collections::HashMap,
+    ffi::CStr,
+    fs::File,
+    io::{self, LineWriter, Write},
+    os::unix::fs::FileExt,
+    path::Path,
+};
+use tracing::warn;
+
+/// strings longer than it are cut with `...`
+const STR_MAX: usize = 64;
+
+/// Writes a strace-like line `tgid pid name(args) = ret` for each syscall, see
+/// [`Interceptor::trace_to`](crate::Interceptor::trace_to).
+pub(crate) struct Tracer {
+    out: LineWriter<File>,
+    /// `tgid pid name(args)` of calls entered, by call id
+    pending: HashMap<u64, String>,
+}
+
+impl Tracer {
+    pub(crate) fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self {
+            out: LineWriter::new(File::create(path)?),
+            pending: HashMap::new(),
+        })
+    }
+
+    pub(crate) fn on_event(&mut self, e: &SyscallEvent) {
+        let line = match e.phase {
+            // never return
+            Phase::Enter if matches!(e.name, "exit" | "exit_group") => {
+                format!("{} = ?", call(e))
+            }
+            Phase::Enter => {
+                self.pending.insert(e.call_id, call(e));
+                return;
+            }
+            Phase::Exit => {
+                let call = self
+                    .pending
+                    .remove(&e.call_id)
+                    .unwrap_or_else(|| format!("{} {} {}(...)", e.tgid, e.pid, e.name));
+                let ret = e.ret.unwrap_or_default();
+                match as_errno(ret) {
+                    Some(errno) => format!("{} = -{} ({})", call, errno, strerror(errno)),
+                    None => format!("{} = {}", call, ret as i64),
+                }
+            }
+        };
+
+        if let Err(err) = writeln!(self.out, "{}", line) {
+            warn!("write trace of pid {} error: {}", e.pid, err);
+        }
+    }
+}
+
+/// `tgid pid name(args)`, strings are read from the tracee here
+fn call(e: &SyscallEvent) -> String {
+    let args = if e.arg_kinds.is_empty() {
+        e.args.iter().map(|a| format!("0x{:x}", a)).collect()
+    } else {
+        e.arg_kinds
+            .iter()
+            .zip(e.args)
+            .map(|(kind, arg)| format_arg(e.pid, *kind, arg))
+            .collect::<Vec<_>>()
+    };
+    format!("{} {} {}({})", e.tgid, e.pid, e.name, args.join(", "))
+}
+
+fn format_arg(pid: i32, kind: ArgKind, arg: u64) -> String {
+    match kind {
+        ArgKind::Int => (arg as i64).to_string(),
+        ArgKind::Fd if arg as i32 == libc::AT_FDCWD => "AT_FDCWD".to_owned(),
+        ArgKind::Fd => (arg as i32).to_string(),
+        ArgKind::Ptr | ArgKind::Str if arg == 0 => "NULL".to_owned(),
+        ArgKind::Ptr | ArgKind::Flags => format!("0x{:x}", arg),
+        ArgKind::Str => read_str(pid, arg).unwrap_or_else(|| format!("0x{:x}", arg)),
+    }
+}
+
+/// quoted string at `addr` of `pid`, cut at [`STR_MAX`] bytes
+fn read_str(pid: i32, addr: u64) -> Option<String> {
+    let mem = File::open(format!("/proc/{}/mem", pid)).ok()?;
+    let mut buf = [0; STR_MAX + 1];
+    let n = mem.read_at(&mut buf, addr).ok()?;
+    let s = &buf[..n];
+    Some(match s.iter().position(|b| *b == 0) {
+        Some(end) => format!("{:?}", String::from_utf8_lossy(&s[..end])),
+        None => format!("{:?}...", String::from_utf8_lossy(&s[..n.min(STR_MAX)])),
+    })
+}
+
+/// message of `errno`, e.g. "No such file or directory"
+fn strerror(errno: i32) -> String {
+    let mut buf = [0 as libc::c_char; 128];
+    // SAFETY: buffer is valid and NUL terminated by `strerror_r` on success
+    if unsafe { libc::strerror_r(errno, buf.as_mut_ptr(), buf.len()) } != 0 {
+        return format!("errno {}", errno);
+    }
+    unsafe { CStr::from_ptr(buf.as_ptr()) }
+        .to_string_lossy()
+        .into_owned()
+}
@@ -0,0 +1,43 @@
+use crate::ptr::ReadRemote;
+use anyhow::{Context, Result};
+use pete::Tracee;
+use std::mem::size_of;
+
+/// syscalls usually served by the vDSO without entering kernel, they are not seen by
+/// tracer unless the vDSO is hidden, see
+/// [`Interceptor::disable_vdso`](crate::Interceptor::disable_vdso).
+pub(crate) const VDSO_SYSCALLS: [&str; 5] = [
+    "clock_gettime",
+    "clock_getres",
+    "gettimeofday",
+    "time",
+    "getcpu",
+];
+
+/// turn the `AT_SYSINFO_EHDR` entry of auxv into `AT_IGNORE`, so libc of the new program
+/// does not find the vDSO and falls back to real syscalls. It must be called right after
+/// a successful exec, when `sp` points to `argc`. Returns whether the entry is found.
+pub(crate) fn hide_vdso(tracee: &mut Tracee, sp: u64) -> Result<bool> {
+    const WORD: u64 = size_of::<u64>() as u64;
+
+    // argc | argv[0..argc] | NULL | envp | NULL | auxv pairs until AT_NULL
+    let argc = tracee.read_u64(sp).context("read argc error")?;
+    let mut addr = sp + (argc + 2) * WORD;
+    while tracee.read_u64(addr).context("read envp error")? != 0 {
+        addr += WORD;
+    }
+    addr += WORD;
+
+    loop {
+        match tracee.read_u64(addr).context("read auxv error")? {
+            libc::AT_NULL => return Ok(false),
+            libc::AT_SYSINFO_EHDR => {
+                tracee
+                    .write_memory(addr, &libc::AT_IGNORE.to_le_bytes())
+                    .context("write auxv error")?;
+                return Ok(true);
+            }
+            _ => addr += 2 * WORD,
+        }
+    }
+}
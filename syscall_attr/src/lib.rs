@@ -2,9 +2,9 @@ use proc_macro2::TokenStream;
 use quote::quote;
 use std::iter::repeat;
 use syn::{
-    parse_macro_input, punctuated::Punctuated, spanned::Spanned, token::Paren, AttributeArgs,
-    Error, Expr, FnArg, Ident, ItemFn, NestedMeta, Pat, PatIdent, PatType, Result, ReturnType,
-    Stmt, Token, Type, TypeTuple,
+    parse::Parser, parse_macro_input, punctuated::Punctuated, spanned::Spanned, token::Paren,
+    AttributeArgs, Error, Expr, FnArg, Ident, ItemFn, NestedMeta, Pat, PatIdent, PatType, Result,
+    ReturnType, Stmt, Token, Type, TypeTuple,
 };
 
 #[proc_macro_attribute]
@@ -113,7 +113,8 @@ fn expand(_args: Vec<NestedMeta>, mut input: ItemFn) -> Result<proc_macro2::Toke
                     span: sig_pre.span(),
                 },
                 elems: {
-                    sig.inputs
+                    let mut elems: Punctuated<Type, Token![,]> = sig
+                        .inputs
                         .iter()
                         .filter_map(|a| {
                             if let FnArg::Typed(pt) = a {
@@ -122,10 +123,21 @@ fn expand(_args: Vec<NestedMeta>, mut input: ItemFn) -> Result<proc_macro2::Toke
                                 None
                             }
                         })
-                        .collect()
+                        .collect();
+                    // `(T)` is not a tuple
+                    if elems.len() == 1 {
+                        elems.push_punct(Token![,](sig_pre.span()));
+                    }
+                    elems
                 },
             })),
         );
+        let mut real_args = Punctuated::<Expr, Token![,]>::parse_terminated
+            .parse2(real_args.unwrap_or_default())?;
+        // `(a)` is not a tuple, others are kept as written, e.g. `(a, b,)`
+        if real_args.len() == 1 && !real_args.trailing_punct() {
+            real_args.push_punct(Token![,](real_args.span()));
+        }
         (
             quote!((#real_args)),
             quote!(interceptor_rs::syscall::Variant::<#sig_ret, #(#args),*>::Passthrough(interceptor_rs::syscall::PassthroughVariant::#fn_variant(#ident_pre))),
@@ -188,10 +200,9 @@ fn expand(_args: Vec<NestedMeta>, mut input: ItemFn) -> Result<proc_macro2::Toke
 fn is_real_macro(expr: &Expr) -> Option<TokenStream> {
     if let Expr::Macro(expr_macro) = expr {
         let mac = &expr_macro.mac;
-        if let Some(ident) = mac.path.get_ident() {
-            if *ident == "real" {
-                return Some(mac.tokens.clone());
-            }
+        // `real!()`, `interceptor_rs::real!()` or `$crate::real!()`
+        if mac.path.segments.last().is_some_and(|s| s.ident == "real") {
+            return Some(mac.tokens.clone());
         }
     }
 
@@ -0,0 +1,47 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{alloc_ptr_to_ptr, read_ptr_to_ptr, syscall};
+use std::{
+    ffi::{c_char, CStr},
+    os::unix::process::CommandExt,
+    process::Command,
+};
+
+#[syscall]
+fn execve(
+    filename: *const c_char,
+    argv: *const *const c_char,
+    mut envp: *const *const c_char,
+) -> i32 {
+    if unsafe { CStr::from_ptr(filename) }
+        .to_bytes()
+        .ends_with(b"/env")
+    {
+        let mut env = read_ptr_to_ptr(envp);
+        env.push(b"INTERCEPTED=1".to_vec());
+        envp = alloc_ptr_to_ptr(env).unwrap();
+    }
+    real!(filename, argv, envp)
+}
+
+#[test]
+fn alloc_ptr_to_ptr_rebuilt() {
+    if common::is_child() {
+        // by the traced process itself, where remote memory is injected
+        let e = Command::new("/usr/bin/env").exec();
+        panic!("exec env error: {}", e);
+    }
+
+    assert!(alloc_ptr_to_ptr(vec![b"A=1".to_vec(), vec![], b"B=2".to_vec()]).is_err());
+    assert!(alloc_ptr_to_ptr(vec![b"A=1\0B=2".to_vec()]).is_err());
+    let p = alloc_ptr_to_ptr(vec![b"A=1".to_vec(), b"B=2\0".to_vec()]).unwrap();
+    assert_eq!(read_ptr_to_ptr(p), [b"A=1\0".to_vec(), b"B=2\0".to_vec()]);
+
+    common::install_preload();
+    let content = common::run_child("alloc_ptr_to_ptr_rebuilt", |i| {
+        i.on(&execve);
+    });
+    assert!(content.contains("INTERCEPTED=1\n"), "{}", content);
+}
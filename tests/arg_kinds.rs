@@ -0,0 +1,35 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{ArgKind, Interceptor, Phase};
+use std::{cell::RefCell, collections::HashMap, fs::File, rc::Rc};
+
+#[test]
+fn arg_kinds_in_event() {
+    if common::is_child() {
+        drop(File::open("/proc/self/status").unwrap());
+        unsafe { libc::getppid() };
+        return;
+    }
+
+    let cmd = common::child_command("arg_kinds_in_event");
+    let kinds = Rc::new(RefCell::new(HashMap::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let kinds = kinds.clone();
+        interceptor.on_event(move |e| {
+            if e.phase == Phase::Enter {
+                kinds.borrow_mut().insert(e.name, e.arg_kinds);
+            }
+        });
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    let kinds = kinds.borrow();
+    use ArgKind::*;
+    assert_eq!(kinds["openat"], [Fd, Str, Flags, Int]);
+    assert_eq!(kinds["close"], [Fd]);
+    assert_eq!(kinds["getppid"], []);
+    assert_eq!(kinds["mmap"], [Ptr, Int, Flags, Flags, Fd, Int]);
+}
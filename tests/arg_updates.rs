@@ -0,0 +1,95 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, ArgUpdate, Phase};
+use parking_lot::Mutex;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    fs,
+    rc::Rc,
+};
+
+/// file names opened, in calling order
+static NAMES: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, mut flags: i32, mode: i32) -> i32 {
+    let file = unsafe { CStr::from_ptr(filename) }
+        .to_string_lossy()
+        .into_owned();
+    let name = file.rsplit('/').next().unwrap_or_default().to_owned();
+    match name.as_str() {
+        "aaaa" => unsafe {
+            let at = filename.add(file.len() - 4) as *mut u8;
+            std::ptr::copy_nonoverlapping(b"bbbb".as_ptr(), at, 4);
+        },
+        "short" => {
+            let target = CString::new(format!("{}-longer", file)).unwrap();
+            with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+        }
+        "flags" => flags |= libc::O_NOCTTY,
+        _ => {}
+    }
+    NAMES.lock().push(name);
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn arg_updates_of_enter_event() {
+    if common::is_child() {
+        common::print_files(&["same", "aaaa", "short", "flags"]);
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("arg-updates");
+    for name in ["same", "bbbb", "short-longer", "flags"] {
+        fs::write(dir.join(name), name).unwrap();
+    }
+
+    let updates = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child_in("arg_updates_of_enter_event", &dir, |i| {
+        let updates = updates.clone();
+        i.on_event(move |e| {
+            if e.name == "openat" && e.phase == Phase::Enter {
+                updates.borrow_mut().push(e.arg_updates);
+            }
+        })
+        .on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    for line in [
+        "same: same",
+        "aaaa: bbbb",
+        "short: short-longer",
+        "flags: flags",
+    ] {
+        assert!(content.contains(line), "{}", content);
+    }
+
+    let names = NAMES.lock();
+    let updates = updates.borrow();
+    assert_eq!(names.len(), updates.len());
+    let find = |name: &str| updates[names.iter().position(|n| n == name).unwrap()];
+    use ArgUpdate::*;
+    assert_eq!(find("same"), [Unchanged; 6]);
+    assert_eq!(
+        find("aaaa"),
+        [
+            Unchanged,
+            RewrittenInPlace,
+            Unchanged,
+            Unchanged,
+            Unchanged,
+            Unchanged
+        ]
+    );
+    assert!(matches!(find("short")[1], Reallocated(addr) if addr != 0));
+    assert!(matches!(
+        find("flags")[2],
+        Changed(flags) if flags as i32 & libc::O_NOCTTY != 0
+    ));
+}
@@ -0,0 +1,13 @@
+use interceptor_rs::as_errno;
+
+#[test]
+fn as_errno_boundary() {
+    assert_eq!(as_errno(-libc::EAGAIN), Some(libc::EAGAIN));
+    assert_eq!(as_errno(-libc::EAGAIN as i64 as u64), Some(libc::EAGAIN));
+    assert_eq!(as_errno(-4095i64), Some(4095));
+    assert_eq!(as_errno(-4096i64), None);
+    assert_eq!(as_errno(0i32), None);
+    assert_eq!(as_errno(3usize), None);
+    // e.g. an address returned by `mmap`
+    assert_eq!(as_errno(0xffff_ffff_ff60_0000u64), None);
+}
@@ -0,0 +1,43 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context};
+use std::{
+    ffi::{c_char, CStr, CString},
+    fs,
+};
+
+const NAME: &str = "interceptor-best-effort";
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let name = unsafe { CStr::from_ptr(filename) }.to_string_lossy();
+    if name.ends_with(NAME) {
+        // longer than the original, remote memory is needed
+        let target = CString::new(format!("/nonexistent/{:x<240}", "long")).unwrap();
+        with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn best_effort_skips_failed_changes() {
+    if common::is_child() {
+        let file = common::child_dir().join(NAME);
+        println!("read: {:?}", fs::read_to_string(file).ok());
+        return;
+    }
+
+    let dir = common::test_dir("best-effort");
+    fs::write(dir.join(NAME), "content").unwrap();
+
+    // the new path can not be written without remote memory
+    let content = common::run_child_in("best_effort_skips_failed_changes", &dir, |i| {
+        i.without_remote_mem().best_effort(true).on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    // sent to kernel unchanged
+    assert!(content.contains("read: Some(\"content\")"), "{}", content);
+}
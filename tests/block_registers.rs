@@ -0,0 +1,58 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Decision, Phase};
+use std::arch::asm;
+
+/// make syscall `sysno` with `rdi`, returns the return value and `rdi` after it
+fn syscall_keeping_rdi(sysno: i64, rdi: u64) -> (i64, u64) {
+    let (ret, after): (i64, u64);
+    unsafe {
+        asm!(
+            "syscall",
+            inlateout("rax") sysno => ret,
+            inlateout("rdi") rdi => after,
+            lateout("rcx") _,
+            lateout("r11") _,
+        );
+    }
+    (ret, after)
+}
+
+#[test]
+fn registers_kept_across_block() {
+    if common::is_child() {
+        let (ret, rdi) = syscall_keeping_rdi(libc::SYS_fsync, 0x1234);
+        println!("fsync: {} 0x{:x}", ret, rdi);
+        let (ret, rdi) = syscall_keeping_rdi(libc::SYS_getppid, 0x5678);
+        println!(
+            "getppid: {} 0x{:x}",
+            ret == unsafe { libc::getpid() } as i64,
+            rdi
+        );
+        return;
+    }
+
+    let content = common::run_child("registers_kept_across_block", |i| {
+        i.without_remote_mem()
+            // the argument changed by the first handler must not leak to the caller
+            .on_matching("fsync", |e| match e.phase {
+                Phase::Enter => Decision::Modify([Some(u64::MAX), None, None, None, None, None]),
+                Phase::Exit => Decision::Continue,
+            })
+            .on_matching("fsync", |e| match e.phase {
+                Phase::Enter => Decision::Block(7),
+                Phase::Exit => Decision::Continue,
+            })
+            .on_matching("getppid", |e| match e.phase {
+                Phase::Enter => Decision::Redirect {
+                    sysno: libc::SYS_getpid as u64,
+                    args: [0; 6],
+                },
+                Phase::Exit => Decision::Continue,
+            });
+    });
+    assert!(content.contains("fsync: 7 0x1234\n"), "{}", content);
+    assert!(content.contains("getppid: true 0x5678\n"), "{}", content);
+}
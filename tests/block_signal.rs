@@ -0,0 +1,38 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Decision, Phase};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+static HANDLED: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn on_usr1(_: libc::c_int) {
+    HANDLED.fetch_add(1, Ordering::SeqCst);
+}
+
+#[test]
+fn block_with_signal_pending() {
+    if common::is_child() {
+        unsafe { libc::signal(libc::SIGUSR1, on_usr1 as *const () as libc::sighandler_t) };
+        let r = unsafe { libc::syscall(libc::SYS_fsync, 0) };
+        println!("fsync: {} handled: {}", r, HANDLED.load(Ordering::SeqCst));
+        let r = unsafe { libc::syscall(libc::SYS_fsync, 0) };
+        println!("fsync again: {}", r);
+        return;
+    }
+
+    let content = common::run_child("block_with_signal_pending", |i| {
+        i.without_remote_mem()
+            .on_matching("fsync", |e| match e.phase {
+                Phase::Enter => {
+                    // delivered to the thread between enter and exit of the blocked call
+                    unsafe { libc::syscall(libc::SYS_tgkill, e.tgid, e.pid, libc::SIGUSR1) };
+                    Decision::Block(7)
+                }
+                Phase::Exit => Decision::Continue,
+            });
+    });
+    assert!(content.contains("fsync: 7 handled: 1\n"), "{}", content);
+    assert!(content.contains("fsync again: 7\n"), "{}", content);
+}
@@ -0,0 +1,30 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::fs;
+
+#[test]
+fn block_times_fails_first_only() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for i in 0..3 {
+            match fs::create_dir(dir.join(i.to_string())) {
+                Ok(_) => println!("{}: ok", i),
+                Err(e) => println!("{}: {:?}", i, e.raw_os_error()),
+            }
+        }
+        return;
+    }
+
+    let dir = common::test_dir("block-times");
+    let output = common::run_child_in("block_times_fails_first_only", &dir, |i| {
+        i.without_remote_mem()
+            .block_times("mkdir*", 2, -libc::ENOMEM as u64);
+    });
+    let created = [0, 1, 2].map(|i| dir.join(i.to_string()).exists());
+    fs::remove_dir_all(&dir).unwrap();
+    let expected = format!("0: Some({e})\n1: Some({e})\n2: ok\n", e = libc::ENOMEM);
+    assert!(output.contains(&expected), "{}", output);
+    assert_eq!(created, [false, false, true]);
+}
@@ -0,0 +1,65 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::{cell::Cell, fs::File, ptr::null_mut, rc::Rc};
+
+const LEN: usize = 64 * 1024;
+const DEVNULL: &str = "/dev/null";
+
+#[test]
+fn bulk_read_and_readonly_write() {
+    if common::is_child() {
+        let buf = unsafe {
+            libc::mmap(
+                null_mut(),
+                LEN,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        } as *mut u8;
+        assert_ne!(buf as *mut libc::c_void, libc::MAP_FAILED);
+        for i in 0..LEN {
+            unsafe { *buf.add(i) = i as u8 };
+        }
+        assert_eq!(unsafe { libc::mprotect(buf as _, LEN, libc::PROT_READ) }, 0);
+
+        let null = File::options().write(true).open(DEVNULL).unwrap();
+        let fd = std::os::fd::AsRawFd::as_raw_fd(&null);
+        let n = unsafe { libc::write(fd, buf as _, LEN) };
+        println!("write: {}", n);
+        println!("first byte: {}", unsafe { *buf });
+        return;
+    }
+
+    let matched = Rc::new(Cell::new(false));
+    let content = common::run_child("bulk_read_and_readonly_write", |i| {
+        let matched = matched.clone();
+        i.without_remote_mem()
+            .on_fn("write", move |fd: i32, buf: u64, count: u64| {
+                if count == LEN as u64 {
+                    with_context(|ctx| {
+                        let data = ctx.read_memory(buf, LEN);
+                        matched.set(data.iter().enumerate().all(|(i, b)| *b == i as u8));
+                        // the page is read-only for the tracee
+                        ctx.write_memory(buf, &[0xaa]).unwrap();
+                    });
+                }
+                Outcome::<i64, _>::Real((fd, buf, count))
+            });
+    });
+    assert!(matched.get());
+    assert!(
+        content.contains(&format!("write: {}\n", LEN)),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains(&format!("first byte: {}\n", 0xaa)),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,67 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{bytes_of, Interceptor, Outcome};
+use std::{cell::RefCell, ffi::c_char, ptr, rc::Rc};
+
+const INVALID: &[u8] = b"/nonexistent/\xff\xfe\0";
+const UNTERMINATED: &[u8] = b"/nonexistent/\xff";
+
+#[test]
+fn bytes_of_string_args() {
+    if common::is_child() {
+        unsafe {
+            libc::mkdir(INVALID.as_ptr() as *const c_char, 0o755);
+            // right before an unmapped page, so no NUL is found, a PROT_NONE page is still
+            // readable by ptrace
+            let page = libc::sysconf(libc::_SC_PAGESIZE) as usize;
+            let base = libc::mmap(
+                ptr::null_mut(),
+                page * 2,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            ) as usize;
+            assert_ne!(base, libc::MAP_FAILED as usize);
+            libc::munmap((base + page) as *mut _, page);
+            let addr = base + page - UNTERMINATED.len();
+            ptr::copy_nonoverlapping(UNTERMINATED.as_ptr(), addr as *mut u8, UNTERMINATED.len());
+            libc::mkdir(addr as *const c_char, 0o755);
+        }
+        return;
+    }
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(common::child_command("bytes_of_string_args")).unwrap();
+    {
+        let seen = seen.clone();
+        interceptor.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+            let bytes = bytes_of(path);
+            if bytes
+                .as_deref()
+                .is_some_and(|b| b.starts_with(b"/nonexistent/"))
+            {
+                let local = 0u8;
+                seen.borrow_mut()
+                    .push((bytes, bytes_of(&local as *const u8 as _)));
+            }
+            Outcome::<i32, _>::Real((path, mode))
+        });
+    }
+    interceptor
+        .on_decode_error(|_, _, _| true)
+        .without_remote_mem()
+        .run()
+        .unwrap();
+
+    let seen = seen.borrow();
+    assert_eq!(
+        *seen,
+        vec![
+            (Some(INVALID.to_vec()), None),
+            (Some(UNTERMINATED.to_vec()), None)
+        ]
+    );
+}
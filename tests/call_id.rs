@@ -0,0 +1,63 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Interceptor, Phase};
+use std::{cell::RefCell, collections::HashMap, rc::Rc, thread};
+
+#[test]
+fn pair_enter_and_exit_by_call_id() {
+    if common::is_child() {
+        let workers: Vec<_> = (0..4)
+            .map(|_| {
+                thread::spawn(|| {
+                    for _ in 0..50 {
+                        unsafe { libc::getppid() };
+                    }
+                })
+            })
+            .collect();
+        for w in workers {
+            w.join().unwrap();
+        }
+        return;
+    }
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor =
+        Interceptor::new(common::child_command("pair_enter_and_exit_by_call_id")).unwrap();
+    {
+        let events = events.clone();
+        interceptor.on_event(move |e| {
+            events
+                .borrow_mut()
+                .push((e.call_id, e.pid, e.sysno, e.phase))
+        });
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    let events = events.borrow();
+    let mut open = HashMap::new();
+    let mut last = 0;
+    let mut paired = 0;
+    for &(id, pid, sysno, phase) in events.iter() {
+        match phase {
+            Phase::Enter => {
+                assert!(id > last, "{} entered after {}", id, last);
+                last = id;
+                assert!(open.insert(id, (pid, sysno)).is_none());
+            }
+            Phase::Exit if id == 0 => {}
+            Phase::Exit => {
+                assert_eq!(open.remove(&id), Some((pid, sysno)), "call {}", id);
+                paired += 1;
+            }
+        }
+    }
+    let getppid = events
+        .iter()
+        .filter(|e| e.2 == libc::SYS_getppid as u64 && e.3 == Phase::Exit)
+        .count();
+    assert_eq!(getppid, 200);
+    assert!(paired >= getppid);
+}
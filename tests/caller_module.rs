@@ -0,0 +1,51 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::{arch::asm, env, process};
+
+#[test]
+fn block_calls_from_main_binary() {
+    if common::is_child() {
+        println!("libc: {}", unsafe { libc::getppid() });
+        let ret: i64;
+        unsafe {
+            asm!(
+                "syscall",
+                inlateout("rax") libc::SYS_getppid => ret,
+                out("rcx") _,
+                out("r11") _,
+            );
+        }
+        println!("inline: {}", ret);
+        return;
+    }
+
+    let exe = env::current_exe().unwrap();
+    let content = common::run_child("block_calls_from_main_binary", |i| {
+        // `getppid` fails with `EPERM` unless it's called from libc
+        i.on_fn("getppid", move || {
+            let from_exe = with_context(|ctx| {
+                assert_eq!(ctx.pc(), ctx.registers().rip);
+                ctx.caller_module() == exe.to_str()
+            });
+            if from_exe {
+                Outcome::Block(-libc::EPERM)
+            } else {
+                Outcome::Real(())
+            }
+        });
+        i.without_remote_mem();
+    });
+    assert!(
+        content.contains(&format!("libc: {}\n", process::id())),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains(&format!("inline: {}\n", -libc::EPERM)),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,31 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Interceptor;
+
+#[test]
+fn capture_child_output() {
+    if common::is_child() {
+        // more than a pipe holds, so it's read while the child is running
+        for i in 0..20000 {
+            println!("line {}", i);
+        }
+        eprintln!("to stderr");
+        return;
+    }
+
+    let mut interceptor = Interceptor::new(common::child_command("capture_child_output")).unwrap();
+    let output = interceptor.capture_output();
+    interceptor
+        .without_remote_mem()
+        .on_return_of("write", |r| r)
+        .run()
+        .unwrap();
+
+    let stdout = String::from_utf8(output.stdout()).unwrap();
+    assert!(stdout.contains("line 0\n"), "{}", stdout);
+    assert!(stdout.contains("line 19999\n"), "{}", stdout);
+    let stderr = String::from_utf8(output.stderr()).unwrap();
+    assert!(stderr.contains("to stderr\n"), "{}", stderr);
+}
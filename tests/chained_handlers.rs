@@ -0,0 +1,96 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Outcome;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr},
+    fs,
+    os::unix::fs::PermissionsExt,
+    rc::Rc,
+};
+
+#[test]
+fn chained_handlers_in_order() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for name in ["keep", "deny"] {
+            match fs::create_dir(dir.join(name)) {
+                Ok(_) => println!("{}: ok", name),
+                Err(e) => println!("{}: {:?}", name, e.raw_os_error()),
+            }
+        }
+        return;
+    }
+
+    let dir = common::test_dir("chained-handlers");
+
+    let log = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child_in("chained_handlers_in_order", &dir, |i| {
+        {
+            let log = log.clone();
+            i.on_return_of("mkdir", move |ret| {
+                log.borrow_mut().push(("a", ret));
+                0
+            });
+        }
+        {
+            let log = log.clone();
+            i.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+                log.borrow_mut().push(("h1", mode as i64));
+                Outcome::<i32, _>::Real((path, mode))
+            });
+        }
+        {
+            let log = log.clone();
+            i.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+                log.borrow_mut().push(("h2", mode as i64));
+                if unsafe { CStr::from_ptr(path) }
+                    .to_bytes()
+                    .ends_with(b"deny")
+                {
+                    Outcome::Block(-libc::EACCES)
+                } else {
+                    Outcome::Real((path, 0o700))
+                }
+            });
+        }
+        {
+            let log = log.clone();
+            i.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+                log.borrow_mut().push(("h3", mode as i64));
+                Outcome::<i32, _>::Real((path, mode))
+            });
+        }
+        {
+            let log = log.clone();
+            i.on_return_of("mkdir", move |ret| {
+                log.borrow_mut().push(("b", ret));
+                if ret == 0 {
+                    -libc::ENOSPC as i64
+                } else {
+                    ret
+                }
+            });
+        }
+        i.without_remote_mem();
+    });
+    let mode = fs::metadata(dir.join("keep")).unwrap().permissions().mode();
+    fs::remove_dir_all(&dir).unwrap();
+    let expected = format!("keep: ok\ndeny: Some({})\n", libc::EACCES);
+    assert!(content.contains(&expected), "{}", content);
+    assert_eq!(mode & 0o777, 0o700);
+    assert_eq!(
+        *log.borrow(),
+        [
+            ("h1", 0o777),
+            ("h2", 0o777),
+            ("h3", 0o700),
+            ("b", 0),
+            ("a", -libc::ENOSPC as i64),
+            ("h1", 0o777),
+            ("h2", 0o777),
+        ]
+    );
+}
@@ -0,0 +1,62 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, CloneArgs, Interceptor, Phase};
+use parking_lot::Mutex;
+use std::{cell::RefCell, process::Command, rc::Rc, thread};
+
+static CLONE3: Mutex<Vec<CloneArgs>> = Mutex::new(Vec::new());
+
+#[syscall]
+fn clone3(args: u64, size: usize) -> i64 {
+    let cl_args = with_context(|ctx| CloneArgs::read(ctx, args, size as u64));
+    CLONE3.lock().push(cl_args);
+    real!(args, size)
+}
+
+#[test]
+fn clone3_thread_and_process() {
+    if common::is_child() {
+        // glibc >= 2.34 creates threads by `clone3`
+        thread::spawn(|| unsafe { libc::getppid() }).join().unwrap();
+        let status = Command::new("/bin/true").status().unwrap();
+        assert!(status.success());
+        return;
+    }
+
+    let cmd = common::child_command("clone3_thread_and_process");
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let events = events.clone();
+        interceptor.on_event(move |e| {
+            if e.phase == Phase::Enter {
+                events.borrow_mut().push((e.name, e.pid, e.tgid));
+            }
+        });
+    }
+    interceptor.without_remote_mem().on(&clone3).run().unwrap();
+
+    let seen = CLONE3.lock();
+    assert!(
+        seen.iter().any(|a| a.is_thread() && a.shares_vm()),
+        "no thread created by clone3: {:?}",
+        seen
+    );
+
+    let events = events.borrow();
+    let main = events[0].1;
+    // the thread calling `getppid` belongs to the spawned process
+    assert!(events
+        .iter()
+        .any(|(name, pid, tgid)| *name == "getppid" && *pid != main && *tgid == main));
+    // the spawned `/bin/true` is a new process
+    let execve = events
+        .iter()
+        .filter(|(name, ..)| *name == "execve")
+        .collect::<Vec<_>>();
+    assert!(execve
+        .iter()
+        .any(|(_, pid, tgid)| *pid == *tgid && *tgid != main));
+}
@@ -0,0 +1,113 @@
+//! Helpers shared by integration tests.
+//!
+//! A test spawns its own binary as the traced child, which runs only the same test with
+//! [`CHILD`] set, so the test function does the child's work when [`is_child`] is true.
+#![allow(dead_code)]
+
+use interceptor_rs::Interceptor;
+use std::{
+    env::{current_exe, temp_dir, var, var_os},
+    fs::{copy, create_dir_all, read_to_string, remove_file, rename, File},
+    path::{Path, PathBuf},
+    process::{id, Command},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const CHILD: &str = "INTERCEPTOR_TEST_CHILD";
+/// directory of files a test works on, passed to the child by [`run_child_in`]
+pub const DIR: &str = "INTERCEPTOR_TEST_DIR";
+
+pub fn is_child() -> bool {
+    var_os(CHILD).is_some()
+}
+
+/// command re-executing the test binary, which only runs `test` in child mode.
+pub fn child_command(test: &str) -> Command {
+    let mut cmd = Command::new(current_exe().unwrap());
+    cmd.args(["--exact", test, "--nocapture"]).env(CHILD, "1");
+    cmd
+}
+
+/// copy `libinter_mem.so` built by `build.rs` next to the test binary, where
+/// `Interceptor` looks for it, so remote memory is available to tests.
+pub fn install_preload() {
+    let exe = current_exe().unwrap();
+    let dst = exe.with_file_name("libinter_mem.so");
+    let src = exe.parent().unwrap().with_file_name("libinter_mem.so");
+    let tmp = dst.with_extension(format!("so.{}", id()));
+    copy(&src, &tmp).unwrap_or_else(|e| panic!("copy {} error: {}", src.display(), e));
+    rename(&tmp, &dst).unwrap();
+}
+
+/// run `cmd` under an interceptor set up by `f` and return what it printed to stdout,
+/// see [`Output`].
+pub fn capture(mut cmd: Command, f: impl FnOnce(&mut Interceptor)) -> String {
+    let output = Output::of(&mut cmd);
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    f(&mut interceptor);
+    interceptor.run().unwrap();
+    output.read()
+}
+
+/// run the child of `test` under an interceptor set up by `f` and return what it
+/// printed to stdout, see [`capture`].
+pub fn run_child(test: &str, f: impl FnOnce(&mut Interceptor)) -> String {
+    capture(child_command(test), f)
+}
+
+/// same as [`run_child`], with `dir` passed to the child by [`DIR`]
+pub fn run_child_in(test: &str, dir: &Path, f: impl FnOnce(&mut Interceptor)) -> String {
+    let mut cmd = child_command(test);
+    cmd.env(DIR, dir);
+    capture(cmd, f)
+}
+
+/// create an empty temporary directory for `test`, removed by the test when done.
+pub fn test_dir(test: &str) -> PathBuf {
+    let dir = temp_dir().join(format!("interceptor-{}-{}", test, id()));
+    create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// [`DIR`] in the child
+pub fn child_dir() -> PathBuf {
+    var(DIR).unwrap().into()
+}
+
+/// print `name: content` of each file of `names` in [`DIR`] in the child, or the error
+/// reading it.
+pub fn print_files(names: &[&str]) {
+    let dir = child_dir();
+    for name in names {
+        match read_to_string(dir.join(name)) {
+            Ok(content) => println!("{}: {}", name, content),
+            Err(e) => println!("{}: {}", name, e),
+        }
+    }
+}
+
+/// stdout of a child captured by a temporary file, for tests running the interceptor
+/// in their own way, [`capture`] does it otherwise.
+pub struct Output(PathBuf);
+
+impl Output {
+    /// redirect stdout of `cmd` into a new temporary file
+    pub fn of(cmd: &mut Command) -> Self {
+        let path = temp_dir().join(format!("interceptor-output-{}-{}", id(), next_output()));
+        cmd.stdout(File::create(&path).unwrap());
+        Self(path)
+    }
+
+    /// what the child printed, the file is removed
+    pub fn read(self) -> String {
+        let content = read_to_string(&self.0).unwrap();
+        remove_file(&self.0).unwrap();
+        content
+    }
+}
+
+/// outputs are per process, tests in it may run at once
+fn next_output() -> usize {
+    static NEXT: AtomicUsize = AtomicUsize::new(0);
+    NEXT.fetch_add(1, Ordering::Relaxed)
+}
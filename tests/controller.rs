@@ -0,0 +1,72 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Outcome, Phase};
+use std::{
+    ffi::c_char,
+    fs::{self},
+    sync::mpsc::channel,
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn controller_registers_while_running() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        // whether `mkdir` succeeds, consecutive same results are merged
+        let mut results = Vec::new();
+        for i in 0..5000 {
+            let ok = fs::create_dir(dir.join(i.to_string())).is_ok();
+            if results.last() != Some(&ok) {
+                results.push(ok);
+            }
+            if results.len() == 3 {
+                break;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        println!("results: {:?}", results);
+        return;
+    }
+
+    let dir = common::test_dir("controller");
+    let mut control = None;
+    let content = common::run_child_in("controller_registers_while_running", &dir, |i| {
+        let controller = i.controller();
+        let (created_tx, created_rx) = channel();
+        let (blocked_tx, blocked_rx) = channel();
+        let mut created_tx = Some(created_tx);
+        i.on_event(move |e| {
+            if e.name == "mkdir" && e.phase == Phase::Exit && e.ret == Some(0) {
+                if let Some(tx) = created_tx.take() {
+                    tx.send(()).unwrap();
+                }
+            }
+        })
+        .without_remote_mem();
+        control = Some(thread::spawn(move || {
+            // deny `mkdir` once one is created, and allow it again once one is denied
+            created_rx.recv().unwrap();
+            controller.apply(move |i| {
+                i.on_fn("mkdir", move |_path: *const c_char, _mode: u32| {
+                    let _ = blocked_tx.send(());
+                    Outcome::<i32, _>::Block(-libc::EACCES)
+                });
+            });
+            blocked_rx.recv().unwrap();
+            controller.apply(|i| {
+                i.off("mkdir");
+            });
+        }));
+    });
+    control.unwrap().join().unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        content.contains("results: [true, false, true]\n"),
+        "{}",
+        content
+    );
+}
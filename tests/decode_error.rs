@@ -0,0 +1,85 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{InterceptError, Outcome};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr},
+    ptr,
+    rc::Rc,
+};
+
+const MISSING: &[u8] = b"/interceptor-decode-error-missing\0";
+
+fn openat(path: *const c_char) -> Option<i32> {
+    let fd = unsafe { libc::openat(libc::AT_FDCWD, path, libc::O_RDONLY) };
+    (fd < 0).then(|| std::io::Error::last_os_error().raw_os_error().unwrap())
+}
+
+#[test]
+fn skip_handler_on_decode_error() {
+    if common::is_child() {
+        // a string running into an unmapped page without NUL
+        let page = unsafe { libc::sysconf(libc::_SC_PAGESIZE) } as usize;
+        let base = unsafe {
+            libc::mmap(
+                ptr::null_mut(),
+                page * 2,
+                libc::PROT_READ | libc::PROT_WRITE,
+                libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+                -1,
+                0,
+            )
+        } as usize;
+        unsafe {
+            libc::munmap((base + page) as *mut _, page);
+            ptr::write_bytes((base + page - 3) as *mut u8, b'a', 3);
+        }
+        println!("boundary: {:x}", base + page);
+        println!(
+            "unterminated: {:?}",
+            openat((base + page - 3) as *const c_char)
+        );
+        println!("missing: {:?}", openat(MISSING.as_ptr() as *const c_char));
+        return;
+    }
+
+    let failed = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("skip_handler_on_decode_error", |i| {
+        {
+            let failed = failed.clone();
+            i.on_decode_error(move |name, index, e| {
+                if let InterceptError::MemoryAccess(addr) = e {
+                    failed.borrow_mut().push((name.to_owned(), index, *addr));
+                }
+                false
+            });
+        }
+        i.on_fn("openat", |dfd: i32, path: *const c_char| {
+            let path_bytes = unsafe { CStr::from_ptr(path) }.to_bytes_with_nul();
+            if path_bytes == MISSING {
+                Outcome::<i32, _>::Block(-libc::EACCES)
+            } else {
+                Outcome::Real((dfd, path))
+            }
+        });
+        i.without_remote_mem();
+    });
+    let boundary = content
+        .lines()
+        .find_map(|l| l.split_once("boundary: "))
+        .map(|(_, b)| u64::from_str_radix(b, 16).unwrap())
+        .unwrap_or_else(|| panic!("{}", content));
+    assert!(
+        content.contains(&format!("unterminated: Some({})\n", libc::EFAULT)),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains(&format!("missing: Some({})\n", libc::EACCES)),
+        "{}",
+        content
+    );
+    assert_eq!(*failed.borrow(), [("openat".to_owned(), 1, boundary)]);
+}
@@ -0,0 +1,63 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::io::Error;
+
+/// needed by the loader, libc and the test harness
+const STARTUP: [&str; 25] = [
+    "access",
+    "arch_prctl",
+    "brk",
+    "clone*",
+    "close",
+    "execve",
+    "futex",
+    "getpid",
+    "gettid",
+    "getrandom",
+    "lseek",
+    "madvise",
+    "mmap",
+    "mprotect",
+    "munmap",
+    "*stat*",
+    "openat",
+    "poll",
+    "prctl",
+    "pread64",
+    "prlimit64",
+    "read",
+    "rseq",
+    "rt_sig*",
+    "write",
+];
+
+#[test]
+fn block_unless_allowed() {
+    if common::is_child() {
+        let ppid = unsafe { libc::getppid() };
+        println!(
+            "getppid: {} {:?}",
+            ppid,
+            Error::last_os_error().raw_os_error()
+        );
+        println!("getuid: {}", unsafe { libc::getuid() });
+        return;
+    }
+
+    let content = common::run_child("block_unless_allowed", |i| {
+        i.without_remote_mem().default_block(libc::EPERM);
+        for pattern in STARTUP {
+            i.allow(pattern);
+        }
+        // allowed by having a handler
+        i.on_return_of("getuid", |_| 4242);
+    });
+    assert!(
+        content.contains(&format!("getppid: -1 Some({})\n", libc::EPERM)),
+        "{}",
+        content
+    );
+    assert!(content.contains("getuid: 4242\n"), "{}", content);
+}
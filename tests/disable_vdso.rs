@@ -0,0 +1,37 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Decision, Interceptor, Phase};
+use std::{cell::Cell, rc::Rc, time::SystemTime};
+
+#[test]
+fn disable_vdso_clock_gettime() {
+    if common::is_child() {
+        for _ in 0..3 {
+            println!("{:?}", SystemTime::now());
+        }
+        return;
+    }
+
+    let mut cmd = common::child_command("disable_vdso_clock_gettime");
+    cmd.stdout(std::process::Stdio::null());
+    let count = Rc::new(Cell::new(0));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let count = count.clone();
+        interceptor.on_matching("clock_gettime", move |event| {
+            if event.phase == Phase::Exit {
+                count.set(count.get() + 1);
+            }
+            Decision::Continue
+        });
+    }
+    interceptor
+        .without_remote_mem()
+        .disable_vdso(true)
+        .run()
+        .unwrap();
+
+    assert!(count.get() >= 3, "clock_gettime seen {} times", count.get());
+}
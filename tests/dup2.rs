@@ -0,0 +1,65 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Outcome;
+use std::{fs::File, os::fd::AsRawFd};
+
+const REQUESTED: i32 = 50;
+const REDIRECTED: i32 = 60;
+const BLOCKED: i32 = 51;
+
+fn is_open(fd: i32) -> bool {
+    unsafe { libc::fcntl(fd, libc::F_GETFD) != -1 }
+}
+
+#[test]
+fn dup2_remapped() {
+    if common::is_child() {
+        let null = File::open("/dev/null").unwrap();
+        let fd = null.as_raw_fd();
+        let r = unsafe { libc::dup2(fd, REQUESTED) };
+        println!("dup2: {}", r);
+        println!("requested open: {}", is_open(REQUESTED));
+        println!("redirected open: {}", is_open(REDIRECTED));
+        let r = unsafe { libc::dup3(fd, BLOCKED, libc::O_CLOEXEC) };
+        println!("dup3: {} {}", r, std::io::Error::last_os_error());
+        println!("blocked open: {}", is_open(BLOCKED));
+        return;
+    }
+
+    let content = common::run_child("dup2_remapped", |i| {
+        i.without_remote_mem()
+            .on_fn("dup2", |oldfd: i32, newfd: i32| -> Outcome<i32, _> {
+                if newfd == REQUESTED {
+                    Outcome::Real((oldfd, REDIRECTED))
+                } else {
+                    Outcome::Real((oldfd, newfd))
+                }
+            })
+            .on_fn("dup3", |oldfd: i32, newfd: i32, flags: i32| {
+                if newfd == BLOCKED {
+                    Outcome::Block(-libc::EPERM)
+                } else {
+                    Outcome::Real((oldfd, newfd, flags))
+                }
+            });
+    });
+    // kernel returns the fd it duplicated to, which is the one set by the handler
+    assert!(
+        content.contains(&format!("dup2: {}\n", REDIRECTED)),
+        "{}",
+        content
+    );
+    assert!(content.contains("requested open: false"), "{}", content);
+    assert!(content.contains("redirected open: true"), "{}", content);
+    assert!(
+        content.contains(&format!(
+            "dup3: -1 {}",
+            std::io::Error::from_raw_os_error(libc::EPERM)
+        )),
+        "{}",
+        content
+    );
+    assert!(content.contains("blocked open: false"), "{}", content);
+}
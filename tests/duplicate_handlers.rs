@@ -0,0 +1,27 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::DuplicatePolicy;
+
+#[test]
+fn duplicate_handlers_policy() {
+    if common::is_child() {
+        println!("getppid: {}", unsafe { libc::getppid() });
+        println!("getuid: {}", unsafe { libc::getuid() });
+        return;
+    }
+
+    let content = common::run_child("duplicate_handlers_policy", |i| {
+        i.without_remote_mem()
+            .on_duplicate(DuplicatePolicy::Replace)
+            .on_return_of("getppid", |_| 100)
+            .on_return_of("getuid", |_| 1000)
+            .on_return_of("getppid", |_| 200)
+            .on_duplicate(DuplicatePolicy::Reject)
+            .on_return_of("getppid", |_| 300);
+        assert_eq!(i.registered(), ["getuid", "getppid"]);
+    });
+    assert!(content.contains("getppid: 200\n"), "{}", content);
+    assert!(content.contains("getuid: 1000\n"), "{}", content);
+}
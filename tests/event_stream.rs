@@ -0,0 +1,52 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", feature = "tokio"))]
+
+mod common;
+
+use interceptor_rs::{Decision, EventStream, Phase};
+use std::io::Error;
+
+#[tokio::test]
+async fn stream_replies_decisions() {
+    if common::is_child() {
+        // both fail with EBADF if sent to kernel
+        for (name, ret) in [
+            ("fsync", unsafe { libc::fsync(-1) }),
+            ("fdatasync", unsafe { libc::fdatasync(-1) }),
+        ] {
+            if ret < 0 {
+                println!("{}: {:?}", name, Error::last_os_error().raw_os_error());
+            } else {
+                println!("{}: {}", name, ret);
+            }
+        }
+        return;
+    }
+
+    let mut cmd = common::child_command("stream_replies_decisions");
+    let output = common::Output::of(&mut cmd);
+    let mut events = EventStream::spawn(cmd, "f*sync", |interceptor| {
+        interceptor.without_remote_mem();
+    });
+    let mut seen = Vec::new();
+    while let Some(pending) = events.next().await {
+        seen.push((pending.event.name, pending.event.phase));
+        if pending.event.name == "fsync" {
+            pending.reply(Decision::Block(0));
+        }
+    }
+    events.wait().await.unwrap();
+
+    let content = output.read();
+    assert!(content.contains("fsync: 0\n"), "{}", content);
+    let expected = format!("fdatasync: Some({})\n", libc::EBADF);
+    assert!(content.contains(&expected), "{}", content);
+    assert_eq!(
+        seen,
+        [
+            ("fsync", Phase::Enter),
+            ("fsync", Phase::Exit),
+            ("fdatasync", Phase::Enter),
+            ("fdatasync", Phase::Exit),
+        ]
+    );
+}
@@ -0,0 +1,46 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::syscall;
+use std::{
+    process::{self, Command},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static PRE: AtomicUsize = AtomicUsize::new(0);
+static POST: AtomicUsize = AtomicUsize::new(0);
+
+#[syscall]
+fn exit_group(mut status: i32) -> i32 {
+    PRE.fetch_add(1, Ordering::SeqCst);
+    if status == 3 {
+        status = 42;
+    }
+
+    let ret = real!(status);
+    POST.fetch_add(1, Ordering::SeqCst);
+    ret
+}
+
+#[test]
+fn exit_group_never_returns() {
+    if common::is_child() {
+        process::exit(3);
+    }
+
+    // the shell reports how the child exited, then exits itself
+    let child = common::child_command("exit_group_never_returns");
+    let mut cmd = Command::new("sh");
+    cmd.arg("-c")
+        .arg(r#""$0" "$@"; echo "status $?""#)
+        .arg(child.get_program())
+        .args(child.get_args())
+        .envs(child.get_envs().filter_map(|(k, v)| Some((k, v?))));
+    let output = common::capture(cmd, |i| {
+        i.without_remote_mem().on(&exit_group);
+    });
+    assert!(output.contains("status 42\n"), "{}", output);
+    assert_eq!(PRE.load(Ordering::SeqCst), 2);
+    assert_eq!(POST.load(Ordering::SeqCst), 0);
+}
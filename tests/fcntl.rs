@@ -0,0 +1,67 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{FcntlArg, FcntlCtx, Outcome};
+use std::{cell::RefCell, rc::Rc};
+
+const UNKNOWN: i32 = 9999;
+
+#[test]
+fn fcntl_by_command() {
+    if common::is_child() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let ret = unsafe { libc::fcntl(fds[0], libc::F_SETFL, libc::O_NONBLOCK) };
+        let flags = unsafe { libc::fcntl(fds[0], libc::F_GETFL) };
+        println!("F_SETFL: {} {}", ret, flags & libc::O_NONBLOCK != 0);
+
+        let mut lock: libc::flock = unsafe { std::mem::zeroed() };
+        lock.l_type = libc::F_WRLCK as i16;
+        lock.l_start = 10;
+        let ret = unsafe { libc::fcntl(fds[0], libc::F_GETLK, &mut lock) };
+        println!("F_GETLK: {} {} {}", ret, lock.l_type, lock.l_pid);
+        let ret = unsafe { libc::fcntl(fds[0], UNKNOWN, 7) };
+        println!("unknown: {}", ret);
+        return;
+    }
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("fcntl_by_command", |i| {
+        for cmd in [libc::F_SETFL, libc::F_GETFL, UNKNOWN] {
+            let seen = seen.clone();
+            i.on_fcntl(cmd, move |fcntl: &mut FcntlCtx| {
+                seen.borrow_mut().push((fcntl.cmd, fcntl.arg));
+                // turn non-blocking mode off
+                if let FcntlArg::Int(flags) = &mut fcntl.arg {
+                    *flags &= !libc::O_NONBLOCK;
+                }
+                Outcome::Real(())
+            });
+        }
+        i.on_fcntl(libc::F_GETLK, |fcntl: &mut FcntlCtx| {
+            let FcntlArg::Flock(lock) = &mut fcntl.arg else {
+                return Outcome::Real(());
+            };
+            assert_eq!(lock.l_start, 10);
+            lock.l_pid = 4242;
+            Outcome::Block(0)
+        })
+        .without_remote_mem();
+    });
+    assert!(content.contains("F_SETFL: 0 false\n"), "{}", content);
+    assert!(
+        content.contains(&format!("F_GETLK: 0 {} 4242\n", libc::F_WRLCK)),
+        "{}",
+        content
+    );
+    assert!(content.contains("unknown: -1\n"), "{}", content);
+    assert_eq!(
+        *seen.borrow(),
+        [
+            (libc::F_SETFL, FcntlArg::Int(libc::O_NONBLOCK)),
+            (libc::F_GETFL, FcntlArg::None),
+            (UNKNOWN, FcntlArg::Raw(7)),
+        ]
+    );
+}
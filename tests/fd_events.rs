@@ -0,0 +1,91 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{FdEvent, FdOp, FdTarget};
+use std::{
+    cell::RefCell,
+    env,
+    fs::{self, File},
+    net::{TcpListener, TcpStream},
+    os::fd::AsRawFd,
+    rc::Rc,
+};
+
+const FILE: &str = "INTERCEPTOR_TEST_FILE";
+
+#[test]
+fn track_fd_lifecycle() {
+    if common::is_child() {
+        let file = File::open(env::var(FILE).unwrap()).unwrap();
+        let dup = unsafe { libc::dup(file.as_raw_fd()) };
+        println!("file: {} {}", file.as_raw_fd(), dup);
+        drop(file);
+
+        let mut fds = [0; 2];
+        unsafe { libc::pipe(fds.as_mut_ptr()) };
+        println!("pipe: {} {}", fds[0], fds[1]);
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let client = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (conn, _) = listener.accept().unwrap();
+        println!(
+            "accept: {} {}",
+            conn.as_raw_fd(),
+            client.local_addr().unwrap()
+        );
+        return;
+    }
+
+    let dir = common::test_dir("fd-events");
+    let path = dir.join("file");
+    fs::write(&path, "").unwrap();
+    let mut cmd = common::child_command("track_fd_lifecycle");
+    cmd.env(FILE, &path);
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let content = common::capture(cmd, |i| {
+        let events = events.clone();
+        i.on_fd_events(move |e: &FdEvent| events.borrow_mut().push(e.clone()))
+            .without_remote_mem();
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let line = |key: &str| -> Vec<String> {
+        content
+            .lines()
+            .find_map(|l| l.split_once(&format!("{}: ", key)))
+            .unwrap_or_else(|| panic!("{}", content))
+            .1
+            .split(' ')
+            .map(str::to_owned)
+            .collect()
+    };
+    let fds = |key| -> Vec<i32> { line(key).iter().map(|s| s.parse().unwrap()).collect() };
+    let (file, dup, pipe) = (fds("file")[0], fds("file")[1], fds("pipe"));
+    let accept = line("accept");
+    let events = events.borrow();
+    let find = |op: FdOp, fd: i32| {
+        events
+            .iter()
+            .rev()
+            .find(|e| e.op == op && e.fd == fd)
+            .unwrap_or_else(|| panic!("{:?} {} not found in {:#?}", op, fd, events))
+    };
+
+    assert_eq!(
+        find(FdOp::Open, file).path_or_addr,
+        Some(FdTarget::Path(path.clone()))
+    );
+    assert_eq!(find(FdOp::Dup, dup).from, Some(file));
+    assert_eq!(find(FdOp::Close, file).name, "close");
+    // glibc makes `pipe` by `pipe2`
+    assert_eq!(
+        find(FdOp::Pipe, pipe[0]).name,
+        find(FdOp::Pipe, pipe[1]).name
+    );
+    assert_eq!(
+        find(FdOp::Accept, accept[0].parse().unwrap()).path_or_addr,
+        Some(FdTarget::Inet(accept[1].parse().unwrap()))
+    );
+    assert!(events.iter().any(|e| e.op == FdOp::Socket));
+}
@@ -0,0 +1,33 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::{fs, process, thread};
+
+#[test]
+fn intercept_worker_threads_only() {
+    if common::is_child() {
+        println!("acceptor: {}", unsafe { libc::getppid() });
+        let worker = thread::Builder::new()
+            .name("worker".into())
+            .spawn(|| unsafe { libc::getppid() })
+            .unwrap();
+        println!("worker: {}", worker.join().unwrap());
+        return;
+    }
+
+    let content = common::run_child("intercept_worker_threads_only", |i| {
+        i.without_remote_mem()
+            .filter_tasks(|tid, tgid| {
+                let comm = fs::read_to_string(format!("/proc/{}/task/{}/comm", tgid, tid));
+                comm.is_ok_and(|c| c == "worker\n")
+            })
+            .on_return_of("getppid", |_| 4242);
+    });
+    assert!(content.contains("worker: 4242\n"), "{}", content);
+    assert!(
+        content.contains(&format!("acceptor: {}\n", process::id())),
+        "{}",
+        content
+    );
+}
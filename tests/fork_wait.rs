@@ -0,0 +1,106 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Phase;
+use std::{
+    cell::RefCell,
+    ptr::null_mut,
+    rc::Rc,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread::sleep,
+    time::Duration,
+};
+
+static SIGCHLD: AtomicUsize = AtomicUsize::new(0);
+
+extern "C" fn on_sigchld(_: libc::c_int) {
+    SIGCHLD.fetch_add(1, Ordering::SeqCst);
+}
+
+/// fork a child running `f` and exiting with the code it returns
+fn fork(f: impl FnOnce() -> i32) -> libc::pid_t {
+    match unsafe { libc::fork() } {
+        0 => unsafe { libc::_exit(f()) },
+        pid => pid,
+    }
+}
+
+#[test]
+fn target_waits_its_children() {
+    if common::is_child() {
+        unsafe {
+            libc::signal(
+                libc::SIGCHLD,
+                on_sigchld as extern "C" fn(libc::c_int) as libc::sighandler_t,
+            )
+        };
+
+        let pid = fork(|| 42);
+        let mut status = 0;
+        let ret = unsafe { libc::waitpid(pid, &mut status, 0) };
+        println!(
+            "waitpid: {} exited {}",
+            ret == pid,
+            libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 42
+        );
+
+        let pid = fork(|| unsafe { libc::raise(libc::SIGTERM) });
+        let ret = unsafe { libc::wait4(pid, &mut status, 0, null_mut()) };
+        println!(
+            "wait4: {} signaled {}",
+            ret == pid,
+            libc::WIFSIGNALED(status) && libc::WTERMSIG(status) == libc::SIGTERM
+        );
+
+        let pid = fork(|| {
+            sleep(Duration::from_millis(50));
+            7
+        });
+        let ret = loop {
+            match unsafe { libc::waitpid(-1, &mut status, libc::WNOHANG) } {
+                0 => sleep(Duration::from_millis(5)),
+                ret => break ret,
+            }
+        };
+        println!(
+            "polled: {} exited {}",
+            ret == pid,
+            libc::WIFEXITED(status) && libc::WEXITSTATUS(status) == 7
+        );
+
+        // all children are reaped
+        let ret = unsafe { libc::waitpid(-1, &mut status, 0) };
+        println!("no more: {}", ret == -1);
+        println!("SIGCHLD: {}", SIGCHLD.load(Ordering::SeqCst));
+        return;
+    }
+
+    let waits = Rc::new(RefCell::new(0));
+    let content = common::run_child("target_waits_its_children", |i| {
+        let waits = waits.clone();
+        i.without_remote_mem().on_event(move |e| {
+            if e.name == "wait4" && e.phase == Phase::Exit {
+                *waits.borrow_mut() += 1;
+            }
+        });
+    });
+    assert!(
+        content.contains("waitpid: true exited true\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("wait4: true signaled true\n"),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains("polled: true exited true\n"),
+        "{}",
+        content
+    );
+    assert!(content.contains("no more: true\n"), "{}", content);
+    assert!(content.contains("SIGCHLD: 3\n"), "{}", content);
+    assert!(*waits.borrow() >= 4);
+}
@@ -0,0 +1,63 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Decision, Interceptor, Phase};
+use std::{
+    cell::Cell,
+    rc::Rc,
+    sync::{Arc, Condvar, Mutex},
+    thread,
+    time::Duration,
+};
+
+#[test]
+fn observe_futex_without_deadlock() {
+    if common::is_child() {
+        // threads take turns through a condition variable, each turn needs a wake up
+        let threads = 4;
+        let token = Arc::new((Mutex::new(0usize), Condvar::new()));
+        let handles = (0..threads)
+            .map(|i| {
+                let token = token.clone();
+                thread::spawn(move || {
+                    for round in 0..100 {
+                        let (lock, cvar) = &*token;
+                        let mut turn = cvar
+                            .wait_while(lock.lock().unwrap(), |turn| *turn != round * threads + i)
+                            .unwrap();
+                        *turn += 1;
+                        cvar.notify_all();
+                    }
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+        println!("turns: {}", token.0.lock().unwrap());
+        return;
+    }
+
+    let mut cmd = common::child_command("observe_futex_without_deadlock");
+    let output = common::Output::of(&mut cmd);
+    let count = Rc::new(Cell::new(0));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let count = count.clone();
+        interceptor.on_matching("futex", move |event| {
+            if event.phase == Phase::Enter {
+                count.set(count.get() + 1);
+            }
+            Decision::Continue
+        });
+    }
+    interceptor
+        .without_remote_mem()
+        .run_with_timeout(Duration::from_secs(60))
+        .unwrap();
+
+    let content = output.read();
+    assert!(content.contains("turns: 400\n"), "{}", content);
+    assert!(count.get() > 0);
+}
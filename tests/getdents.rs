@@ -0,0 +1,62 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", feature = "dirent"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, DirentBuf};
+use std::{
+    collections::BTreeSet,
+    fs::{self, File},
+};
+
+const HIDDEN: [&[u8]; 2] = [b"file-7", b"file-150"];
+
+#[syscall]
+fn getdents64(fd: i32, dirp: u64, count: u32) -> i64 {
+    let ret = real!(fd, dirp, count);
+    if ret <= 0 {
+        return ret;
+    }
+
+    with_context(|ctx| {
+        let mut buf = DirentBuf::read_arg(ctx, 1, ret as usize);
+        assert!(buf.iter().any(|e| e.kind == libc::DT_REG));
+        buf.retain(|e| !HIDDEN.contains(&e.name.as_slice()));
+        buf.store(ctx).unwrap() as i64
+    })
+}
+
+#[test]
+fn hide_directory_entries() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let names = fs::read_dir(dir)
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect::<Vec<_>>();
+        println!("entries: {}", names.join(","));
+        return;
+    }
+
+    let dir = common::test_dir("getdents");
+    let names = (0..1000)
+        .map(|i| format!("file-{}", i))
+        .collect::<BTreeSet<_>>();
+    for name in &names {
+        File::create(dir.join(name)).unwrap();
+    }
+
+    let content = common::run_child_in("hide_directory_entries", &dir, |i| {
+        i.without_remote_mem().on(&getdents64);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    let listed = content
+        .lines()
+        .find_map(|l| l.split_once("entries: "))
+        .map(|(_, names)| names.split(',').map(str::to_owned).collect::<BTreeSet<_>>())
+        .unwrap_or_default();
+    let expected = names
+        .into_iter()
+        .filter(|n| !HIDDEN.contains(&n.as_bytes()))
+        .collect::<BTreeSet<_>>();
+    assert_eq!(listed, expected);
+}
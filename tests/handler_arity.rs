@@ -0,0 +1,72 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use interceptor_rs::{Interceptor, Outcome};
+use std::{
+    fmt::Debug,
+    process::Command,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+/// collects messages of warnings
+#[derive(Clone, Default)]
+struct Warnings(Arc<Mutex<Vec<String>>>);
+
+impl Visit for Warnings {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0.lock().unwrap().push(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Warnings {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() == Level::WARN
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        event.record(&mut self.clone());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn handler_arity_warned() {
+    let warnings = Warnings::default();
+    tracing::subscriber::with_default(warnings.clone(), || {
+        let mut interceptor = Interceptor::new(Command::new("true")).unwrap();
+        interceptor
+            // `openat` takes 4
+            .on_fn("openat", |dfd: i32, flags: i32| {
+                Outcome::<i32, _>::Real((dfd, flags))
+            })
+            .on_fn("close", |fd: i32| Outcome::<i32, _>::Real((fd,)));
+    });
+
+    let warnings = warnings.0.lock().unwrap();
+    let arity = warnings
+        .iter()
+        .filter(|w| w.contains("arguments, but its handler declares"))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        arity,
+        ["openat takes 4 arguments, but its handler declares 2"],
+        "{:?}",
+        warnings
+    );
+}
@@ -0,0 +1,77 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::io::Error;
+
+/// a filter failing `getppid` with `EPERM`
+fn deny_getppid() -> Vec<libc::sock_filter> {
+    let stmt = |code, k| libc::sock_filter {
+        code: code as u16,
+        jt: 0,
+        jf: 0,
+        k,
+    };
+    vec![
+        // offset of `nr` in `struct seccomp_data`
+        stmt(libc::BPF_LD | libc::BPF_W | libc::BPF_ABS, 0),
+        libc::sock_filter {
+            code: (libc::BPF_JMP | libc::BPF_JEQ | libc::BPF_K) as u16,
+            jt: 0,
+            jf: 1,
+            k: libc::SYS_getppid as u32,
+        },
+        stmt(
+            libc::BPF_RET | libc::BPF_K,
+            libc::SECCOMP_RET_ERRNO | libc::EPERM as u32,
+        ),
+        stmt(libc::BPF_RET | libc::BPF_K, libc::SECCOMP_RET_ALLOW),
+    ]
+}
+
+#[test]
+fn install_seccomp_filter() {
+    if common::is_child() {
+        let mut filter = deny_getppid();
+        let prog = libc::sock_fprog {
+            len: filter.len() as u16,
+            filter: filter.as_mut_ptr(),
+        };
+        let result = |r: libc::c_long| {
+            if r < 0 {
+                Error::last_os_error().raw_os_error().unwrap_or_default() as libc::c_long
+            } else {
+                r
+            }
+        };
+        unsafe {
+            libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0);
+            let set = |flags: libc::c_ulong| {
+                libc::syscall(
+                    libc::SYS_seccomp,
+                    libc::SECCOMP_SET_MODE_FILTER,
+                    flags,
+                    &prog,
+                )
+            };
+            println!("seccomp: {}", result(set(0)));
+            println!(
+                "listener: {}",
+                result(set(libc::SECCOMP_FILTER_FLAG_NEW_LISTENER))
+            );
+            let r = libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_FILTER, &prog);
+            println!("prctl: {}", result(r as libc::c_long));
+            println!("getppid: {}", libc::syscall(libc::SYS_getppid) > 0);
+        }
+        return;
+    }
+
+    let content = common::run_child("install_seccomp_filter", |i| {
+        i.without_remote_mem().ignore_seccomp();
+    });
+    let expected = format!(
+        "seccomp: 0\nlistener: {}\nprctl: 0\ngetppid: true\n",
+        libc::EINVAL
+    );
+    assert!(content.contains(&expected), "{}", content);
+}
@@ -0,0 +1,61 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{IoctlCtx, Outcome, Pod};
+use std::{cell::RefCell, rc::Rc};
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Winsize {
+    row: u16,
+    col: u16,
+    xpixel: u16,
+    ypixel: u16,
+}
+
+unsafe impl Pod for Winsize {}
+
+#[test]
+fn ioctl_by_request() {
+    if common::is_child() {
+        let mut ws: libc::winsize = unsafe { std::mem::zeroed() };
+        let ret = unsafe { libc::ioctl(0, libc::TIOCGWINSZ, &mut ws) };
+        println!("TIOCGWINSZ: {} {}x{}", ret, ws.ws_row, ws.ws_col);
+
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        let mut on: libc::c_int = 1;
+        let ret = unsafe { libc::ioctl(fds[0], libc::FIONBIO, &mut on) };
+        let flags = unsafe { libc::fcntl(fds[0], libc::F_GETFL) };
+        println!("FIONBIO: {} {}", ret, flags & libc::O_NONBLOCK != 0);
+        let mut n: libc::c_int = -1;
+        let ret = unsafe { libc::ioctl(fds[0], libc::FIONREAD, &mut n) };
+        println!("FIONREAD: {} {}", ret, n);
+        return;
+    }
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("ioctl_by_request", |i| {
+        let seen = seen.clone();
+        i.without_remote_mem()
+            .on_ioctl(libc::TIOCGWINSZ, |ioctl: &mut IoctlCtx<Winsize>| {
+                ioctl.arg = Some(Winsize {
+                    row: 24,
+                    col: 80,
+                    ..Default::default()
+                });
+                Outcome::Block(0)
+            })
+            // turn non-blocking mode off
+            .on_ioctl(libc::FIONBIO, move |ioctl: &mut IoctlCtx<i32>| {
+                seen.borrow_mut().push(ioctl.arg);
+                ioctl.arg = Some(0);
+                Outcome::Real(())
+            });
+    });
+    assert!(content.contains("TIOCGWINSZ: 0 24x80\n"), "{}", content);
+    assert!(content.contains("FIONBIO: 0 false\n"), "{}", content);
+    assert!(content.contains("FIONREAD: 0 0\n"), "{}", content);
+    assert_eq!(*seen.borrow(), [Some(1)]);
+}
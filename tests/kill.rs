@@ -0,0 +1,42 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Outcome;
+use std::{
+    io::Error, os::unix::process::ExitStatusExt, process::Command, thread::sleep, time::Duration,
+};
+
+#[test]
+fn kill_blocked_signal_not_delivered() {
+    if common::is_child() {
+        let mut sleeper = Command::new("sleep").arg("5").spawn().unwrap();
+        let pid = sleeper.id() as i32;
+        for sig in [libc::SIGKILL, libc::SIGTERM] {
+            let r = unsafe { libc::kill(pid, sig) };
+            println!("{}: {} {:?}", sig, r, Error::last_os_error().raw_os_error());
+        }
+        sleep(Duration::from_millis(200));
+        println!("alive: {}", sleeper.try_wait().unwrap().is_none());
+        unsafe { libc::kill(pid, libc::SIGUSR1) };
+        println!("signal: {:?}", sleeper.wait().unwrap().signal());
+        return;
+    }
+
+    let content = common::run_child("kill_blocked_signal_not_delivered", |i| {
+        i.without_remote_mem()
+            .on_fn("kill", |pid: i32, sig: i32| match sig {
+                libc::SIGKILL => Outcome::Block(-libc::EPERM),
+                libc::SIGTERM => Outcome::Block(0),
+                _ => Outcome::Real((pid, sig)),
+            });
+    });
+    for line in [
+        format!("{}: -1 Some({})\n", libc::SIGKILL, libc::EPERM),
+        format!("{}: 0 ", libc::SIGTERM),
+        "alive: true\n".to_owned(),
+        format!("signal: Some({})\n", libc::SIGUSR1),
+    ] {
+        assert!(content.contains(&line), "{}", content);
+    }
+}
@@ -0,0 +1,36 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use interceptor_rs::{Interceptor, LaunchSpec};
+use std::{env, ffi::OsString, process::Command};
+
+#[test]
+fn launch_spec_of_command() {
+    let dir = env::temp_dir();
+    let mut cmd = Command::new("sh");
+    cmd.args(["-c", "exit 0"])
+        .env("INTERCEPTOR_TEST_SET", "1")
+        .env_remove("INTERCEPTOR_TEST_REMOVED")
+        .current_dir(&dir);
+
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    let expected = LaunchSpec {
+        program: "sh".into(),
+        args: vec!["-c".into(), "exit 0".into()],
+        envs: vec![
+            ("INTERCEPTOR_TEST_REMOVED".into(), None),
+            ("INTERCEPTOR_TEST_SET".into(), Some("1".into())),
+        ],
+        current_dir: Some(dir.clone()),
+    };
+    assert_eq!(*interceptor.launch_spec(), expected);
+
+    interceptor.without_remote_mem().run().unwrap();
+    assert_eq!(*interceptor.launch_spec(), expected);
+    let cmd = expected.to_command();
+    assert_eq!(cmd.get_program(), "sh");
+    assert_eq!(
+        cmd.get_args().collect::<Vec<_>>(),
+        [OsString::from("-c"), OsString::from("exit 0")]
+    );
+    assert_eq!(cmd.get_current_dir(), Some(dir.as_path()));
+}
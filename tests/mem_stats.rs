@@ -0,0 +1,50 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, Interceptor, MemStats};
+use std::{
+    env,
+    ffi::{c_char, CStr},
+    fs,
+};
+
+const FILE: &str = "INTERCEPTOR_TEST_FILE";
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = unsafe { CStr::from_ptr(filename) }.to_bytes();
+    if file.ends_with(b"/origin") {
+        // written back in place
+        let at = file.len() - b"origin".len();
+        unsafe { *(filename as *mut u8).add(at) = b'O' };
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn count_remote_memory_access() {
+    if common::is_child() {
+        let _ = fs::read(env::var(FILE).unwrap());
+        return;
+    }
+
+    let dir = common::test_dir("mem-stats");
+    let file = dir.join("origin");
+    let len = file.as_os_str().len() as u64;
+    let mut cmd = common::child_command("count_remote_memory_access");
+    cmd.env(FILE, &file);
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    interceptor.without_remote_mem().on(&openat);
+    assert_eq!(interceptor.stats(), MemStats::default());
+    interceptor.run().unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+
+    let stats = interceptor.stats();
+    // strings are read 8 bytes at a time
+    assert!(stats.read_bytes >= len, "{:?}", stats);
+    assert!(stats.reads >= len / 8, "{:?}", stats);
+    assert!(stats.writes >= 1, "{:?}", stats);
+    assert!(stats.written_bytes >= len, "{:?}", stats);
+}
@@ -0,0 +1,68 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{FdEvent, FdOp, FdTarget, Outcome};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    path::PathBuf,
+    rc::Rc,
+};
+
+/// names around the 8 bytes read at a time, up to the longest one allowed
+fn names() -> Vec<String> {
+    [1, 7, 8, 9, 249].map(|n| "m".repeat(n)).to_vec()
+}
+
+#[test]
+fn memfd_names_and_fds() {
+    if common::is_child() {
+        for name in names() {
+            let c = CString::new(name.clone()).unwrap();
+            let fd = unsafe { libc::memfd_create(c.as_ptr(), 0) };
+            println!("memfd {}: {}", name.len(), fd);
+        }
+        println!("eventfd: {}", unsafe { libc::eventfd(0, 0) });
+        return;
+    }
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let decoded = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("memfd_names_and_fds", |i| {
+        {
+            let events = events.clone();
+            let decoded = decoded.clone();
+            i.on_fn("memfd_create", move |name: *const c_char, flags: u32| {
+                let s = unsafe { CStr::from_ptr(name) };
+                decoded.borrow_mut().push(s.to_string_lossy().into_owned());
+                Outcome::<i32, _>::Real((name, flags))
+            })
+            .on_fd_events(move |e: &FdEvent| events.borrow_mut().push(e.clone()));
+        }
+        i.without_remote_mem();
+    });
+    assert_eq!(*decoded.borrow(), names());
+    let events = events.borrow();
+    let fd_of = |key: &str| -> i32 {
+        let (_, rest) = content
+            .split_once(&format!("{}: ", key))
+            .unwrap_or_else(|| panic!("{}", content));
+        rest.lines().next().unwrap().parse().unwrap()
+    };
+    for name in names() {
+        let fd = fd_of(&format!("memfd {}", name.len()));
+        let event = events
+            .iter()
+            .find(|e| e.op == FdOp::MemFd && e.fd == fd)
+            .unwrap_or_else(|| panic!("{} not found in {:#?}", fd, events));
+        assert_eq!(
+            event.path_or_addr,
+            Some(FdTarget::MemFd(PathBuf::from(&name)))
+        );
+    }
+    let eventfd = fd_of("eventfd");
+    assert!(events
+        .iter()
+        .any(|e| e.op == FdOp::Anon && e.fd == eventfd && e.name.starts_with("eventfd")));
+}
@@ -0,0 +1,56 @@
+#![cfg(target_os = "linux")]
+
+use interceptor_rs::{Interceptor, MapEntry};
+use std::{env, process};
+
+#[test]
+fn parse_map_lines() {
+    let entry: MapEntry =
+        "7f1c2a000000-7f1c2a022000 r-xp 00001000 08:01 1234    /usr/lib/libc.so.6"
+            .parse()
+            .unwrap();
+    assert_eq!(
+        entry,
+        MapEntry {
+            start: 0x7f1c2a000000,
+            end: 0x7f1c2a022000,
+            perms: "r-xp".to_owned(),
+            offset: 0x1000,
+            path: Some("/usr/lib/libc.so.6".to_owned()),
+        }
+    );
+    assert!(entry.is_executable());
+    assert!(entry.contains(0x7f1c2a000000));
+    assert!(!entry.contains(0x7f1c2a022000));
+
+    let anon: MapEntry = "7ffd1000-7ffd3000 rw-p 00000000 00:00 0 ".parse().unwrap();
+    assert_eq!(anon.path, None);
+    assert!(!anon.is_executable());
+
+    let spaced: MapEntry = "1000-2000 r--s 00000000 00:05 42   /tmp/a b (deleted)"
+        .parse()
+        .unwrap();
+    assert_eq!(spaced.path.as_deref(), Some("/tmp/a b (deleted)"));
+
+    let pseudo: MapEntry = "1000-2000 rw-p 00000000 00:00 0          [heap]"
+        .parse()
+        .unwrap();
+    assert_eq!(pseudo.path.as_deref(), Some("[heap]"));
+
+    assert!("1000 rw-p 00000000 00:00 0".parse::<MapEntry>().is_err());
+    assert!("xyz-2000 rw-p 00000000 00:00 0"
+        .parse::<MapEntry>()
+        .is_err());
+}
+
+#[test]
+fn read_own_maps() {
+    let maps = Interceptor::memory_maps(process::id() as i32).unwrap();
+    let exe = env::current_exe().unwrap();
+    let code = parse_map_lines as fn() as usize as u64;
+    let entry = maps.iter().find(|m| m.contains(code)).unwrap();
+    assert!(entry.is_executable());
+    assert_eq!(entry.path.as_deref(), exe.to_str());
+
+    assert!(Interceptor::memory_maps(-1).is_err());
+}
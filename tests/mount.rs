@@ -0,0 +1,83 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{flags::MountFlags, syscall, Outcome};
+use std::{
+    env,
+    ffi::{c_char, CStr, CString},
+    io::Error,
+    ptr::null,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static ENTERED: AtomicUsize = AtomicUsize::new(0);
+static RETURNED: AtomicUsize = AtomicUsize::new(0);
+
+/// logs before the blocking handler, its code after `real!()` must not run for a
+/// blocked mount
+#[syscall]
+fn mount(
+    source: *const c_char,
+    target: *const c_char,
+    fstype: *const c_char,
+    flags: MountFlags,
+    data: u64,
+) -> i32 {
+    ENTERED.fetch_add(1, Ordering::SeqCst);
+    let ret = real!(source, target, fstype, flags, data);
+    RETURNED.fetch_add(1, Ordering::SeqCst);
+    ret
+}
+
+#[test]
+fn block_mount_by_fstype() {
+    if common::is_child() {
+        let target = CString::new(env::temp_dir().to_string_lossy().as_bytes()).unwrap();
+        for fstype in ["tmpfs", "interceptor-no-such-fs"] {
+            let fstype = CString::new(fstype).unwrap();
+            let ret = unsafe {
+                libc::mount(
+                    fstype.as_ptr(),
+                    target.as_ptr(),
+                    fstype.as_ptr(),
+                    libc::MS_NOSUID | libc::MS_NODEV,
+                    null(),
+                )
+            };
+            if ret < 0 {
+                let errno = Error::last_os_error().raw_os_error();
+                println!("{:?}: {:?}", fstype, errno);
+            }
+        }
+        return;
+    }
+
+    let content = common::run_child("block_mount_by_fstype", |i| {
+        i.without_remote_mem().on(&mount).on_fn(
+            "mount",
+            |source: *const c_char,
+             target: *const c_char,
+             fstype: *const c_char,
+             flags: MountFlags,
+             data: u64| {
+                assert_eq!(flags, MountFlags::MS_NOSUID | MountFlags::MS_NODEV);
+                if unsafe { CStr::from_ptr(fstype) }.to_bytes() == b"tmpfs" {
+                    Outcome::Block(-libc::EROFS)
+                } else {
+                    Outcome::Real((source, target, fstype, flags, data))
+                }
+            },
+        );
+    });
+    let expected = format!("\"tmpfs\": Some({})\n", libc::EROFS);
+    assert!(content.contains(&expected), "{}", content);
+    // sent to kernel, which fails as the filesystem type is unknown (or not permitted)
+    assert!(
+        content.contains("\"interceptor-no-such-fs\": Some("),
+        "{}",
+        content
+    );
+    assert_eq!(ENTERED.load(Ordering::SeqCst), 2);
+    assert_eq!(RETURNED.load(Ordering::SeqCst), 1);
+}
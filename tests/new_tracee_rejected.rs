@@ -0,0 +1,87 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::with_context;
+use std::{cell::RefCell, fs, rc::Rc};
+
+/// `getppid` returns it when intercepted
+const FAKE_PPID: i64 = 4242;
+
+/// whether `pid` is a thread of another process, e.g. the one running tests
+fn is_thread(pid: i32) -> bool {
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).unwrap();
+    let tgid = status
+        .lines()
+        .find_map(|l| l.strip_prefix("Tgid:"))
+        .unwrap()
+        .trim();
+    tgid != pid.to_string()
+}
+
+#[test]
+fn new_tracee_rejected() {
+    if common::is_child() {
+        println!("parent: {}", unsafe { libc::getppid() });
+        match unsafe { libc::fork() } {
+            0 => {
+                println!("forked: {}", unsafe { libc::getppid() });
+                unsafe { libc::_exit(0) };
+            }
+            pid => {
+                let mut status = 0;
+                unsafe { libc::waitpid(pid, &mut status, 0) };
+                println!("forked exited: {}", libc::WEXITSTATUS(status));
+            }
+        }
+        return;
+    }
+
+    let tracees = Rc::new(RefCell::new(Vec::new()));
+    let handled = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("new_tracee_rejected", |i| {
+        {
+            let tracees = tracees.clone();
+            i.on_new_tracee(move |pid, parent| {
+                if is_thread(pid) {
+                    return true;
+                }
+                tracees.borrow_mut().push((pid, parent));
+                // only the spawned child is traced, not the one it forks
+                parent.is_none()
+            });
+        }
+        {
+            let handled = handled.clone();
+            i.on_return_of("getppid", move |_| {
+                handled.borrow_mut().push(with_context(|ctx| ctx.pid()));
+                FAKE_PPID
+            });
+        }
+        i.without_remote_mem();
+    });
+    let tracees = tracees.borrow();
+    assert_eq!(tracees.len(), 2, "{:?}", tracees);
+    let (child, None) = tracees[0] else {
+        panic!("{:?}", tracees)
+    };
+    // forked by the thread running the test
+    let (forked, Some(_)) = tracees[1] else {
+        panic!("{:?}", tracees)
+    };
+    // the forked one is not intercepted but still runs to the end
+    let handled = handled.borrow();
+    assert_eq!(handled.len(), 1, "{:?}", handled);
+    assert_ne!(handled[0], forked);
+    assert!(
+        content.contains(&format!("parent: {}\n", FAKE_PPID)),
+        "{}",
+        content
+    );
+    assert!(
+        content.contains(&format!("forked: {}\n", child)),
+        "{}",
+        content
+    );
+    assert!(content.contains("forked exited: 0\n"), "{}", content);
+}
@@ -0,0 +1,30 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::io::Error;
+
+#[test]
+fn noop_returns_without_kernel() {
+    if common::is_child() {
+        // both fail with EBADF if sent to kernel
+        for (name, ret) in [
+            ("fsync", unsafe { libc::fsync(-1) }),
+            ("fdatasync", unsafe { libc::fdatasync(-1) }),
+        ] {
+            if ret < 0 {
+                println!("{}: {:?}", name, Error::last_os_error().raw_os_error());
+            } else {
+                println!("{}: {}", name, ret);
+            }
+        }
+        return;
+    }
+
+    let content = common::run_child("noop_returns_without_kernel", |i| {
+        i.without_remote_mem().noop("fsync", 0);
+    });
+    assert!(content.contains("fsync: 0\n"), "{}", content);
+    let expected = format!("fdatasync: Some({})\n", libc::EBADF);
+    assert!(content.contains(&expected), "{}", content);
+}
@@ -0,0 +1,46 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, Interceptor};
+use std::{
+    fs::{self, File},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static CALLED: AtomicUsize = AtomicUsize::new(0);
+
+/// swallow the write without real!()
+#[syscall]
+fn write(fd: i32, _buf: u64, count: u64) -> i64 {
+    assert_eq!(fd, libc::STDERR_FILENO);
+    CALLED.fetch_add(1, Ordering::SeqCst);
+    count as i64
+}
+
+#[test]
+fn filtered_by_raw_args() {
+    if common::is_child() {
+        println!("stdout: shown");
+        eprintln!("stderr: hidden");
+        return;
+    }
+
+    let dir = common::test_dir("on-filtered");
+    let mut cmd = common::child_command("filtered_by_raw_args");
+    cmd.stdout(File::create(dir.join("stdout")).unwrap())
+        .stderr(File::create(dir.join("stderr")).unwrap());
+    Interceptor::new(cmd)
+        .unwrap()
+        .without_remote_mem()
+        .on_filtered(&write, |args| args[0] == libc::STDERR_FILENO as u64)
+        .run()
+        .unwrap();
+
+    let stdout = fs::read_to_string(dir.join("stdout")).unwrap();
+    let stderr = fs::read_to_string(dir.join("stderr")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(stdout.contains("stdout: shown\n"), "{}", stdout);
+    assert!(!stderr.contains("hidden"), "{}", stderr);
+    assert!(CALLED.load(Ordering::SeqCst) > 0);
+}
@@ -0,0 +1,53 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Outcome;
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr},
+    fs,
+    rc::Rc,
+};
+
+#[test]
+fn on_fn_with_captured_state() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for i in 0..3 {
+            match fs::create_dir(dir.join(i.to_string())) {
+                Ok(_) => println!("{}: ok", i),
+                Err(e) => println!("{}: {:?}", i, e.raw_os_error()),
+            }
+        }
+        return;
+    }
+
+    let dir = common::test_dir("on-fn");
+
+    let paths = Rc::new(RefCell::new(Vec::new()));
+    let output = common::run_child_in("on_fn_with_captured_state", &dir, |i| {
+        {
+            let paths = paths.clone();
+            // fail every other call
+            i.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+                let path = unsafe { CStr::from_ptr(path) };
+                paths.borrow_mut().push(path.to_string_lossy().into_owned());
+                if paths.borrow().len() % 2 == 0 {
+                    Outcome::Block(-libc::EACCES)
+                } else {
+                    Outcome::Real((path.as_ptr(), mode))
+                }
+            });
+        }
+        i.without_remote_mem();
+    });
+    let created = [0, 1, 2].map(|i| dir.join(i.to_string()).exists());
+    fs::remove_dir_all(&dir).unwrap();
+    let expected = format!("0: ok\n1: Some({})\n2: ok\n", libc::EACCES);
+    assert!(output.contains(&expected), "{}", output);
+    assert_eq!(created, [true, false, true]);
+    let paths = paths.borrow();
+    assert_eq!(paths.len(), 3);
+    assert!(paths[1].ends_with("/1"), "{:?}", paths);
+}
@@ -0,0 +1,44 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Outcome;
+use std::{
+    ffi::{c_char, CStr},
+    fs,
+};
+
+#[test]
+fn skip_write_back_of_none() {
+    if common::is_child() {
+        let path = format!("{}/created\0", common::child_dir().display());
+        let fd = unsafe { libc::open(path.as_ptr() as *const c_char, libc::O_WRONLY, 0o600) };
+        println!("fd: {}", fd >= 0);
+        return;
+    }
+
+    let dir = common::test_dir("on-fn-partial");
+
+    let output = common::run_child_in("skip_write_back_of_none", &dir, |i| {
+        i.on_fn_partial(
+            "openat",
+            |_: i32, filename: *const c_char, flags: i32, mode: u32| -> Outcome<i32, _> {
+                if !unsafe { CStr::from_ptr(filename) }
+                    .to_bytes()
+                    .ends_with(b"/created")
+                {
+                    return Outcome::Real((None, None, None, None));
+                }
+
+                // changed here only, as the filename is not returned
+                unsafe { *(filename as *mut u8) = b'X' };
+                Outcome::Real((None, None, Some(flags | libc::O_CREAT), Some(mode)))
+            },
+        );
+        i.without_remote_mem();
+    });
+    let created = dir.join("created").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("fd: true\n"), "{}", output);
+    assert!(created);
+}
@@ -0,0 +1,60 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{NthScope, Outcome};
+use std::{ffi::c_char, fs, thread};
+
+const FAKE_PPID: i32 = 4242;
+
+#[test]
+fn on_nth_of_session_and_tracee() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let created = (0..5)
+            .map(|i| fs::create_dir(dir.join(i.to_string())).is_ok())
+            .collect::<Vec<_>>();
+        println!("created: {:?}", created);
+        for i in 0..2 {
+            let ppids = thread::spawn(|| {
+                (0..3)
+                    .map(|_| unsafe { libc::syscall(libc::SYS_getppid) } as i32 == FAKE_PPID)
+                    .collect::<Vec<_>>()
+            })
+            .join()
+            .unwrap();
+            println!("thread {}: {:?}", i, ppids);
+        }
+        return;
+    }
+
+    let dir = common::test_dir("on-nth");
+
+    let content = common::run_child_in("on_nth_of_session_and_tracee", &dir, |i| {
+        i.without_remote_mem()
+            .on_nth(
+                "mkdir",
+                3,
+                NthScope::Session,
+                |_: *const c_char, _: u32| -> Outcome<i32, (*const c_char, u32)> {
+                    Outcome::Block(-libc::EACCES)
+                },
+            )
+            .on_nth("getppid", 2, NthScope::PerTracee, || {
+                Outcome::<i32, ()>::Block(FAKE_PPID)
+            });
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        content.contains("created: [true, true, false, true, true]\n"),
+        "{}",
+        content
+    );
+    for i in 0..2 {
+        assert!(
+            content.contains(&format!("thread {}: [false, true, false]\n", i)),
+            "{}",
+            content
+        );
+    }
+}
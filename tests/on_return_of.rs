@@ -0,0 +1,36 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use std::{cell::RefCell, fs, rc::Rc};
+
+#[test]
+fn on_return_of_counts_and_changes() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for sub in ["a", "a", "b/c"] {
+            let _ = fs::create_dir(dir.join(sub));
+        }
+        println!("ppid: {}", unsafe { libc::getppid() });
+        return;
+    }
+
+    let dir = common::test_dir("on-return-of");
+
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let output = common::run_child_in("on_return_of_counts_and_changes", &dir, |i| {
+        {
+            let errors = errors.clone();
+            i.on_return_of("mkdir", move |ret| {
+                if ret < 0 {
+                    errors.borrow_mut().push(-ret as i32);
+                }
+                ret
+            });
+        }
+        i.on_return_of("getppid", |_| 4242).without_remote_mem();
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("ppid: 4242\n"), "{}", output);
+    assert_eq!(*errors.borrow(), [libc::EEXIST, libc::ENOENT]);
+}
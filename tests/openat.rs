@@ -0,0 +1,61 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::syscall;
+use std::{
+    ffi::{c_char, CStr, CString},
+    fs,
+    mem::forget,
+};
+
+#[syscall]
+fn openat(dfd: i32, mut filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = unsafe { CStr::from_ptr(filename) }.to_bytes().to_vec();
+    if file.ends_with(b"/origin") {
+        // same length, rewritten in place
+        let at = file.len() - b"origin".len();
+        unsafe {
+            std::ptr::copy_nonoverlapping(
+                b"target".as_ptr(),
+                (filename as *mut u8).add(at),
+                b"target".len(),
+            )
+        };
+    } else if let Some(dir) = file.strip_suffix(b"/short") {
+        // longer one needs remote memory
+        let mut longer = dir.to_vec();
+        longer.extend(b"/much-longer-target");
+        let longer = CString::new(longer).unwrap();
+        filename = longer.as_ptr();
+        forget(longer);
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn openat_rewrites_filename() {
+    if common::is_child() {
+        common::print_files(&["origin", "short"]);
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("openat");
+    for (name, content) in [
+        ("origin", "requested"),
+        ("target", "rewritten"),
+        ("short", "requested"),
+        ("much-longer-target", "replaced"),
+    ] {
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    let output = common::run_child_in("openat_rewrites_filename", &dir, |i| {
+        i.on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("origin: rewritten\n"), "{}", output);
+    assert!(output.contains("short: replaced\n"), "{}", output);
+}
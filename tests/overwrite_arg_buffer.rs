@@ -0,0 +1,56 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, InterceptError};
+use parking_lot::Mutex;
+use std::ffi::c_char;
+
+const SECRET: &[u8] = b"hunter2";
+
+/// whether each rejected write is too large
+static REJECTED: Mutex<Vec<Option<bool>>> = Mutex::new(Vec::new());
+
+#[syscall]
+fn read(fd: i32, buf: *mut c_char, count: u64) -> i64 {
+    let ret = real!(fd, buf, count);
+    with_context(|ctx| {
+        let data = ctx.read_arg_buffer(1, 2, ret.max(0) as usize).unwrap();
+        if data != SECRET {
+            return;
+        }
+        for (size_index, data) in [(1, SECRET), (6, SECRET), (2, &[b'*'; 64][..])] {
+            let r = ctx.overwrite_arg_buffer(1, size_index, data);
+            REJECTED.lock().push(
+                r.err()
+                    .map(|e| matches!(e, InterceptError::WriteTooLarge { .. })),
+            );
+        }
+        ctx.overwrite_arg_buffer(1, 2, &[b'*'; 7]).unwrap();
+    });
+    ret
+}
+
+#[test]
+fn overwrite_arg_buffer_scrubbed() {
+    if common::is_child() {
+        let mut fds = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        unsafe { libc::write(fds[1], SECRET.as_ptr() as *const _, SECRET.len()) };
+        let mut buf = [0u8; 16];
+        let n = unsafe { libc::read(fds[0], buf.as_mut_ptr() as *mut _, buf.len()) };
+        println!(
+            "read: {} {}",
+            n,
+            String::from_utf8_lossy(&buf[..n.max(0) as usize])
+        );
+        return;
+    }
+
+    let content = common::run_child("overwrite_arg_buffer_scrubbed", |i| {
+        i.without_remote_mem().on(&read);
+    });
+    assert!(content.contains("read: 7 *******\n"), "{}", content);
+    // a size taken from the buffer itself or out of arguments, and data over it
+    assert_eq!(*REJECTED.lock(), [Some(false), Some(false), Some(true)]);
+}
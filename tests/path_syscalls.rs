@@ -0,0 +1,76 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Outcome, PathOp};
+use std::{
+    cell::RefCell,
+    ffi::CString,
+    fs::{self},
+    path::PathBuf,
+    rc::Rc,
+};
+
+#[test]
+fn path_syscalls_normalized() {
+    if common::is_child() {
+        let _ = fs::metadata("sub/../target");
+        let _ = fs::symlink_metadata("./link");
+        let _ = fs::read_link("link");
+        let path = CString::new("target").unwrap();
+        unsafe { libc::access(path.as_ptr(), libc::R_OK) };
+        match fs::remove_file("target") {
+            Ok(()) => println!("unlink: ok"),
+            Err(e) => println!("unlink: {:?}", e.raw_os_error()),
+        }
+        return;
+    }
+
+    let dir = common::test_dir("path-syscalls");
+    fs::create_dir(dir.join("sub")).unwrap();
+    fs::write(dir.join("target"), "target").unwrap();
+    std::os::unix::fs::symlink("target", dir.join("link")).unwrap();
+    let dir = fs::canonicalize(&dir).unwrap();
+
+    let mut cmd = common::child_command("path_syscalls_normalized");
+    cmd.current_dir(&dir);
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let content = common::capture(cmd, |i| {
+        let seen = seen.clone();
+        i.without_remote_mem().on_path_syscalls(move |ctx| {
+            seen.borrow_mut().push((ctx.op, ctx.resolved_path.clone()));
+            if ctx.op == PathOp::Unlink {
+                Outcome::Block(-libc::EACCES as i64)
+            } else {
+                Outcome::Real(())
+            }
+        });
+    });
+    let target_exists = dir.join("target").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        content.contains(&format!("unlink: Some({})\n", libc::EACCES)),
+        "{}",
+        content
+    );
+    assert!(target_exists);
+
+    let target: PathBuf = dir.join("target");
+    let link: PathBuf = dir.join("link");
+    let seen = seen.borrow();
+    for expected in [
+        (PathOp::Stat, &target),
+        (PathOp::Lstat, &link),
+        (PathOp::Readlink, &link),
+        (PathOp::Access, &target),
+        (PathOp::Unlink, &target),
+    ] {
+        assert!(
+            seen.iter()
+                .any(|(op, p)| *op == expected.0 && p == expected.1),
+            "{:?} not in {:?}",
+            expected,
+            seen
+        );
+    }
+}
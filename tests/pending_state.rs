@@ -0,0 +1,61 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", debug_assertions))]
+
+mod common;
+
+use interceptor_rs::{Interceptor, Outcome, PendingState};
+use std::{
+    ffi::{c_char, CStr},
+    fs,
+    sync::{Arc, Mutex},
+};
+
+#[test]
+fn pending_state_at_exit() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let _ = fs::create_dir(dir.join("blocked"));
+        let _ = fs::create_dir(dir.join("kept"));
+        return;
+    }
+
+    let dir = common::test_dir("pending-state");
+    let mut cmd = common::child_command("pending_state_at_exit");
+    cmd.env(common::DIR, &dir);
+
+    // taken at exit stops of `mkdir`
+    let states: Arc<Mutex<Vec<PendingState>>> = Arc::default();
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    let controller = interceptor.controller();
+    {
+        let states = states.clone();
+        interceptor.on_fn("mkdir", move |path: *const c_char, mode: u32| {
+            let name = unsafe { CStr::from_ptr(path) }.to_string_lossy();
+            let blocked = name.ends_with("/blocked");
+            if blocked || name.ends_with("/kept") {
+                let states = states.clone();
+                controller.apply(move |i| states.lock().unwrap().push(i.pending_state()));
+            }
+            if blocked {
+                Outcome::Block(-libc::EPERM)
+            } else {
+                Outcome::Real((path, mode))
+            }
+        });
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    assert_eq!(interceptor.pending_state(), PendingState::default());
+    let kept = dir.join("kept").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(kept);
+    let states = states.lock().unwrap();
+    assert_eq!(states.len(), 2, "{:?}", states);
+    let blocked = &states[0];
+    assert_eq!(blocked.blocked.len(), 1, "{:?}", blocked);
+    assert_eq!(blocked.blocked[0].sysno, libc::SYS_mkdir as u64);
+    assert_eq!(blocked.blocked[0].ret, -libc::EPERM as i64 as u64);
+    assert_ne!(blocked.blocked[0].fake_sysno, libc::SYS_mkdir as u64);
+    let kept = &states[1];
+    assert!(kept.blocked.is_empty(), "{:?}", kept);
+    assert_eq!(kept.contexts.len(), 1, "{:?}", kept);
+}
@@ -0,0 +1,51 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{flags::PrctlOption, Outcome};
+use std::{cell::RefCell, io::Error, rc::Rc};
+
+#[test]
+fn prctl_deny_seccomp() {
+    if common::is_child() {
+        let ret = unsafe { libc::prctl(libc::PR_SET_NO_NEW_PRIVS, 1, 0, 0, 0) };
+        println!("no new privs: {}", ret);
+        let ret = unsafe { libc::prctl(libc::PR_SET_SECCOMP, libc::SECCOMP_MODE_STRICT, 0, 0, 0) };
+        println!(
+            "seccomp: {} {:?}",
+            ret,
+            Error::last_os_error().raw_os_error()
+        );
+        // still alive, strict mode would only allow read, write and exit
+        let ret = unsafe { libc::prctl(libc::PR_GET_SECCOMP, 0, 0, 0, 0) };
+        println!("mode: {}", ret);
+        return;
+    }
+
+    let options = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("prctl_deny_seccomp", |i| {
+        {
+            let options = options.clone();
+            i.on_fn(
+                "prctl",
+                move |option: PrctlOption, arg2: u64, arg3: u64, arg4: u64, arg5: u64| {
+                    options.borrow_mut().push(format!("{:?}", option));
+                    if option == PrctlOption::PR_SET_SECCOMP {
+                        Outcome::Block(-libc::EPERM)
+                    } else {
+                        Outcome::Real((option, arg2, arg3, arg4, arg5))
+                    }
+                },
+            );
+        }
+        i.without_remote_mem();
+    });
+    assert!(content.contains("no new privs: 0\n"), "{}", content);
+    let expected = format!("seccomp: -1 Some({})\n", libc::EPERM);
+    assert!(content.contains(&expected), "{}", content);
+    assert!(content.contains("mode: 0\n"), "{}", content);
+    let options = options.borrow();
+    for option in ["PR_SET_NO_NEW_PRIVS", "PR_SET_SECCOMP", "PR_GET_SECCOMP"] {
+        assert!(options.iter().any(|o| o == option), "{:?}", options);
+    }
+}
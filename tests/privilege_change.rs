@@ -0,0 +1,84 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Outcome, PrivilegeChange, PrivilegeKind};
+use std::{cell::RefCell, collections::HashSet, fs, rc::Rc};
+
+const DENIED: u32 = 12345;
+
+/// distinct `Uid:` and `Groups:` lines of all threads
+fn credentials() -> HashSet<String> {
+    fs::read_dir("/proc/self/task")
+        .unwrap()
+        .flat_map(|task| {
+            let status = fs::read_to_string(task.unwrap().path().join("status")).unwrap();
+            status
+                .lines()
+                .filter(|l| l.starts_with("Uid:") || l.starts_with("Groups:"))
+                .map(str::to_owned)
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+#[test]
+fn block_privilege_change() {
+    if common::is_child() {
+        // raw syscalls change the calling thread only, libc ones broadcast to the others
+        // by a realtime signal
+        let before = credentials();
+        let denied = DENIED as libc::c_long;
+        let r = unsafe { libc::syscall(libc::SYS_setresuid, -1, denied, -1) };
+        println!("setresuid: {} {}", r, std::io::Error::last_os_error());
+        let r = unsafe { libc::syscall(libc::SYS_setgroups, 1, &DENIED) };
+        println!("setgroups: {}", r);
+        println!("unchanged: {}", before == credentials());
+        let r = unsafe { libc::syscall(libc::SYS_setgid, libc::getgid()) };
+        println!("setgid: {}", r);
+        return;
+    }
+
+    let changes = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("block_privilege_change", |i| {
+        {
+            let changes = changes.clone();
+            i.on_privilege_change(move |c: &PrivilegeChange| {
+                changes.borrow_mut().push(c.clone());
+                if c.effective == Some(DENIED) || c.groups.contains(&DENIED) {
+                    Outcome::Block(-libc::EPERM as i64)
+                } else {
+                    Outcome::Real(())
+                }
+            });
+        }
+        i.without_remote_mem();
+    });
+    assert!(
+        content.contains(&format!(
+            "setresuid: -1 {}",
+            std::io::Error::from_raw_os_error(libc::EPERM)
+        )),
+        "{}",
+        content
+    );
+    assert!(content.contains("setgroups: -1"), "{}", content);
+    assert!(content.contains("setgid: 0"), "{}", content);
+    assert!(content.contains("unchanged: true"), "{}", content);
+
+    let changes = changes.borrow();
+    let setresuid: Vec<_> = changes.iter().filter(|c| c.name == "setresuid").collect();
+    assert_eq!(setresuid.len(), 1, "{:#?}", changes);
+    assert_eq!(setresuid[0].kind, PrivilegeKind::Uid);
+    assert_eq!(
+        (setresuid[0].real, setresuid[0].saved),
+        (None, None),
+        "{:#?}",
+        changes
+    );
+    let setgroups = changes.iter().find(|c| c.name == "setgroups").unwrap();
+    assert_eq!(setgroups.groups, vec![DENIED]);
+    let setgid = changes.iter().find(|c| c.name == "setgid").unwrap();
+    assert_eq!(setgid.kind, PrivilegeKind::Gid);
+    assert_eq!(setgid.effective, setgid.real);
+}
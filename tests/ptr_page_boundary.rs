@@ -0,0 +1,96 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{read_ptr_to_ptr, syscall, Interceptor};
+use parking_lot::Mutex;
+use std::{ffi::c_char, ptr};
+
+type Entries = Vec<Vec<u8>>;
+
+static SEEN: Mutex<Vec<(Entries, Entries)>> = Mutex::new(Vec::new());
+
+#[syscall]
+fn execve(filename: *const c_char, argv: *const *const c_char, envp: *const *const c_char) -> i32 {
+    SEEN.lock()
+        .push((read_ptr_to_ptr(argv), read_ptr_to_ptr(envp)));
+    real!(filename, argv, envp)
+}
+
+/// Lays out `argv` misaligned across the boundary of two mapped pages, and `envp` so
+/// that its NULL entry and its last string end right before an inaccessible page.
+unsafe fn exec_with_straddling_arrays() -> ! {
+    let page = libc::sysconf(libc::_SC_PAGESIZE) as usize;
+    let base = libc::mmap(
+        ptr::null_mut(),
+        page * 3,
+        libc::PROT_READ | libc::PROT_WRITE,
+        libc::MAP_PRIVATE | libc::MAP_ANONYMOUS,
+        -1,
+        0,
+    ) as usize;
+    assert_ne!(base, libc::MAP_FAILED as usize);
+    assert_eq!(
+        libc::mprotect((base + page * 2) as *mut _, page, libc::PROT_NONE),
+        0
+    );
+
+    let put = |addr: usize, s: &[u8]| {
+        ptr::copy_nonoverlapping(s.as_ptr(), addr as *mut u8, s.len());
+        addr as u64
+    };
+    let put_ptrs = |addr: usize, ptrs: &[u64]| {
+        for (i, p) in ptrs.iter().enumerate() {
+            ptr::write_unaligned((addr + i * 8) as *mut u64, *p);
+        }
+        addr as *const *const c_char
+    };
+
+    let args = [
+        put(base + 16, b"/bin/true\0"),
+        put(base + 32, b"first\0"),
+        put(base + page - 24, b"split\0"),
+        put(base + page + 64, b"third\0"),
+        0,
+    ];
+    let argv = put_ptrs(base + page - 12, &args);
+
+    let edge = b"EDGE=1\0";
+    let envs = [
+        put(base + 80, b"A=1\0"),
+        put(base + page * 2 - edge.len(), edge),
+        0,
+    ];
+    let envp = put_ptrs(base + page * 2 - edge.len() - envs.len() * 8, &envs);
+
+    libc::execve(c"/bin/true".as_ptr(), argv, envp);
+    libc::_exit(127)
+}
+
+#[test]
+fn ptr_arrays_across_page_boundary() {
+    if common::is_child() {
+        unsafe { exec_with_straddling_arrays() }
+    }
+
+    let cmd = common::child_command("ptr_arrays_across_page_boundary");
+    Interceptor::new(cmd)
+        .unwrap()
+        .without_remote_mem()
+        .on(&execve)
+        .run()
+        .unwrap();
+
+    let seen = SEEN.lock();
+    let (argv, envp) = seen.last().expect("execve not intercepted");
+    assert_eq!(
+        argv,
+        &[
+            b"/bin/true\0".to_vec(),
+            b"first\0".to_vec(),
+            b"split\0".to_vec(),
+            b"third\0".to_vec()
+        ]
+    );
+    assert_eq!(envp, &[b"A=1\0".to_vec(), b"EDGE=1\0".to_vec()]);
+}
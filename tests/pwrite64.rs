@@ -0,0 +1,67 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::{cell::RefCell, fs, os::fd::AsRawFd, rc::Rc};
+
+const SECRET: &[u8] = b"hunter2";
+const DATA: &[u8] = b"user=alice password=hunter2;";
+const OFFSET: i64 = 8;
+
+#[test]
+fn pwrite64_scrubbed() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(dir.join("data"))
+            .unwrap();
+        let n = unsafe {
+            libc::pwrite64(
+                file.as_raw_fd(),
+                DATA.as_ptr() as *const _,
+                DATA.len(),
+                OFFSET,
+            )
+        };
+        println!("written: {}", n);
+        return;
+    }
+
+    let dir = common::test_dir("pwrite64");
+    fs::write(dir.join("data"), "........").unwrap();
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child_in("pwrite64_scrubbed", &dir, |i| {
+        {
+            let seen = seen.clone();
+            i.on_fn(
+                "pwrite64",
+                move |fd: i32, buf: u64, count: u64, offset: i64| -> Outcome<i64, _> {
+                    with_context(|ctx| {
+                        let mut data = ctx.read_arg_buffer(1, 2, usize::MAX).unwrap();
+                        seen.borrow_mut().push((count, offset, data.clone()));
+                        if let Some(i) = data.windows(SECRET.len()).position(|w| w == SECRET) {
+                            data[i..i + SECRET.len()].fill(b'*');
+                            ctx.write_memory(buf, &data).unwrap();
+                        }
+                    });
+                    Outcome::Real((fd, buf, count, offset))
+                },
+            );
+        }
+        i.without_remote_mem();
+    });
+    let data = fs::read_to_string(dir.join("data")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        content.contains(&format!("written: {}\n", DATA.len())),
+        "{}",
+        content
+    );
+    assert_eq!(data, "........user=alice password=*******;");
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 1, "{:?}", seen);
+    assert_eq!(seen[0], (DATA.len() as u64, OFFSET, DATA.to_vec()));
+}
@@ -0,0 +1,68 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", feature = "iovec"))]
+
+mod common;
+
+use interceptor_rs::{with_context, IoVecs, Outcome};
+use std::{cell::RefCell, fs, os::fd::AsRawFd, rc::Rc};
+
+#[test]
+fn pwritev2_iovecs_and_offset() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let file = fs::OpenOptions::new()
+            .write(true)
+            .open(dir.join("data"))
+            .unwrap();
+        let (a, b) = (b"hello ", b"world");
+        let iov = [
+            libc::iovec {
+                iov_base: a.as_ptr() as *mut _,
+                iov_len: a.len(),
+            },
+            libc::iovec {
+                iov_base: b.as_ptr() as *mut _,
+                iov_len: b.len(),
+            },
+        ];
+        let n = unsafe { libc::pwritev2(file.as_raw_fd(), iov.as_ptr(), 2, 4, libc::RWF_DSYNC) };
+        println!("written: {}", n);
+        return;
+    }
+
+    let dir = common::test_dir("pwritev2");
+    fs::write(dir.join("data"), "....xxxxxxxxxxx.").unwrap();
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let output = common::run_child_in("pwritev2_iovecs_and_offset", &dir, |i| {
+        {
+            let seen = seen.clone();
+            i.on_fn(
+                "pwritev2",
+                move |fd: i32, iov: u64, cnt: i32, pos_l: u64, pos_h: u64, flags: i32| {
+                    with_context(|ctx| {
+                        let iovecs = IoVecs::read_arg(ctx, 1);
+                        let bufs = iovecs.read_bufs(ctx, usize::MAX);
+                        // change the second buffer in place
+                        ctx.write_memory(iovecs[1].base, b"WORLD").unwrap();
+                        seen.borrow_mut()
+                            .push((bufs, iovecs.offset(), iovecs.flags()));
+                    });
+                    Outcome::<i64, _>::Real((fd, iov, cnt, pos_l, pos_h, flags))
+                },
+            );
+        }
+        i.without_remote_mem();
+    });
+    let data = fs::read_to_string(dir.join("data")).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("written: 11\n"), "{}", output);
+    assert_eq!(data, "....hello WORLD.");
+    assert_eq!(
+        *seen.borrow(),
+        [(
+            vec![b"hello ".to_vec(), b"world".to_vec()],
+            Some(4),
+            Some(libc::RWF_DSYNC)
+        )]
+    );
+}
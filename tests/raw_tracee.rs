@@ -0,0 +1,43 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{pete::x86::DebugRegister, with_context, Interceptor, Outcome};
+use std::{cell::RefCell, process::Stdio, rc::Rc};
+
+#[test]
+fn raw_tracee_debug_register() {
+    if common::is_child() {
+        unsafe { libc::getppid() };
+        return;
+    }
+
+    let mut cmd = common::child_command("raw_tracee_debug_register");
+    cmd.stdout(Stdio::null());
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let seen = seen.clone();
+        interceptor.on_fn("getppid", move || {
+            with_context(|ctx| {
+                let tracee = ctx.raw_tracee();
+                let regs = tracee.registers().unwrap();
+                // set a debug address register and read it back, then restore it
+                let old = tracee.debug_register(DebugRegister::Dr0).unwrap();
+                tracee
+                    .set_debug_register(DebugRegister::Dr0, regs.rip)
+                    .unwrap();
+                let dr0 = tracee.debug_register(DebugRegister::Dr0).unwrap();
+                tracee.set_debug_register(DebugRegister::Dr0, old).unwrap();
+                seen.borrow_mut().push((regs.orig_rax, dr0 == regs.rip));
+            });
+            Outcome::<i32, _>::Real(())
+        });
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    let seen = seen.borrow();
+    assert_eq!(seen.len(), 1, "{:?}", seen);
+    assert_eq!(seen[0].0, libc::SYS_getppid as u64);
+    assert!(seen[0].1, "debug register not set");
+}
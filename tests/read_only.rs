@@ -0,0 +1,42 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Outcome, Phase};
+use std::{cell::RefCell, env, io::Error, rc::Rc};
+
+#[test]
+fn read_only_ignores_changes() {
+    if common::is_child() {
+        println!("LD_PRELOAD: {:?}", env::var_os("LD_PRELOAD"));
+        // fails with EBADF if sent to kernel unchanged
+        if unsafe { libc::fsync(-1) } < 0 {
+            println!("fsync: {:?}", Error::last_os_error().raw_os_error());
+        }
+        if unsafe { libc::fdatasync(-1) } < 0 {
+            println!("fdatasync: {:?}", Error::last_os_error().raw_os_error());
+        }
+        return;
+    }
+
+    let discarded = Rc::new(RefCell::new(0));
+    let content = common::run_child("read_only_ignores_changes", |i| {
+        let discarded = discarded.clone();
+        i.read_only()
+            .noop("fsync", 0)
+            .on_fn("fdatasync", |_: i32| Outcome::<i32, _>::Real((0,)))
+            .on_return_of("fdatasync", |_| 0)
+            .on_event(move |e| {
+                if e.name.ends_with("sync") && e.phase == Phase::Enter {
+                    *discarded.borrow_mut() += e.discarded.len();
+                }
+            });
+    });
+    assert!(content.contains("LD_PRELOAD: None\n"), "{}", content);
+    let expected = format!("fsync: Some({})\n", libc::EBADF);
+    assert!(content.contains(&expected), "{}", content);
+    let expected = format!("fdatasync: Some({})\n", libc::EBADF);
+    assert!(content.contains(&expected), "{}", content);
+    // blocking fsync and changing the fd of fdatasync
+    assert_eq!(*discarded.borrow(), 2);
+}
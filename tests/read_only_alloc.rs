@@ -0,0 +1,70 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, Change, Phase};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString, OsStr},
+    fs,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::Path,
+    rc::Rc,
+};
+
+const TARGET: &str = "much-longer-target";
+
+#[syscall]
+fn openat(dfd: i32, mut filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = Path::new(OsStr::from_bytes(
+        unsafe { CStr::from_ptr(filename) }.to_bytes(),
+    ));
+    let target = CString::new(file.with_file_name(TARGET).into_os_string().into_vec()).unwrap();
+    match file.file_name().and_then(|n| n.to_str()) {
+        Some("short") => {
+            with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+        }
+        // a new pointer, freed after written into target
+        Some("pointer") => filename = target.into_raw(),
+        _ => {}
+    }
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn read_only_discards_allocation() {
+    if common::is_child() {
+        common::print_files(&["short", "pointer"]);
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("read-only-alloc");
+    for (name, content) in [("short", "requested"), ("pointer", "requested")] {
+        fs::write(dir.join(name), content).unwrap();
+    }
+    fs::write(dir.join(TARGET), "allocated").unwrap();
+
+    let discarded = Rc::new(RefCell::new(Vec::new()));
+    let output = common::run_child_in("read_only_discards_allocation", &dir, |i| {
+        let discarded = discarded.clone();
+        i.read_only().on(&openat).on_event(move |e| {
+            if e.name == "openat" && e.phase == Phase::Enter {
+                discarded.borrow_mut().extend(e.discarded.clone());
+            }
+        });
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("short: requested\n"), "{}", output);
+    assert!(output.contains("pointer: requested\n"), "{}", output);
+    // the string and the new pointer, never allocated in target
+    let discarded = discarded.borrow();
+    assert_eq!(discarded.len(), 2, "{:?}", discarded);
+    for change in discarded.iter() {
+        let Change::Alloc { data } = change else {
+            panic!("{:?}", discarded)
+        };
+        let data = CStr::from_bytes_with_nul(data).unwrap();
+        assert_eq!(data.to_bytes(), dir.join(TARGET).as_os_str().as_bytes());
+    }
+}
@@ -0,0 +1,65 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::with_context;
+use std::{cell::RefCell, rc::Rc};
+
+const FAKE: &[u8] = b"/opt/fake/exe";
+
+#[test]
+fn readlink_rewritten() {
+    if common::is_child() {
+        let mut buf = [b'#'; 32];
+        let n = unsafe {
+            libc::readlink(
+                c"/proc/self/exe".as_ptr(),
+                buf.as_mut_ptr() as *mut _,
+                buf.len(),
+            )
+        };
+        println!(
+            "readlink: {} {}",
+            n,
+            String::from_utf8_lossy(&buf[..n.max(0) as usize])
+        );
+        let mut buf = [b'#'; 8];
+        let n = unsafe {
+            libc::readlinkat(
+                libc::AT_FDCWD,
+                c"/proc/self/exe".as_ptr(),
+                buf.as_mut_ptr() as *mut _,
+                5,
+            )
+        };
+        println!("readlinkat: {} {}", n, String::from_utf8_lossy(&buf));
+        return;
+    }
+
+    let errors = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("readlink_rewritten", |i| {
+        {
+            let errors = errors.clone();
+            i.on_return_of("getpid", move |ret| {
+                let r = with_context(|ctx| ctx.set_link_target(FAKE));
+                errors.borrow_mut().push(r.is_err());
+                ret
+            });
+        }
+        i.without_remote_mem()
+            .on_return_of("readlink", |ret| {
+                with_context(|ctx| ctx.set_link_target(FAKE)).unwrap_or(ret)
+            })
+            .on_return_of("readlinkat", |ret| {
+                with_context(|ctx| ctx.set_link_target(FAKE)).unwrap_or(ret)
+            });
+    });
+    assert!(
+        content.contains("readlink: 13 /opt/fake/exe\n"),
+        "{}",
+        content
+    );
+    // cut to `bufsiz` and not NUL terminated, bytes after it are untouched
+    assert!(content.contains("readlinkat: 5 /opt/###\n"), "{}", content);
+    assert!(errors.borrow().iter().all(|e| *e));
+}
@@ -0,0 +1,34 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::syscall;
+
+/// `real!()` called by its full path still sends the syscall to kernel
+#[syscall]
+fn umask(mask: u32) -> u32 {
+    let old = interceptor_rs::real!(mask | 0o007);
+    old
+}
+
+/// a trailing comma is kept as written
+#[syscall]
+fn dup2(oldfd: i32, newfd: i32) -> i32 {
+    real!(oldfd, newfd,)
+}
+
+#[test]
+fn detect_qualified_real() {
+    if common::is_child() {
+        unsafe { libc::umask(0o022) };
+        println!("umask: {:o}", unsafe { libc::umask(0) });
+        println!("dup2: {}", unsafe { libc::dup2(1, 10) });
+        return;
+    }
+
+    let content = common::run_child("detect_qualified_real", |i| {
+        i.without_remote_mem().on(&umask).on(&dup2);
+    });
+    assert!(content.contains("umask: 27\n"), "{}", content);
+    assert!(content.contains("dup2: 10\n"), "{}", content);
+}
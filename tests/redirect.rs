@@ -0,0 +1,71 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{Decision, Phase};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::CString,
+    fs::{self, File},
+    io::Read,
+    os::fd::FromRawFd,
+    rc::Rc,
+};
+
+const FILE: &str = "INTERCEPTOR_TEST_FILE";
+
+#[test]
+fn redirect_open_to_openat() {
+    if common::is_child() {
+        let path = CString::new(env::var(FILE).unwrap()).unwrap();
+        let fd = unsafe { libc::syscall(libc::SYS_open, path.as_ptr(), libc::O_RDONLY) };
+        assert!(fd >= 0, "open error: {}", std::io::Error::last_os_error());
+        let mut content = String::new();
+        let mut file = unsafe { File::from_raw_fd(fd as i32) };
+        file.read_to_string(&mut content).unwrap();
+        println!("content: {}", content);
+        println!("getppid: {}", unsafe { libc::getppid() == libc::getpid() });
+        return;
+    }
+
+    let dir = common::test_dir("redirect");
+    fs::write(dir.join("file"), "opened").unwrap();
+    let mut cmd = common::child_command("redirect_open_to_openat");
+    cmd.env(FILE, dir.join("file"));
+
+    let events = Rc::new(RefCell::new(Vec::new()));
+    let output = common::capture(cmd, |i| {
+        let events = events.clone();
+        i.on_event(move |e| {
+            if e.name.starts_with("open") || e.name.ends_with("pid") {
+                events.borrow_mut().push((e.name, e.phase));
+            }
+        })
+        .on_matching("open", |e| {
+            let [path, flags, mode, ..] = e.args;
+            Decision::Redirect {
+                sysno: libc::SYS_openat as u64,
+                args: [libc::AT_FDCWD as u64, path, flags, mode, 0, 0],
+            }
+        })
+        .on_matching("getppid", |e| Decision::Redirect {
+            sysno: libc::SYS_getpid as u64,
+            args: e.args,
+        })
+        .without_remote_mem();
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("content: opened\n"), "{}", output);
+    assert!(output.contains("getppid: true\n"), "{}", output);
+    // both enter and exit are reported as the origin one
+    let events = events.borrow();
+    for name in ["open", "getppid"] {
+        let phases = events
+            .iter()
+            .filter(|(n, _)| *n == name)
+            .map(|(_, phase)| *phase)
+            .collect::<Vec<_>>();
+        assert_eq!(phases, [Phase::Enter, Phase::Exit], "{}", name);
+    }
+}
@@ -0,0 +1,31 @@
+use interceptor_rs::{as_errno, RegisterValue};
+
+#[test]
+fn register_value_conversions() {
+    // negative values are sign-extended
+    let r = RegisterValue::from_signed(-2);
+    assert_eq!(r.as_unsigned(), 0xffff_ffff_ffff_fffe);
+    assert_eq!(r.as_signed(), -2);
+    assert_eq!(r.as_i32(), -2);
+    assert_eq!(r.as_u32(), 0xffff_fffe);
+    assert_eq!(as_errno(r), Some(2));
+
+    // unsigned ones are zero-extended
+    let r = RegisterValue::from_unsigned(u32::MAX as u64);
+    assert_eq!(r.as_signed(), u32::MAX as i64);
+    assert_eq!(r.as_i32(), -1);
+    assert_eq!(as_errno(r), None);
+
+    // upper bits of an `int` argument are ignored
+    let r = RegisterValue::from_unsigned(0xdead_beef_0000_0007);
+    assert_eq!(r.as_i32(), 7);
+    assert_eq!(r.as_u32(), 7);
+
+    let p = 0x7fff_1234_5678usize;
+    assert_eq!(RegisterValue::from_ptr(p).as_ptr(), p);
+    assert_eq!(RegisterValue::from_ptr(p).as_unsigned(), p as u64);
+    // a large address is not an error
+    assert_eq!(as_errno(RegisterValue::from_ptr(usize::MAX - 5000)), None);
+    assert_eq!(as_errno(RegisterValue::from_signed(-4095)), Some(4095));
+    assert_eq!(as_errno(RegisterValue::from_signed(-4096)), None);
+}
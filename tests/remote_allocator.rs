@@ -0,0 +1,73 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{
+    pete::Tracee, syscall, with_context, ArgWrite, InterceptError, RemoteAllocator,
+};
+use std::{
+    cell::RefCell,
+    env,
+    ffi::{c_char, CStr, CString},
+    fs,
+    rc::Rc,
+};
+
+/// scratch space below the stack pointer of the tracee, which is not used while it's in
+/// a syscall, past the red zone
+#[derive(Default)]
+struct BelowStack {
+    used: usize,
+    calls: Rc<RefCell<(usize, usize)>>,
+}
+
+impl RemoteAllocator for BelowStack {
+    fn alloc(&mut self, tracee: &mut Tracee, len: usize) -> Result<u64, InterceptError> {
+        let rsp = tracee.registers().map_err(anyhow::Error::from)?.rsp;
+        self.used += len;
+        self.calls.borrow_mut().0 += 1;
+        Ok((rsp - 1024 - self.used as u64) & !7)
+    }
+
+    fn reset(&mut self) {
+        self.used = 0;
+        self.calls.borrow_mut().1 += 1;
+    }
+}
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let name = unsafe { CStr::from_ptr(filename) }.to_bytes();
+    if name.ends_with(b"/short") {
+        let long = format!("{}/{:x<200}", common::child_dir().display(), "long");
+        let long = CString::new(long).unwrap();
+        let write = with_context(|ctx| ctx.set_arg_string(1, &long)).unwrap();
+        assert_eq!(write, ArgWrite::Allocated);
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn custom_remote_allocator() {
+    if common::is_child() {
+        let path = common::child_dir().join("short");
+        println!("content: {}", fs::read_to_string(path).unwrap());
+        return;
+    }
+
+    let dir = common::test_dir("remote-allocator");
+    env::set_var(common::DIR, &dir);
+    fs::write(dir.join(format!("{:x<200}", "long")), "redirected").unwrap();
+
+    let allocator = BelowStack::default();
+    let calls = allocator.calls.clone();
+    let content = common::run_child_in("custom_remote_allocator", &dir, |i| {
+        i.remote_allocator(allocator).on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(content.contains("content: redirected"), "{}", content);
+    let (allocs, resets) = *calls.borrow();
+    assert_eq!(allocs, 1);
+    assert!(resets > allocs);
+}
@@ -0,0 +1,46 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, Interceptor};
+use std::{
+    env,
+    ffi::{c_char, CStr, CString},
+    fs, io,
+    time::Duration,
+};
+
+const PREFIX: &str = "interceptor-remote-mem-reset-";
+/// enough allocations to wrap around the remote memory block if never reset
+const FILES: usize = 80;
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let name = unsafe { CStr::from_ptr(filename) }.to_string_lossy();
+    if name.contains(PREFIX) {
+        // ~250 bytes for each call
+        let target = CString::new(format!("/nonexistent/{:x<240}", "long")).unwrap();
+        with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn remote_mem_reset_on_exit() {
+    if common::is_child() {
+        for i in 0..FILES {
+            let e = fs::read(env::temp_dir().join(format!("{}{}", PREFIX, i))).unwrap_err();
+            assert_eq!(e.kind(), io::ErrorKind::NotFound);
+        }
+        return;
+    }
+
+    common::install_preload();
+    Interceptor::new(common::child_command("remote_mem_reset_on_exit"))
+        .unwrap()
+        .remote_mem_guard(Duration::from_secs(60))
+        .on(&openat)
+        .run()
+        .unwrap();
+}
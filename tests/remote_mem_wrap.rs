@@ -0,0 +1,69 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, ArgWrite};
+use std::{
+    env,
+    ffi::{c_char, CStr, CString, OsStr},
+    fs,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::{Path, PathBuf},
+};
+
+/// enough allocations to wrap around the remote memory block a few times
+const FILES: usize = 80;
+
+fn long_name(i: usize) -> PathBuf {
+    // ~250 bytes, a few dozen of them exceed the block
+    common::child_dir().join(format!("{:x<240}-{}", "long", i))
+}
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = Path::new(OsStr::from_bytes(
+        unsafe { CStr::from_ptr(filename) }.to_bytes(),
+    ));
+    let short = file
+        .file_name()
+        .and_then(|n| n.to_str())
+        .and_then(|n| n.strip_prefix('s'))
+        .and_then(|n| n.parse::<usize>().ok());
+    if let Some(i) = short {
+        let target = CString::new(long_name(i).into_os_string().into_vec()).unwrap();
+        let write = with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+        assert_eq!(write, ArgWrite::Allocated);
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn remote_mem_allocations_wrap_around() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for i in 0..FILES {
+            match fs::read_to_string(dir.join(format!("s{}", i))) {
+                Ok(content) => println!("{}: {}", i, content),
+                Err(e) => println!("{}: {}", i, e),
+            }
+        }
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("remote-mem-wrap");
+    env::set_var(common::DIR, &dir);
+    for i in 0..FILES {
+        fs::write(long_name(i), i.to_string()).unwrap();
+    }
+
+    let content = common::run_child_in("remote_mem_allocations_wrap_around", &dir, |i| {
+        i.verify_writes(true).on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    for i in 0..FILES {
+        let expected = format!("{}: {}\n", i, i);
+        assert!(content.contains(&expected), "{}", content);
+    }
+}
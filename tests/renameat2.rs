@@ -0,0 +1,94 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, Phase};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString},
+    fs::{self},
+    mem::forget,
+    rc::Rc,
+};
+
+/// a long path to the same file, so two of them take most of the remote memory
+fn padded(name: &[u8]) -> CString {
+    let mut path = b"./".repeat(1500);
+    path.extend(name);
+    CString::new(path).unwrap()
+}
+
+fn rewrite(path: &mut *const c_char) {
+    let name = unsafe { CStr::from_ptr(*path) }.to_bytes();
+    if name == b"a" || name == b"b" {
+        let longer = padded(&[name, b"-longer"].concat());
+        *path = longer.as_ptr();
+        forget(longer);
+    }
+}
+
+#[syscall]
+fn renameat2(
+    olddfd: i32,
+    mut oldname: *const c_char,
+    newdfd: i32,
+    mut newname: *const c_char,
+    flags: u32,
+) -> i32 {
+    rewrite(&mut oldname);
+    rewrite(&mut newname);
+    real!(olddfd, oldname, newdfd, newname, flags)
+}
+
+#[test]
+fn renameat2_rewrites_both_paths() {
+    if common::is_child() {
+        // the second and third ones wrap around the remote memory
+        for (old, new) in [(c"a", c"b"), (c"b", c"a"), (c"a", c"b")] {
+            let ret = unsafe {
+                libc::syscall(
+                    libc::SYS_renameat2,
+                    libc::AT_FDCWD,
+                    old.as_ptr(),
+                    libc::AT_FDCWD,
+                    new.as_ptr(),
+                    0,
+                )
+            };
+            println!("renameat2: {}", ret);
+        }
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("renameat2");
+    fs::write(dir.join("a-longer"), "content").unwrap();
+
+    let mut cmd = common::child_command("renameat2_rewrites_both_paths");
+    cmd.current_dir(&dir);
+    let sent = Rc::new(RefCell::new(Vec::new()));
+    let output = common::capture(cmd, |i| {
+        let sent = sent.clone();
+        i.on_event(move |e| {
+            if e.name == "renameat2" && e.phase == Phase::Exit {
+                sent.borrow_mut().push((e.args[1], e.args[3]));
+            }
+        })
+        .on(&renameat2);
+    });
+    let renamed = fs::read_to_string(dir.join("b-longer"));
+    let origin_left = dir.join("a-longer").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    assert_eq!(output.matches("renameat2: 0\n").count(), 3, "{}", output);
+    assert_eq!(renamed.unwrap(), "content");
+    assert!(!origin_left);
+
+    // paths sent to kernel are in remote memory, they never overlap or exceed the block
+    let sent = sent.borrow();
+    let len = padded(b"a-longer").as_bytes_with_nul().len() as u64;
+    let base = sent.iter().flat_map(|(a, b)| [*a, *b]).min().unwrap();
+    for (a, b) in sent.iter() {
+        assert!(a.abs_diff(*b) >= len, "{:x} and {:x} overlap", a, b);
+        assert!(a.max(b) + len <= base + inter_mem::MEM_BLOCK_SIZE as u64);
+    }
+}
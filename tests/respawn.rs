@@ -0,0 +1,75 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, Interceptor};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr, CString, OsStr},
+    fs,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::Path,
+    rc::Rc,
+};
+
+#[syscall]
+fn mkdir(path: *const c_char, mode: u32) -> i32 {
+    // longer than the original one, so it's written into remote memory
+    let target = Path::new(OsStr::from_bytes(
+        unsafe { CStr::from_ptr(path) }.to_bytes(),
+    ))
+    .with_file_name("much-longer-target");
+    let target = CString::new(target.into_os_string().into_vec()).unwrap();
+    with_context(|ctx| ctx.set_arg_string(0, &target)).unwrap();
+    real!(path, mode)
+}
+
+#[test]
+fn respawn_keeps_handlers() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        let path = CString::new(dir.join("a").into_os_string().into_vec()).unwrap();
+        let r = unsafe { libc::mkdir(path.as_ptr(), 0o755) };
+        println!("mkdir: {}", r);
+        println!("ppid: {}", unsafe { libc::getppid() });
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("respawn");
+    let mut cmd = common::child_command("respawn_keeps_handlers");
+    cmd.env(common::DIR, &dir);
+
+    let roots = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    let output = interceptor.capture_output();
+    {
+        let roots = roots.clone();
+        interceptor.on_new_tracee(move |pid, parent| {
+            if parent.is_none() {
+                roots.borrow_mut().push(pid);
+            }
+            true
+        });
+    }
+    interceptor
+        .on(&mkdir)
+        .on_return_of("getppid", |_| 4242)
+        .run()
+        .unwrap();
+    assert!(dir.join("much-longer-target").exists());
+    fs::remove_dir(dir.join("much-longer-target")).unwrap();
+
+    interceptor.respawn().unwrap();
+    interceptor.run().unwrap();
+
+    let stdout = String::from_utf8(output.stdout()).unwrap();
+    let exists = dir.join("much-longer-target").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(exists);
+    assert_eq!(stdout.matches("mkdir: 0\n").count(), 2, "{}", stdout);
+    assert_eq!(stdout.matches("ppid: 4242\n").count(), 2, "{}", stdout);
+    let roots = roots.borrow();
+    assert_eq!(roots.len(), 2);
+    assert_ne!(roots[0], roots[1]);
+}
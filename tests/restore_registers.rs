@@ -0,0 +1,80 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, Outcome};
+use std::{
+    cell::RefCell,
+    ffi::{c_char, CStr},
+    fs,
+    rc::Rc,
+};
+
+/// every `rmdir` fails with `EPERM`, unless the handler backs it out
+#[syscall]
+fn rmdir(path: *const c_char) -> i32 {
+    let _ = real!(path);
+    with_context(|ctx| {
+        let path = ctx.read_memory(ctx.arg(0), libc::PATH_MAX as usize);
+        let path = CStr::from_bytes_until_nul(&path).unwrap();
+        if path.to_bytes().ends_with(b"keep") {
+            ctx.restore_registers().unwrap();
+        }
+    });
+    -libc::EPERM
+}
+
+#[test]
+fn restore_registers_backs_out_changes() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        for op in ["mkdir", "rmdir"] {
+            for name in ["keep", "deny"] {
+                let path = dir.join(name);
+                let ret = match op {
+                    "mkdir" => fs::create_dir(path),
+                    _ => fs::remove_dir(path),
+                };
+                match ret {
+                    Ok(_) => println!("{} {}: ok", op, name),
+                    Err(e) => println!("{} {}: {:?}", op, name, e.raw_os_error()),
+                }
+            }
+        }
+        return;
+    }
+
+    let dir = common::test_dir("restore-registers");
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child_in("restore_registers_backs_out_changes", &dir, |i| {
+        {
+            let seen = seen.clone();
+            // every `mkdir` is blocked, unless the handler backs it out
+            i.on_fn("mkdir", move |path: *const c_char, _mode: u32| {
+                let keep = unsafe { CStr::from_ptr(path) }
+                    .to_bytes()
+                    .ends_with(b"keep");
+                with_context(|ctx| {
+                    let regs = ctx.registers();
+                    seen.borrow_mut().push((regs.rdi == ctx.arg(0), regs.rsi));
+                    if keep {
+                        ctx.restore_registers().unwrap();
+                    }
+                });
+                Outcome::<i32, _>::Block(-libc::EACCES)
+            });
+        }
+        i.without_remote_mem().on(&rmdir);
+    });
+    let kept = dir.join("keep").exists();
+    fs::remove_dir_all(&dir).unwrap();
+    let expected = format!(
+        "mkdir keep: ok\nmkdir deny: Some({})\nrmdir keep: ok\nrmdir deny: Some({})\n",
+        libc::EACCES,
+        libc::EPERM
+    );
+    assert!(content.contains(&expected), "{}", content);
+    assert!(!kept);
+    assert_eq!(*seen.borrow(), [(true, 0o777), (true, 0o777)]);
+}
@@ -0,0 +1,58 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{as_errno, Decision, Phase};
+use std::{cell::RefCell, rc::Rc};
+
+const BAD_FD: i64 = 999;
+
+#[test]
+fn retry_failed_syscall() {
+    if common::is_child() {
+        let fd = unsafe { libc::syscall(libc::SYS_dup, BAD_FD) };
+        println!("dup: {}", fd > 2);
+        let fd = unsafe { libc::syscall(libc::SYS_dup2, BAD_FD, 100) };
+        println!(
+            "dup2: {:?}",
+            (fd, std::io::Error::last_os_error().raw_os_error())
+        );
+        return;
+    }
+
+    let entered = Rc::new(RefCell::new(Vec::new()));
+    let content = common::run_child("retry_failed_syscall", |i| {
+        {
+            let entered = entered.clone();
+            i.on_event(move |e| {
+                if e.phase == Phase::Enter && e.args[0] == BAD_FD as u64 {
+                    entered.borrow_mut().push((e.name, e.args[0]));
+                }
+            });
+        }
+        i.without_remote_mem()
+            .max_retries(3)
+            // use stdout instead of the bad fd
+            .on_matching("dup", |e| match (e.phase, e.ret.and_then(as_errno)) {
+                (Phase::Exit, Some(libc::EBADF)) => {
+                    Decision::Retry([Some(1), None, None, None, None, None])
+                }
+                _ => Decision::Continue,
+            })
+            // always fails, stopped by max retries
+            .on_matching("dup2", |e| match e.phase {
+                Phase::Exit => Decision::Retry([None; 6]),
+                Phase::Enter => Decision::Continue,
+            });
+    });
+    assert!(content.contains("dup: true\n"), "{}", content);
+    assert!(
+        content.contains(&format!("dup2: {:?}\n", (-1, Some(libc::EBADF)))),
+        "{}",
+        content
+    );
+    let entered = entered.borrow();
+    let count = |name| entered.iter().filter(|(n, _)| *n == name).count();
+    assert_eq!(count("dup"), 1, "{:?}", entered);
+    assert_eq!(count("dup2"), 4, "{:?}", entered);
+}
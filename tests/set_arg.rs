@@ -0,0 +1,49 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::{
+    env,
+    ffi::{c_char, CStr},
+    fs::{self, File},
+    os::unix::fs::PermissionsExt,
+};
+
+const PATH: &str = "INTERCEPTOR_TEST_PATH";
+
+#[test]
+fn set_flags_and_mode_in_handler() {
+    if common::is_child() {
+        let ret = File::open(env::var(PATH).unwrap());
+        println!("open: {:?}", ret.map(|_| ()).map_err(|e| e.raw_os_error()));
+        return;
+    }
+
+    let dir = common::test_dir("set-arg");
+    let path = dir.join("created");
+    let mut cmd = common::child_command("set_flags_and_mode_in_handler");
+    cmd.env(PATH, &path);
+
+    let content = common::capture(cmd, |i| {
+        // opening the file for reading creates it
+        i.on_fn("openat", |dfd: i32, name: *const c_char, flags: i32| {
+            if unsafe { CStr::from_ptr(name) }
+                .to_bytes()
+                .ends_with(b"created")
+            {
+                with_context(|ctx| {
+                    ctx.set_flags(flags | libc::O_CREAT).unwrap();
+                    ctx.set_mode(0o600).unwrap();
+                    assert!(ctx.set_arg(6, 0).is_err());
+                });
+            }
+            Outcome::<i32, _>::Real((dfd, name, flags))
+        })
+        .without_remote_mem();
+    });
+    let mode = fs::metadata(&path).map(|m| m.permissions().mode() & 0o777);
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(content.contains("open: Ok(())\n"), "{}", content);
+    assert_eq!(mode.unwrap(), 0o600);
+}
@@ -0,0 +1,60 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{syscall, with_context, ArgWrite};
+use parking_lot::Mutex;
+use std::{
+    ffi::{c_char, CStr, CString, OsStr},
+    fs,
+    os::unix::ffi::{OsStrExt, OsStringExt},
+    path::Path,
+};
+
+static WRITES: Mutex<Vec<ArgWrite>> = Mutex::new(Vec::new());
+
+#[syscall]
+fn openat(dfd: i32, filename: *const c_char, flags: i32, mode: i32) -> i32 {
+    let file = Path::new(OsStr::from_bytes(
+        unsafe { CStr::from_ptr(filename) }.to_bytes(),
+    ));
+    let target = match file.file_name().and_then(|n| n.to_str()) {
+        Some("same") => Some(file.with_file_name("xxxx")),
+        Some("short") => Some(file.with_file_name("much-longer-target")),
+        _ => None,
+    };
+    if let Some(target) = target {
+        let target = CString::new(target.into_os_string().into_vec()).unwrap();
+        let write = with_context(|ctx| ctx.set_arg_string(1, &target)).unwrap();
+        WRITES.lock().push(write);
+    }
+
+    real!(dfd, filename, flags, mode)
+}
+
+#[test]
+fn set_arg_string_in_place_or_allocated() {
+    if common::is_child() {
+        common::print_files(&["same", "short"]);
+        return;
+    }
+
+    common::install_preload();
+    let dir = common::test_dir("set-arg-string");
+    for (name, content) in [
+        ("same", "requested"),
+        ("xxxx", "in place"),
+        ("short", "requested"),
+        ("much-longer-target", "allocated"),
+    ] {
+        fs::write(dir.join(name), content).unwrap();
+    }
+
+    let output = common::run_child_in("set_arg_string_in_place_or_allocated", &dir, |i| {
+        i.verify_writes(true).on(&openat);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(output.contains("same: in place\n"), "{}", output);
+    assert!(output.contains("short: allocated\n"), "{}", output);
+    assert_eq!(*WRITES.lock(), [ArgWrite::InPlace, ArgWrite::Allocated]);
+}
@@ -0,0 +1,62 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use interceptor_rs::{syscall, with_context, Simulated, Simulator};
+use std::ffi::{c_char, CStr, CString};
+
+/// files are opened without `O_CREAT`, and the fd returned is shifted
+#[syscall]
+fn openat(dfd: i32, path: *const c_char, flags: i32, mode: u32) -> i32 {
+    assert_eq!(unsafe { CStr::from_ptr(path) }.to_bytes(), b"/tmp/file");
+    let ret = real!(dfd, path, flags & !libc::O_CREAT, mode);
+    assert_eq!(
+        with_context(|ctx| ctx.arg(2)) as i32 & libc::O_CREAT,
+        libc::O_CREAT
+    );
+    ret + 100
+}
+
+#[syscall]
+fn unlink(path: *const c_char) -> i32 {
+    let _ = path;
+    -libc::EPERM
+}
+
+#[test]
+fn run_handlers_without_tracee() {
+    let mut sim = Simulator::new().unwrap();
+    sim.on(&openat).on(&unlink);
+    sim.on_return_of("openat", |ret| ret * 2);
+
+    let path = CString::new("/tmp/file").unwrap();
+    let flags = (libc::O_RDWR | libc::O_CREAT) as u64;
+    let args = [
+        libc::AT_FDCWD as u64,
+        path.as_ptr() as u64,
+        flags,
+        0o644,
+        0,
+        0,
+    ];
+    assert_eq!(
+        sim.call("openat", args, 3).unwrap(),
+        Simulated::Returned {
+            args: [args[0], args[1], libc::O_RDWR as u64, 0o644, 0, 0],
+            // handlers after `real!()` run in reverse order
+            ret: 106,
+        }
+    );
+
+    let args = [path.as_ptr() as u64, 0, 0, 0, 0, 0];
+    assert_eq!(
+        sim.call("unlink", args, 0).unwrap(),
+        Simulated::Blocked(-libc::EPERM as u64)
+    );
+    assert_eq!(
+        sim.call("getpid", [0; 6], 42).unwrap(),
+        Simulated::Returned {
+            args: [0; 6],
+            ret: 42
+        }
+    );
+    assert!(sim.call("no_such_syscall", [0; 6], 0).is_err());
+}
@@ -0,0 +1,64 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Interceptor};
+use std::{arch::asm, cell::RefCell, process::Stdio, rc::Rc};
+
+/// marks the `getppid` to step after in `rdi`, which it does not take
+const MARK: u64 = 0x5354_4550;
+const STEPS: usize = 4;
+
+#[test]
+fn single_step_after_syscall() {
+    if common::is_child() {
+        let ret: u64;
+        // one-byte `nop`s, so each step advances `rip` by one
+        unsafe {
+            asm!(
+                "syscall",
+                "nop",
+                "nop",
+                "nop",
+                "nop",
+                "nop",
+                "nop",
+                inlateout("rax") libc::SYS_getppid as u64 => ret,
+                in("rdi") MARK,
+                lateout("rcx") _,
+                lateout("r11") _,
+            )
+        };
+        assert!(ret > 0);
+        return;
+    }
+
+    let pc = Rc::new(RefCell::new(None));
+    let steps = Rc::new(RefCell::new(Vec::new()));
+    let mut cmd = common::child_command("single_step_after_syscall");
+    cmd.stdout(Stdio::null());
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let pc = pc.clone();
+        interceptor.on_return_of("getppid", move |ret| {
+            with_context(|ctx| {
+                if ctx.registers().rdi == MARK {
+                    *pc.borrow_mut() = Some(ctx.pc());
+                    ctx.single_step(STEPS);
+                }
+            });
+            ret
+        });
+    }
+    {
+        let steps = steps.clone();
+        interceptor.on_step(move |e| steps.borrow_mut().push((e.remaining, e.regs.rip)));
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    let pc = pc.borrow().expect("marked getppid not seen");
+    let expected = (0..STEPS)
+        .map(|i| (STEPS - i - 1, pc + i as u64 + 1))
+        .collect::<Vec<_>>();
+    assert_eq!(*steps.borrow(), expected);
+}
@@ -0,0 +1,77 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{
+    as_errno,
+    flags::{Domain, SockType},
+    Interceptor, Outcome, Phase,
+};
+use std::{cell::RefCell, rc::Rc};
+
+#[test]
+fn socket_blocked_by_type() {
+    if common::is_child() {
+        for (domain, ty) in [
+            (libc::AF_INET, libc::SOCK_RAW),
+            (libc::AF_UNIX, libc::SOCK_DGRAM | libc::SOCK_CLOEXEC),
+        ] {
+            let fd = unsafe { libc::socket(domain, ty, 0) };
+            assert!(fd >= 0 || domain == libc::AF_INET);
+        }
+        return;
+    }
+
+    let cmd = common::child_command("socket_blocked_by_type");
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let exits = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let seen = seen.clone();
+        interceptor.on_fn(
+            "socket",
+            move |domain: Domain, ty: SockType, protocol: i32| {
+                seen.borrow_mut().push((domain, ty.kind(), ty));
+                if ty.kind() == libc::SOCK_RAW {
+                    Outcome::Block(-libc::EAFNOSUPPORT)
+                } else {
+                    Outcome::Real((domain, ty, protocol))
+                }
+            },
+        );
+    }
+    {
+        let exits = exits.clone();
+        interceptor.on_event(move |e| {
+            if e.phase == Phase::Exit {
+                exits.borrow_mut().push((e.name, e.ret.unwrap()));
+            }
+        });
+    }
+    interceptor.without_remote_mem().run().unwrap();
+
+    assert_eq!(
+        *seen.borrow(),
+        [
+            (
+                Domain::AF_INET,
+                libc::SOCK_RAW,
+                SockType::from_bits_retain(libc::SOCK_RAW)
+            ),
+            (
+                Domain::AF_UNIX,
+                libc::SOCK_DGRAM,
+                SockType::SOCK_CLOEXEC | SockType::from_bits_retain(libc::SOCK_DGRAM)
+            ),
+        ]
+    );
+    // the blocked one is reported as `socket`, not the fake syscall replacing it
+    let sockets = exits
+        .borrow()
+        .iter()
+        .filter(|(name, _)| *name == "socket")
+        .map(|(_, ret)| as_errno(*ret))
+        .collect::<Vec<_>>();
+    assert_eq!(sockets, [Some(libc::EAFNOSUPPORT), None]);
+    assert!(exits.borrow().iter().all(|(name, _)| *name != "unknown"));
+}
@@ -0,0 +1,74 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64", feature = "statx"))]
+
+mod common;
+
+use interceptor_rs::{flags::StatxMask, syscall, with_context, Statx};
+use std::{
+    ffi::{c_char, CStr, CString},
+    fs,
+    mem::MaybeUninit,
+};
+
+const NAME: &str = "interceptor-statx";
+const FAKE_SIZE: u64 = 12345;
+
+#[syscall]
+fn statx(dfd: i32, path: *const c_char, flags: i32, mask: StatxMask, buf: u64) -> i32 {
+    let ret = real!(dfd, path, flags, mask, buf);
+    if ret != 0 {
+        return ret;
+    }
+
+    // arguments are read from the context after `real!()`
+    with_context(|ctx| {
+        let path = ctx.read_memory(ctx.arg(1), libc::PATH_MAX as usize);
+        let path = CStr::from_bytes_until_nul(&path).unwrap_or_default();
+        let mask = StatxMask::from_bits_retain(ctx.arg(3) as u32);
+        // only the size requested by the caller is faked
+        if path.to_bytes().ends_with(NAME.as_bytes()) && mask.contains(StatxMask::STATX_SIZE) {
+            let mut st = Statx::read_arg(ctx, 4);
+            st.size = FAKE_SIZE;
+            st.mask |= StatxMask::STATX_SIZE.bits();
+            st.store_arg(ctx, 4).unwrap();
+        }
+    });
+    ret
+}
+
+#[test]
+fn statx_fake_size() {
+    if common::is_child() {
+        let file = format!("{}/{}", common::child_dir().display(), NAME);
+        // by `statx` requesting all basic fields
+        println!("metadata: {}", fs::metadata(&file).unwrap().len());
+
+        let path = CString::new(file).unwrap();
+        let mut st = MaybeUninit::<libc::statx>::zeroed();
+        let r = unsafe {
+            libc::statx(
+                libc::AT_FDCWD,
+                path.as_ptr(),
+                0,
+                libc::STATX_MODE,
+                st.as_mut_ptr(),
+            )
+        };
+        let st = unsafe { st.assume_init() };
+        println!("mode only: {} {}", r, st.stx_size);
+        return;
+    }
+
+    let dir = common::test_dir("statx");
+    fs::write(dir.join(NAME), "content").unwrap();
+
+    let content = common::run_child_in("statx_fake_size", &dir, |i| {
+        i.without_remote_mem().on(&statx);
+    });
+    fs::remove_dir_all(&dir).unwrap();
+    assert!(
+        content.contains(&format!("metadata: {}\n", FAKE_SIZE)),
+        "{}",
+        content
+    );
+    assert!(content.contains("mode only: 0 7\n"), "{}", content);
+}
@@ -0,0 +1,28 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::syscall::{SysCall, SysCall1, SysCall2};
+use std::io::Error;
+
+static UMASK: SysCall1<u32, u32> = SysCall::new1("umask", |mask| (mask | 0o077,), |r| r);
+static KILL: SysCall2<i32, i32, i32> = SysCall::block2("kill", |_, _| -libc::EPERM);
+
+#[test]
+fn hand_written_syscalls() {
+    if common::is_child() {
+        unsafe { libc::umask(0o022) };
+        println!("umask: {:o}", unsafe { libc::umask(0o022) });
+        if unsafe { libc::kill(libc::getpid(), 0) } < 0 {
+            println!("kill: {:?}", Error::last_os_error().raw_os_error());
+        }
+        return;
+    }
+
+    let content = common::run_child("hand_written_syscalls", |i| {
+        i.without_remote_mem().on(&UMASK).on(&KILL);
+    });
+    assert!(content.contains("umask: 77\n"), "{}", content);
+    let expected = format!("kill: Some({})\n", libc::EPERM);
+    assert!(content.contains(&expected), "{}", content);
+}
@@ -0,0 +1,17 @@
+use interceptor_rs::syscall_number;
+
+#[test]
+fn syscall_numbers_of_target() {
+    // the table of the build target is used, bundled ones are tested in `lib.rs`
+    assert_eq!(syscall_number("openat"), Some(libc::SYS_openat as u64));
+    assert_eq!(syscall_number("openatt"), None);
+    #[cfg(target_arch = "x86_64")]
+    assert_eq!(syscall_number("openat"), Some(257));
+    // not the x32 variants sharing names, numbered from 512
+    #[cfg(target_arch = "x86_64")]
+    {
+        assert_eq!(syscall_number("ioctl"), Some(16));
+        assert_eq!(syscall_number("execve"), Some(59));
+        assert_eq!(syscall_number("readv"), Some(19));
+    }
+}
@@ -0,0 +1,21 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use interceptor_rs::{InterceptError, Interceptor};
+use std::{process::Command, time::Duration};
+
+#[test]
+fn run_with_timeout_error() {
+    let mut cmd = Command::new("sleep");
+    cmd.arg("5");
+    let dur = Duration::from_millis(200);
+    let err = Interceptor::new(cmd)
+        .unwrap()
+        .without_remote_mem()
+        .run_with_timeout(dur)
+        .unwrap_err();
+    assert!(
+        matches!(err, InterceptError::TimedOut(ref t) if t.0 == dur),
+        "unexpected error: {:?}",
+        err
+    );
+}
@@ -0,0 +1,52 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::Interceptor;
+use std::{env, fs};
+
+const MISSING: &str = "INTERCEPTOR_TEST_MISSING";
+
+#[test]
+fn write_strace_like_lines() {
+    if common::is_child() {
+        let _ = fs::read(env::var(MISSING).unwrap());
+        return;
+    }
+
+    let dir = common::test_dir("trace-to");
+    let (missing, trace) = (dir.join("missing"), dir.join("trace"));
+    let mut cmd = common::child_command("write_strace_like_lines");
+    cmd.env(MISSING, &missing);
+    Interceptor::new(cmd)
+        .unwrap()
+        .without_remote_mem()
+        .trace_to(&trace)
+        .unwrap()
+        .run()
+        .unwrap();
+
+    let content = fs::read_to_string(&trace).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    let open = content
+        .lines()
+        .find(|l| l.contains(&format!("openat(AT_FDCWD, {:?}, ", missing)))
+        .unwrap_or_else(|| panic!("{}", content));
+    assert!(
+        open.ends_with(") = -2 (No such file or directory)"),
+        "{}",
+        open
+    );
+    // tgid and pid
+    let ids: Vec<_> = open.splitn(3, ' ').take(2).collect();
+    assert!(ids.iter().all(|id| id.parse::<i32>().is_ok()), "{}", open);
+    assert!(
+        content
+            .lines()
+            .last()
+            .unwrap()
+            .ends_with("exit_group(0) = ?"),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,40 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::process;
+
+#[test]
+fn survive_tracee_killed_in_handler() {
+    if common::is_child() {
+        let pid = match unsafe { libc::fork() } {
+            0 => unsafe { libc::_exit(libc::getsid(0)) },
+            pid => pid,
+        };
+        let mut status = 0;
+        unsafe { libc::waitpid(pid, &mut status, 0) };
+        println!(
+            "killed: {}",
+            libc::WIFSIGNALED(status) && libc::WTERMSIG(status) == libc::SIGKILL
+        );
+        println!("ppid: {}", unsafe { libc::getppid() });
+        return;
+    }
+
+    let content = common::run_child("survive_tracee_killed_in_handler", |i| {
+        // the caller is killed before the syscall is blocked
+        i.on_fn("getsid", |_pid: i32| {
+            let pid = with_context(|ctx| ctx.pid());
+            unsafe { libc::kill(pid, libc::SIGKILL) };
+            Outcome::Block(0)
+        });
+        i.without_remote_mem();
+    });
+    assert!(content.contains("killed: true\n"), "{}", content);
+    assert!(
+        content.contains(&format!("ppid: {}\n", process::id())),
+        "{}",
+        content
+    );
+}
@@ -0,0 +1,63 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Interceptor, Outcome, PathOp, PathSyscallCtx};
+use std::{
+    cell::RefCell,
+    env,
+    fs::{self, File},
+    rc::Rc,
+    thread,
+};
+
+#[test]
+fn resolve_paths_by_cached_cwd() {
+    if common::is_child() {
+        let dir = common::child_dir();
+        env::set_current_dir(&dir).unwrap();
+        File::create("a").unwrap();
+        env::set_current_dir("sub").unwrap();
+        fs::metadata("../a").unwrap();
+        // changed by another thread
+        thread::spawn(move || env::set_current_dir(dir).unwrap())
+            .join()
+            .unwrap();
+        File::create("b").unwrap();
+        return;
+    }
+
+    let dir = common::test_dir("track-cwd");
+    fs::create_dir(dir.join("sub")).unwrap();
+    let mut cmd = common::child_command("resolve_paths_by_cached_cwd");
+    cmd.env(common::DIR, &dir);
+
+    let seen = Rc::new(RefCell::new(Vec::new()));
+    let mut interceptor = Interceptor::new(cmd).unwrap();
+    {
+        let (seen, dir) = (seen.clone(), dir.clone());
+        interceptor.on_path_syscalls(move |p: &PathSyscallCtx| {
+            let cwd = with_context(|ctx| ctx.cwd()).unwrap();
+            if p.resolved_path.starts_with(&dir) && matches!(p.op, PathOp::Open | PathOp::Stat) {
+                seen.borrow_mut().push((p.resolved_path.clone(), cwd));
+            }
+            Outcome::Real(())
+        });
+    }
+    interceptor
+        .without_remote_mem()
+        .track_cwd(true)
+        .run()
+        .unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+    let sub = dir.join("sub");
+    assert_eq!(
+        *seen.borrow(),
+        [
+            (dir.join("a"), dir.clone()),
+            (dir.join("a"), sub),
+            (dir.join("b"), dir.clone())
+        ]
+    );
+}
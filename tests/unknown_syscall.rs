@@ -0,0 +1,72 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+use interceptor_rs::{Interceptor, Outcome};
+use std::{
+    fmt::Debug,
+    process::Command,
+    sync::{Arc, Mutex},
+};
+use tracing::{
+    field::{Field, Visit},
+    span, Event, Level, Metadata, Subscriber,
+};
+
+/// collects messages of warnings
+#[derive(Clone, Default)]
+struct Warnings(Arc<Mutex<Vec<String>>>);
+
+impl Visit for Warnings {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0.lock().unwrap().push(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Warnings {
+    fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+        *metadata.level() == Level::WARN
+    }
+
+    fn new_span(&self, _: &span::Attributes<'_>) -> span::Id {
+        span::Id::from_u64(1)
+    }
+
+    fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        event.record(&mut self.clone());
+    }
+
+    fn enter(&self, _: &span::Id) {}
+
+    fn exit(&self, _: &span::Id) {}
+}
+
+#[test]
+fn unknown_syscall_warned() {
+    let warnings = Warnings::default();
+    tracing::subscriber::with_default(warnings.clone(), || {
+        let mut interceptor = Interceptor::new(Command::new("true")).unwrap();
+        interceptor
+            .on_fn("openatt", |dfd: i32| Outcome::<i32, _>::Real((dfd,)))
+            .on_return_of("getpid", |r| r)
+            // built on the table, nothing unknown is registered
+            .on_fd_events(|_| {})
+            .on_path_syscalls(|_| Outcome::Real(()))
+            .on_privilege_change(|_| Outcome::Real(()))
+            .ignore_seccomp();
+        assert!(interceptor.registered().contains(&"openatt"));
+    });
+
+    let warnings = warnings.0.lock().unwrap();
+    let unknown = warnings
+        .iter()
+        .filter(|w| w.contains("not a known syscall"))
+        .collect::<Vec<_>>();
+    assert_eq!(unknown.len(), 1, "{:?}", warnings);
+    assert!(unknown[0].starts_with("openatt "), "{:?}", unknown);
+    assert!(unknown[0].contains("\"openat\""), "{:?}", unknown);
+}
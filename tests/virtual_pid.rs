@@ -0,0 +1,61 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{
+    syscall,
+    syscall::{SysCall, SysCall0},
+    Outcome,
+};
+use std::arch::asm;
+
+static GETPID: SysCall0<i32> = SysCall::block0("getpid", || 1);
+
+#[syscall]
+fn getppid() -> i32 {
+    0
+}
+
+/// make syscall `sysno` with `rdi`, returns the return value and `rdi` after it
+fn syscall_keeping_rdi(sysno: i64, rdi: u64) -> (i64, u64) {
+    let (ret, after): (i64, u64);
+    unsafe {
+        asm!(
+            "syscall",
+            inlateout("rax") sysno => ret,
+            inlateout("rdi") rdi => after,
+            lateout("rcx") _,
+            lateout("r11") _,
+        );
+    }
+    (ret, after)
+}
+
+#[test]
+fn virtual_pid() {
+    if common::is_child() {
+        for _ in 0..2 {
+            println!("getpid: {}", unsafe { libc::getpid() });
+        }
+        println!("getppid: {}", unsafe { libc::getppid() });
+        println!("gettid: {}", unsafe { libc::gettid() });
+        let (ret, rdi) = syscall_keeping_rdi(libc::SYS_getpid, 0x1234);
+        println!("raw getpid: {} 0x{:x}", ret, rdi);
+        // not blocked, so nothing of the blocked ones is left to it
+        let (ret, _) = syscall_keeping_rdi(libc::SYS_getuid, 0);
+        println!("getuid: {}", ret == unsafe { libc::getuid() } as i64);
+        return;
+    }
+
+    let content = common::run_child("virtual_pid", |i| {
+        i.without_remote_mem()
+            .on(&GETPID)
+            .on(&getppid)
+            .on_fn("gettid", || Outcome::<i32, ()>::Block(1));
+    });
+    assert_eq!(content.matches("getpid: 1\n").count(), 2, "{}", content);
+    assert!(content.contains("getppid: 0\n"), "{}", content);
+    assert!(content.contains("gettid: 1\n"), "{}", content);
+    assert!(content.contains("raw getpid: 1 0x1234\n"), "{}", content);
+    assert!(content.contains("getuid: true\n"), "{}", content);
+}
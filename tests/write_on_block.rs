@@ -0,0 +1,44 @@
+#![cfg(all(target_os = "linux", target_arch = "x86_64"))]
+
+mod common;
+
+use interceptor_rs::{with_context, Outcome};
+use std::{
+    ffi::CStr,
+    mem::{size_of, zeroed},
+};
+
+/// size of each field of `struct utsname`
+const FIELD: usize = 65;
+
+#[test]
+fn fake_uname_output() {
+    if common::is_child() {
+        let mut uts: libc::utsname = unsafe { zeroed() };
+        let ret = unsafe { libc::uname(&mut uts) };
+        let field = |f: &[libc::c_char]| {
+            unsafe { CStr::from_ptr(f.as_ptr()) }
+                .to_string_lossy()
+                .into_owned()
+        };
+        println!(
+            "uname: {} {} {}",
+            ret,
+            field(&uts.sysname),
+            field(&uts.release)
+        );
+        return;
+    }
+
+    let content = common::run_child("fake_uname_output", |i| {
+        i.on_fn("uname", |buf: u64| {
+            let mut uts = vec![0; size_of::<libc::utsname>()];
+            uts[..6].copy_from_slice(b"FakeOS");
+            uts[FIELD * 2..FIELD * 2 + 5].copy_from_slice(b"9.9.9");
+            with_context(|ctx| ctx.write_on_block(buf, &uts)).unwrap();
+            Outcome::<i32, _>::Block(0)
+        });
+        i.without_remote_mem();
+    });
+    assert!(content.contains("uname: 0 FakeOS 9.9.9\n"), "{}", content);
+}